@@ -0,0 +1,136 @@
+// acronym / initialism detection
+
+/// Function words that initialisms commonly skip ("Bank of America" -> "BOA"
+/// or "BA").
+const STOPWORDS: &[&str] = &["a", "an", "and", "for", "in", "of", "on", "the", "to", "&"];
+
+/// First letter (lowercased) of every hyphen- or whitespace-separated token,
+/// optionally skipping stopwords.
+fn initials(s: &str, skip_stopwords: bool) -> String {
+    s.split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|token| !token.is_empty())
+        .filter(|token| !(skip_stopwords && STOPWORDS.contains(&token.to_lowercase().as_str())))
+        .filter_map(|token| token.chars().find(|c| c.is_alphanumeric()))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// How plausibly `short` is an acronym/initialism of `long`, in `[0, 1]`.
+///
+/// `short` is compacted to its lowercased alphanumeric characters ("I.B.M."
+/// and "I B M" both become "ibm") and compared with normalized
+/// Damerau-Levenshtein against the initials of `long`, both with and without
+/// stopwords; the better of the two is kept so an occasional dropped, extra
+/// or swapped letter is tolerated. `long` needs at least two tokens and the
+/// acronym at least two characters, otherwise the score is `0.0`.
+fn acronym_score(short: &str, long: &str) -> f64 {
+    let acronym: String = short
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if acronym.chars().count() < 2 {
+        return 0.0;
+    }
+
+    let all_initials = initials(long, false);
+    if all_initials.chars().count() < 2 {
+        return 0.0;
+    }
+    let content_initials = initials(long, true);
+
+    strsim::normalized_damerau_levenshtein(&acronym, &all_initials).max(
+        strsim::normalized_damerau_levenshtein(&acronym, &content_initials),
+    )
+}
+
+/// Acronym similarity of two strings: the shorter one is treated as the
+/// candidate acronym of the longer one (see `acronym_score`).
+pub fn acronym_similarity(a: &str, b: &str) -> f64 {
+    if a.chars().count() <= b.chars().count() {
+        acronym_score(a, b)
+    } else {
+        acronym_score(b, a)
+    }
+}
+
+/// How plausibly `abbreviation` abbreviates `long`, in `[0, 1]`, e.g. "Intl
+/// Bus Mach" for "International Business Machines" or "Dept of Med" for
+/// "Department of Medicine".
+///
+/// `abbreviation` is cut into segments that each abbreviate one word of
+/// `long`, in order: a segment starts with the word's first letter and
+/// continues with a subsequence of the rest of the word ("intl" in
+/// "international"), and never spans a word boundary of `abbreviation`.
+/// Words of `long` may be skipped. Comparison is on lowercased
+/// alphanumeric characters. The score is the largest fraction of the words
+/// of `long` that such a segmentation covers, where stopwords do not count
+/// unless `long` has nothing else. `0.0` when no segmentation consumes all
+/// of `abbreviation`.
+pub fn abbreviation_similarity(abbreviation: &str, long: &str) -> f64 {
+    let words: Vec<Vec<char>> = long
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.chars().flat_map(char::to_lowercase).collect())
+        .collect();
+    let mut chars = Vec::new();
+    // Whether each character starts a word of `abbreviation`.
+    let mut word_starts = Vec::new();
+    for word in abbreviation
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        for (i, c) in word.chars().flat_map(char::to_lowercase).enumerate() {
+            chars.push(c);
+            word_starts.push(i == 0);
+        }
+    }
+    if chars.is_empty() || words.is_empty() {
+        return 0.0;
+    }
+
+    let is_stopword = |word: &[char]| {
+        let word: String = word.iter().collect();
+        STOPWORDS.contains(&word.as_str())
+    };
+    let content_words = words.iter().filter(|word| !is_stopword(word)).count();
+    let gains: Vec<usize> = words
+        .iter()
+        .map(|word| usize::from(content_words == 0 || !is_stopword(word)))
+        .collect();
+
+    // covered[i][j]: the most words gained by a segmentation of the first
+    // `i` characters over the first `j` words, `None` if there is none.
+    let (n, m) = (chars.len(), words.len());
+    let mut covered = vec![vec![None; m + 1]; n + 1];
+    covered[0][0] = Some(0);
+    for j in 0..m {
+        let word = &words[j];
+        for i in 0..=n {
+            let Some(gain) = covered[i][j] else {
+                continue;
+            };
+            let skip = &mut covered[i][j + 1];
+            *skip = (*skip).max(Some(gain));
+            if i == n || chars[i] != word[0] {
+                continue;
+            }
+            // The longest segment from `i`: greedy matching finds the
+            // longest prefix embeddable in the rest of the word, and every
+            // shorter prefix is embeddable too.
+            let mut end = i + 1;
+            let mut rest = word[1..].iter();
+            while end < n && !word_starts[end] && rest.any(|&c| c == chars[end]) {
+                end += 1;
+            }
+            for row in &mut covered[i + 1..=end] {
+                row[j + 1] = row[j + 1].max(Some(gain + gains[j]));
+            }
+        }
+    }
+
+    match covered[n][m] {
+        Some(gain) => gain as f64 / gains.iter().sum::<usize>() as f64,
+        None => 0.0,
+    }
+}
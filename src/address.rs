@@ -0,0 +1,90 @@
+// postal-address normalization and matching
+
+use crate::weighted_DL;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Street-type, directional and unit-designator abbreviations with their
+/// expansion, sorted by abbreviation. Every unit designator ("Apt",
+/// "Suite", "#", ...) expands to "unit" so "Apt 4" and "#4" agree.
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("aly", "alley"),
+    ("apartment", "unit"),
+    ("apt", "unit"),
+    ("av", "avenue"),
+    ("ave", "avenue"),
+    ("bldg", "building"),
+    ("blvd", "boulevard"),
+    ("cir", "circle"),
+    ("ct", "court"),
+    ("dr", "drive"),
+    ("e", "east"),
+    ("expy", "expressway"),
+    ("fl", "floor"),
+    ("hwy", "highway"),
+    ("ln", "lane"),
+    ("n", "north"),
+    ("ne", "northeast"),
+    ("nw", "northwest"),
+    ("pkwy", "parkway"),
+    ("pl", "place"),
+    ("rd", "road"),
+    ("s", "south"),
+    ("se", "southeast"),
+    ("sq", "square"),
+    ("st", "street"),
+    ("ste", "unit"),
+    ("suite", "unit"),
+    ("sw", "southwest"),
+    ("ter", "terrace"),
+    ("w", "west"),
+];
+
+/// The lowercased, accent-free tokens of `s` with abbreviations expanded
+/// (see `ABBREVIATIONS`). Periods are dropped, "#" is read as a unit
+/// designator and other punctuation separates tokens.
+fn address_tokens(s: &str) -> Vec<String> {
+    let folded: String = s
+        .nfkd()
+        .filter(|&c| !is_combining_mark(c) && c != '.')
+        .flat_map(|c| match c {
+            '#' => " unit ".chars().collect::<Vec<_>>(),
+            _ => c.to_lowercase().collect(),
+        })
+        .collect();
+    folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            ABBREVIATIONS
+                .binary_search_by_key(&token, |&(abbreviation, _)| abbreviation)
+                .map_or(token, |i| ABBREVIATIONS[i].1)
+                .to_string()
+        })
+        .collect()
+}
+
+/// Similarity of two address tokens. Tokens with a digit (house, unit and
+/// postal numbers) only match exactly, since "12" and "21" are different
+/// places; other tokens score their normalized Damerau-Levenshtein
+/// similarity, tolerating typos in street names.
+fn token_similarity(a: &str, b: &str) -> f64 {
+    let has_digit = |token: &str| token.chars().any(|c| c.is_ascii_digit());
+    if has_digit(a) || has_digit(b) {
+        return if a == b { 1.0 } else { 0.0 };
+    }
+    strsim::normalized_damerau_levenshtein(a, b)
+}
+
+/// Similarity of two postal addresses in `[0, 1]`: the
+/// `weighted_DL::assigned_token_similarity` of their `address_tokens`, so
+/// token order does not matter and "Apt 4, 12 Main St" matches
+/// "12 Main Street #4".
+pub fn address_similarity(a: &str, b: &str, unmatched_token_weight: f64) -> f64 {
+    weighted_DL::assigned_token_similarity(
+        &address_tokens(a),
+        &address_tokens(b),
+        token_similarity,
+        unmatched_token_weight,
+    )
+}
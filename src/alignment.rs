@@ -0,0 +1,126 @@
+// Damerau-Levenshtein alignment
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditOp {
+    Match,
+    Substitute,
+    Delete,
+    Insert,
+    Transpose,
+}
+
+impl EditOp {
+    pub fn name(self) -> &'static str {
+        match self {
+            EditOp::Match => "match",
+            EditOp::Substitute => "substitute",
+            EditOp::Delete => "delete",
+            EditOp::Insert => "insert",
+            EditOp::Transpose => "transpose",
+        }
+    }
+}
+
+/// One column of an alignment. `pos_a` and `pos_b` are the positions of the
+/// elements it consumes; a deletion consumes nothing from `b` and an
+/// insertion nothing from `a`, so their other position is the one the next
+/// step will consume.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub op: EditOp,
+    pub pos_a: usize,
+    pub pos_b: usize,
+}
+
+/// An optimal unrestricted Damerau-Levenshtein (Lowrance-Wagner) alignment
+/// of `a_elems` against `b_elems`, in order. A transposition is a pair of
+/// `Transpose` steps (the swapped elements of `a` against those of `b`)
+/// around the deletions and insertions of the elements between them, so it
+/// counts as one edit plus those. Ties prefer matches and substitutions,
+/// then transpositions, deletions and insertions.
+pub fn damerau_levenshtein_alignment<Elem>(a_elems: &[Elem], b_elems: &[Elem]) -> Vec<Step>
+where
+    Elem: Eq + Hash + Clone,
+{
+    let a_len = a_elems.len();
+    let b_len = b_elems.len();
+    let width = a_len + 2;
+    let at = |i: usize, j: usize| j * width + i;
+
+    let mut distances = vec![0_usize; (a_len + 2) * (b_len + 2)];
+    let max_distance = a_len + b_len;
+    distances[0] = max_distance;
+    for i in 0..=a_len {
+        distances[at(i + 1, 0)] = max_distance;
+        distances[at(i + 1, 1)] = i;
+    }
+    for j in 0..=b_len {
+        distances[at(0, j + 1)] = max_distance;
+        distances[at(1, j + 1)] = j;
+    }
+
+    let mut elems: HashMap<Elem, usize> = HashMap::with_capacity(64);
+    for i in 1..=a_len {
+        let mut db = 0;
+        for j in 1..=b_len {
+            let k = *elems.get(&b_elems[j - 1]).unwrap_or(&0);
+            let is_match = a_elems[i - 1] == b_elems[j - 1];
+            distances[at(i + 1, j + 1)] = (distances[at(i, j)] + usize::from(!is_match))
+                .min(distances[at(i, j + 1)] + 1)
+                .min(distances[at(i + 1, j)] + 1)
+                .min(distances[at(k, db)] + (i - k - 1) + 1 + (j - db - 1));
+            if is_match {
+                db = j;
+            }
+        }
+        elems.insert(a_elems[i - 1].clone(), i);
+    }
+
+    let mut steps = Vec::with_capacity(a_len.max(b_len));
+    let step = |op, pos_a, pos_b| Step { op, pos_a, pos_b };
+    let (mut i, mut j) = (a_len, b_len);
+    while i > 0 || j > 0 {
+        let distance = distances[at(i + 1, j + 1)];
+        if i > 0 && j > 0 {
+            let is_match = a_elems[i - 1] == b_elems[j - 1];
+            if distance == distances[at(i, j)] + usize::from(!is_match) {
+                let op = if is_match {
+                    EditOp::Match
+                } else {
+                    EditOp::Substitute
+                };
+                steps.push(step(op, i - 1, j - 1));
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+            // The swapped pair is the last earlier `a` element equal to
+            // `b[j - 1]` and the last earlier `b` element equal to `a[i - 1]`.
+            let k = (1..i).rev().find(|&k| a_elems[k - 1] == b_elems[j - 1]);
+            let l = (1..j).rev().find(|&l| b_elems[l - 1] == a_elems[i - 1]);
+            if let (Some(k), Some(l)) = (k, l) {
+                if distance == distances[at(k, l)] + (i - k - 1) + 1 + (j - l - 1) {
+                    steps.push(step(EditOp::Transpose, i - 1, j - 1));
+                    steps.extend((l..j - 1).rev().map(|jj| step(EditOp::Insert, i - 1, jj)));
+                    steps.extend((k..i - 1).rev().map(|ii| step(EditOp::Delete, ii, l)));
+                    steps.push(step(EditOp::Transpose, k - 1, l - 1));
+                    i = k - 1;
+                    j = l - 1;
+                    continue;
+                }
+            }
+        }
+        if i > 0 && distance == distances[at(i, j + 1)] + 1 {
+            steps.push(step(EditOp::Delete, i - 1, j));
+            i -= 1;
+        } else {
+            steps.push(step(EditOp::Insert, i, j - 1));
+            j -= 1;
+        }
+    }
+    steps.reverse();
+    steps
+}
@@ -1,7 +1,233 @@
+use crate::deadline;
+use crate::ngram;
+use polars::chunked_array::builder::get_list_builder;
 use polars::prelude::*;
 use pyo3_polars::derive::CallerContext;
 use pyo3_polars::export::polars_core::POOL;
 use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Environment variable that, when set to a positive integer before the first
+/// expression runs, moves the plugin's work off Polars' global `POOL` onto a
+/// dedicated rayon pool of that size.
+pub const NUM_THREADS_ENV: &str = "STRSIM_POLARS_PLUGIN_NUM_THREADS";
+
+/// Fewest rows worth handing to a pool thread. Inputs are whatever Polars
+/// passes in (a whole column, a group or a streaming morsel), so small ones
+/// are scored on the calling thread rather than split further.
+const MIN_SPLIT_LEN: usize = 64;
+
+/// Execution options shared by the string-pair expressions, flattened into
+/// their kwargs structs next to the preprocessing options.
+#[derive(Deserialize, Default, Clone)]
+pub struct ApplyKwargs {
+    /// Time budget per row; rows that exceed it yield `timeout_value`
+    /// (null when unset) instead of stalling their chunk.
+    #[serde(default)]
+    per_row_timeout_ms: Option<u64>,
+    #[serde(default)]
+    timeout_value: Option<f64>,
+    #[serde(default)]
+    null_strategy: NullStrategy,
+    /// Score each distinct `(a, b)` pair once and scatter the result to
+    /// every row holding it. Also accepted as `dedup_pairs`.
+    #[serde(default, alias = "dedup_pairs")]
+    memoize: bool,
+    /// Return similarity scores as Float32 instead of Float64.
+    #[serde(default)]
+    float32: bool,
+    /// The metric's worst score, declared by the expression through
+    /// `with_worst_score`.
+    #[serde(skip)]
+    worst_score: Option<f64>,
+}
+
+/// How a row with a null string input is scored.
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NullStrategy {
+    /// The row yields null.
+    #[default]
+    Propagate,
+    /// Null inputs are scored as empty strings.
+    Empty,
+    /// The row gets the metric's worst score. Unbounded distances have none
+    /// and score the null side as an empty string, i.e. the other side's
+    /// full length.
+    MaxDistance,
+}
+
+/// How the scores of a row's element pairs are reduced to one value.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreAggregation {
+    Max,
+    Min,
+    Mean,
+}
+
+impl ScoreAggregation {
+    /// Reduces `scores`, or `None` when there are none.
+    pub fn aggregate(self, scores: impl Iterator<Item = f64>) -> Option<f64> {
+        let (count, total) = scores.fold((0_usize, None), |(count, total), score| {
+            let total = match (self, total) {
+                (_, None) => score,
+                (ScoreAggregation::Max, Some(total)) => score.max(total),
+                (ScoreAggregation::Min, Some(total)) => score.min(total),
+                (ScoreAggregation::Mean, Some(total)) => score + total,
+            };
+            (count + 1, Some(total))
+        });
+        match self {
+            ScoreAggregation::Mean => total.map(|total| total / count as f64),
+            _ => total,
+        }
+    }
+}
+
+impl ApplyKwargs {
+    /// These options for a metric whose worst score is `worst_score` (e.g.
+    /// `0.0` for similarities), used by `NullStrategy::MaxDistance`.
+    pub fn with_worst_score(&self, worst_score: f64) -> ApplyKwargs {
+        ApplyKwargs {
+            worst_score: Some(worst_score),
+            ..self.clone()
+        }
+    }
+
+    /// The dtype of the scores of a similarity expression.
+    pub fn score_dtype(&self) -> DataType {
+        if self.float32 {
+            DataType::Float32
+        } else {
+            DataType::Float64
+        }
+    }
+
+    /// The strings to score for a row under the null strategy, or the fixed
+    /// result of a row it settles without running the metric.
+    fn resolve_nulls<'a, T: ?Sized>(
+        &self,
+        a: Option<&'a T>,
+        b: Option<&'a T>,
+    ) -> Result<(&'a T, &'a T), Option<f64>>
+    where
+        &'a T: Default,
+    {
+        match (a, b, self.null_strategy) {
+            (Some(a), Some(b), _) => Ok((a, b)),
+            (_, _, NullStrategy::Propagate) => Err(None),
+            (_, _, NullStrategy::MaxDistance) if self.worst_score.is_some() => {
+                Err(self.worst_score)
+            }
+            (a, b, _) => Ok((a.unwrap_or_default(), b.unwrap_or_default())),
+        }
+    }
+
+    /// Runs `native_fn` for one row, returning `None` if it exceeded the
+    /// per-row timeout.
+    fn within_budget<K>(&self, native_fn: impl FnOnce() -> K) -> Option<K> {
+        match self.per_row_timeout_ms {
+            Some(timeout_ms) => {
+                deadline::within_budget(Duration::from_millis(timeout_ms), native_fn)
+            }
+            None => Some(native_fn()),
+        }
+    }
+
+    /// Scores one row with `native_fn`, falling back to the timeout value when
+    /// the row runs out of time.
+    fn score<K: num::NumCast>(&self, native_fn: impl FnOnce() -> K) -> Option<K> {
+        self.within_budget(native_fn)
+            .or_else(|| self.timeout_value.and_then(num::NumCast::from))
+    }
+}
+
+/// The bound a thresholded Damerau-Levenshtein expression holds a pair to.
+#[derive(Clone, Copy, Debug)]
+pub enum Threshold {
+    /// The distance must not exceed this.
+    MaxDistance(usize),
+    /// The normalized similarity must reach this, i.e. the distance must
+    /// not exceed `max_distance_for_cutoff` of the longer length.
+    ScoreCutoff(f64),
+}
+
+/// Largest distance over `len` characters whose normalized similarity can
+/// still reach `score_cutoff`. Rounded generously, since the score itself is
+/// compared against the cutoff afterwards.
+pub fn max_distance_for_cutoff(score_cutoff: f64, len: usize) -> usize {
+    ((1.0 - score_cutoff) * len as f64 + 1e-9).floor().max(0.0) as usize
+}
+
+impl Threshold {
+    /// Whether the character Damerau-Levenshtein distance of `a` and `b`
+    /// provably exceeds the bound, from counts far cheaper than the DP.
+    ///
+    /// The distance is at least the length difference. Past that, an edit
+    /// destroys at most three of the bigrams of either string (a
+    /// transposition touches the bigrams covering both swapped characters),
+    /// so a pair within `d` edits shares at least `max_len - 1 - 3 * d`
+    /// bigrams; the count is only taken when that is positive.
+    pub fn rejects(self, a: &str, b: &str) -> bool {
+        let (a_len, b_len) = (a.chars().count(), b.chars().count());
+        let max_len = a_len.max(b_len);
+        let max_distance = match self {
+            Threshold::MaxDistance(max_distance) => max_distance,
+            Threshold::ScoreCutoff(score_cutoff) => max_distance_for_cutoff(score_cutoff, max_len),
+        };
+        if a_len.abs_diff(b_len) > max_distance {
+            return true;
+        }
+        let required = (max_len as isize) - 1 - 3 * max_distance as isize;
+        required > 0 && (ngram::shared_bigram_count(a, b) as isize) < required
+    }
+}
+
+/// Opts a thresholded metric into the prefilter: pairs `threshold` rejects
+/// yield `rejected` (the bound, or the value the expression turns into null)
+/// without running `native_fn`.
+pub fn prefiltered<F, T>(
+    threshold: Threshold,
+    rejected: T,
+    native_fn: F,
+) -> impl Fn(&str, &str) -> T + Sync + Send
+where
+    F: Fn(&str, &str) -> T + Sync + Send,
+    T: Copy + Sync + Send,
+{
+    move |a, b| {
+        if threshold.rejects(a, b) {
+            rejected
+        } else {
+            native_fn(a, b)
+        }
+    }
+}
+
+fn dedicated_pool() -> Option<&'static ThreadPool> {
+    static DEDICATED_POOL: OnceLock<Option<ThreadPool>> = OnceLock::new();
+    DEDICATED_POOL
+        .get_or_init(|| {
+            let num_threads = std::env::var(NUM_THREADS_ENV)
+                .ok()?
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n > 0)?;
+            ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .thread_name(|i| format!("strsim-polars-{i}"))
+                .build()
+                .ok()
+        })
+        .as_ref()
+}
 
 fn split_offsets(len: usize, n: usize) -> Vec<(usize, usize)> {
     if n == 1 {
@@ -23,6 +249,31 @@ fn split_offsets(len: usize, n: usize) -> Vec<(usize, usize)> {
     }
 }
 
+/// Offsets of `n` splits of rows that follow the existing chunks of an
+/// input, given by their lengths: consecutive whole chunks are grouped
+/// until a split holds its share of the rows, so no chunk is cut. Inputs
+/// with fewer chunks than splits are split evenly instead.
+fn chunk_split_offsets(chunk_lengths: &[usize], n: usize) -> Vec<(usize, usize)> {
+    let rows_len = chunk_lengths.iter().sum();
+    if n == 1 || chunk_lengths.len() < n {
+        return split_offsets(rows_len, n);
+    }
+    let share = rows_len.div_ceil(n);
+    let mut offsets = Vec::with_capacity(n);
+    let (mut offset, mut len) = (0, 0);
+    for &chunk_len in chunk_lengths {
+        len += chunk_len;
+        if len >= share {
+            offsets.push((offset, len));
+            (offset, len) = (offset + len, 0);
+        }
+    }
+    if len > 0 {
+        offsets.push((offset, len));
+    }
+    offsets
+}
+
 pub fn parallel_apply<F, Out>(
     inputs: &[Series],
     context: CallerContext,
@@ -32,36 +283,1473 @@ where
     F: Fn(&str, &str) -> Out::Native + Sync + Send,
     Out: PolarsNumericType,
 {
-    let a = inputs[0].str()?;
-    let b = inputs[1].str()?;
-    if a.len() != b.len() {
+    parallel_apply_values::<F, Out::Native, Out>(inputs, context, native_fn)
+}
+
+/// Like `parallel_apply`, but for any output dtype whose arrays can be
+/// collected from the values `native_fn` returns (e.g. `BooleanType`).
+/// The output validity is that of the inputs, so `native_fn` must not
+/// return `Option`s; use `parallel_apply_opt_with` for metrics that may
+/// yield null.
+pub fn parallel_apply_values<F, K, Out>(
+    inputs: &[Series],
+    context: CallerContext,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> K + Sync + Send,
+    Out: PolarsDataType,
+    Out::Array: ArrayFromIter<K>,
+    ChunkedArray<Out>: IntoSeries,
+{
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let out: ChunkedArray<Out> =
+        apply_in_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+            let a = slice_rows(a, offset, len);
+            let b = slice_rows(b, offset, len);
+            arity::broadcast_binary_elementwise_values(&a, &b, |a, b| native_fn(a, b))
+        });
+    Ok(out.into_series())
+}
+
+/// `parallel_apply` honouring the shared `ApplyKwargs`. With a per-row
+/// timeout, every row runs under `deadline::within_budget` and rows that run
+/// out of time yield the configured timeout value (or null). When both
+/// inputs are Categorical or Enum, each distinct pair of categories is
+/// scored once.
+pub fn parallel_apply_with<F, Out>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> Out::Native + Sync + Send,
+    Out: PolarsNumericType,
+{
+    let score_row = |a: Option<&str>, b: Option<&str>| match apply.resolve_nulls(a, b) {
+        Ok((a, b)) => apply.score(|| native_fn(a, b)),
+        Err(fixed) => fixed.and_then(num::NumCast::from),
+    };
+    if let Some((a, b, rows_len)) = categorical_inputs(inputs)? {
+        let cats = rows(&a.cats, rows_len).zip(rows(&b.cats, rows_len));
+        let out: ChunkedArray<Out> = apply_memoized(cats, context, |(a_cat, b_cat)| {
+            score_row(a.get(a_cat), b.get(b_cat))
+        })
+        .into_iter()
+        .collect();
+        return Ok(out.into_series());
+    }
+    if apply.per_row_timeout_ms.is_none()
+        && apply.null_strategy == NullStrategy::Propagate
+        && !apply.memoize
+    {
+        return parallel_apply::<_, Out>(inputs, context, &native_fn);
+    }
+
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    if apply.memoize {
+        let pairs = rows(a, rows_len).zip(rows(b, rows_len));
+        let out: ChunkedArray<Out> = apply_memoized(pairs, context, |(a, b)| score_row(a, b))
+            .into_iter()
+            .collect();
+        return Ok(out.into_series());
+    }
+    let out: ChunkedArray<Out> =
+        apply_in_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+            let a = slice_rows(a, offset, len);
+            let b = slice_rows(b, offset, len);
+            arity::broadcast_binary_elementwise(&a, &b, score_row)
+        });
+    Ok(out.into_series())
+}
+
+/// `parallel_apply_with` for metrics that may leave a row unscored by
+/// returning `None` (null).
+pub fn parallel_apply_opt_with<F, Out>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> Option<Out::Native> + Sync + Send,
+    Out: PolarsNumericType,
+{
+    apply_rows::<_, Out, _>(inputs, context, apply, |a, b| {
+        match apply.resolve_nulls(a, b) {
+            Ok((a, b)) => apply
+                .within_budget(|| native_fn(a, b))
+                .unwrap_or_else(|| apply.timeout_value.and_then(num::NumCast::from)),
+            Err(fixed) => fixed.and_then(num::NumCast::from),
+        }
+    })
+}
+
+/// `parallel_apply_opt_with` for Boolean tests (e.g. whether a pair is
+/// within a bound). A fixed score from the null strategy or the timeout
+/// value becomes `false` when it is `0` and `true` otherwise.
+pub fn parallel_apply_bool_with<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> bool + Sync + Send,
+{
+    apply_rows::<_, BooleanType, _>(inputs, context, apply, |a, b| {
+        match apply.resolve_nulls(a, b) {
+            Ok((a, b)) => apply
+                .within_budget(|| native_fn(a, b))
+                .or_else(|| apply.timeout_value.map(|value| value != 0.0)),
+            Err(fixed) => fixed.map(|score| score != 0.0),
+        }
+    })
+}
+
+/// Scores every row of two string inputs with `score_row`, which sees the
+/// row's raw (possibly null) strings: each distinct pair of categories once
+/// for Categorical or Enum inputs, each distinct pair once with `memoize`,
+/// and row by row otherwise.
+fn apply_rows<K, Out, G>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    score_row: G,
+) -> PolarsResult<Series>
+where
+    G: Fn(Option<&str>, Option<&str>) -> Option<K> + Sync + Send,
+    K: Copy + Send,
+    Out: PolarsDataType,
+    Out::Array: ArrayFromIter<Option<K>>,
+    ChunkedArray<Out>: IntoSeries + FromIterator<Option<K>>,
+{
+    if let Some((a, b, rows_len)) = categorical_inputs(inputs)? {
+        let cats = rows(&a.cats, rows_len).zip(rows(&b.cats, rows_len));
+        let out: ChunkedArray<Out> = apply_memoized(cats, context, |(a_cat, b_cat)| {
+            score_row(a.get(a_cat), b.get(b_cat))
+        })
+        .into_iter()
+        .collect();
+        return Ok(out.into_series());
+    }
+
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    if apply.memoize {
+        let pairs = rows(a, rows_len).zip(rows(b, rows_len));
+        let out: ChunkedArray<Out> = apply_memoized(pairs, context, |(a, b)| score_row(a, b))
+            .into_iter()
+            .collect();
+        return Ok(out.into_series());
+    }
+    let out: ChunkedArray<Out> =
+        apply_in_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+            let a = slice_rows(a, offset, len);
+            let b = slice_rows(b, offset, len);
+            arity::broadcast_binary_elementwise(&a, &b, &score_row)
+        });
+    Ok(out.into_series())
+}
+
+/// `parallel_apply_opt_with` for two Binary inputs, handing `native_fn`
+/// their raw bytes (e.g. non-UTF-8 identifiers). Either side may be a
+/// single broadcast value.
+pub fn parallel_apply_bytes<F, Out>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&[u8], &[u8]) -> Option<Out::Native> + Sync + Send,
+    Out: PolarsNumericType,
+{
+    let (a, b) = (inputs[0].binary()?, inputs[1].binary()?);
+    let rows_len = broadcast_len(a.len(), b.len())?;
+    let out: ChunkedArray<Out> =
+        apply_in_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+            let a = slice_rows(a, offset, len);
+            let b = slice_rows(b, offset, len);
+            arity::broadcast_binary_elementwise(&a, &b, |a: Option<&[u8]>, b: Option<&[u8]>| {
+                match apply.resolve_nulls(a, b) {
+                    Ok((a, b)) => apply
+                        .within_budget(|| native_fn(a, b))
+                        .unwrap_or_else(|| apply.timeout_value.and_then(num::NumCast::from)),
+                    Err(fixed) => fixed.and_then(num::NumCast::from),
+                }
+            })
+        });
+    Ok(out.into_series())
+}
+
+/// Applies a single-input string transform (e.g. a phonetic encoding) to
+/// `inputs[0]`, keeping nulls.
+pub fn parallel_apply_str<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str) -> String + Sync + Send,
+{
+    let a = string_values(&inputs[0])?;
+    let a = a.as_ref();
+    let out: StringChunked =
+        apply_in_splits(layout_chunks([a], a.len()), context, |offset, len| {
+            a.slice(offset as i64, len)
+                .iter()
+                .map(|a| a.map(&native_fn))
+                .collect()
+        });
+    Ok(out.into_series())
+}
+
+/// Like `parallel_apply_str`, for lookups that may find nothing, with
+/// null handling and time budgets; rows yielding `None` are null.
+pub fn parallel_apply_str_opt<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str) -> Option<String> + Sync + Send,
+{
+    let a = string_values(&inputs[0])?;
+    let a = a.as_ref();
+    let out: StringChunked =
+        apply_in_splits(layout_chunks([a], a.len()), context, |offset, len| {
+            a.slice(offset as i64, len)
+                .iter()
+                .map(|a| {
+                    let (a, _) = apply.resolve_nulls(a, Some("")).ok()?;
+                    apply.within_budget(|| native_fn(a)).flatten()
+                })
+                .collect()
+        });
+    Ok(out.into_series())
+}
+
+/// Like `parallel_apply_str`, for transforms producing two strings per row,
+/// returned as a struct with the given field names.
+pub fn parallel_apply_str_pair<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    names: [&str; 2],
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str) -> (String, String) + Sync + Send,
+{
+    let a = string_values(&inputs[0])?;
+    let a = a.as_ref();
+    let splits = map_splits(layout_chunks([a], a.len()), context, |offset, len| {
+        let (first, second): (Vec<Option<String>>, Vec<Option<String>>) = a
+            .slice(offset as i64, len)
+            .iter()
+            .map(|a| match a.map(&native_fn) {
+                Some((first, second)) => (Some(first), Some(second)),
+                None => (None, None),
+            })
+            .unzip();
+        (
+            first.into_iter().collect::<StringChunked>(),
+            second.into_iter().collect::<StringChunked>(),
+        )
+    });
+
+    let (first_splits, second_splits): (Vec<_>, Vec<_>) = splits.into_iter().unzip();
+    let concat = |splits: Vec<StringChunked>, name: &str| {
+        StringChunked::from_chunk_iter(
+            name.into(),
+            splits
+                .iter()
+                .flat_map(|split| split.downcast_iter().cloned().collect::<Vec<_>>()),
+        )
+        .into_series()
+    };
+    let fields = [
+        concat(first_splits, names[0]),
+        concat(second_splits, names[1]),
+    ];
+    Ok(StructChunked::from_series("".into(), a.len(), fields.iter())?.into_series())
+}
+
+/// Applies a single-input function returning a label and its score per row
+/// (e.g. the best-matching candidate) to `inputs[0]`, as a struct with the
+/// given field names.
+pub fn parallel_apply_str_scored<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    names: [&str; 2],
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str) -> (String, f64) + Sync + Send,
+{
+    let a = string_values(&inputs[0])?;
+    let a = a.as_ref();
+    let splits = map_splits(layout_chunks([a], a.len()), context, |offset, len| {
+        let (labels, scores): (Vec<Option<String>>, Vec<Option<f64>>) = a
+            .slice(offset as i64, len)
+            .iter()
+            .map(|a| match apply.resolve_nulls(a, Some("")) {
+                Ok((a, _)) => match apply.within_budget(|| native_fn(a)) {
+                    Some((label, score)) => (Some(label), Some(score)),
+                    None => (None, apply.timeout_value),
+                },
+                Err(fixed) => (None, fixed),
+            })
+            .unzip();
+        (
+            labels.into_iter().collect::<StringChunked>(),
+            scores.into_iter().collect::<Float64Chunked>(),
+        )
+    });
+
+    let (label_splits, score_splits): (Vec<_>, Vec<_>) = splits.into_iter().unzip();
+    let labels = StringChunked::from_chunk_iter(
+        names[0].into(),
+        label_splits
+            .iter()
+            .flat_map(|split| split.downcast_iter().cloned().collect::<Vec<_>>()),
+    );
+    let scores = Float64Chunked::from_chunk_iter(
+        names[1].into(),
+        score_splits
+            .iter()
+            .flat_map(|split| split.downcast_iter().cloned().collect::<Vec<_>>()),
+    );
+    let fields = [labels.into_series(), scores.into_series()];
+    Ok(StructChunked::from_series("".into(), a.len(), fields.iter())?.into_series())
+}
+
+/// Like `parallel_apply_str_scored`, for functions producing a
+/// variable-length `Series` of `inner_dtype` per row, returned as a list
+/// column.
+pub fn parallel_apply_str_list<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    inner_dtype: &DataType,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str) -> PolarsResult<Series> + Sync + Send,
+{
+    let a = string_values(&inputs[0])?;
+    let a = a.as_ref();
+    let splits = map_splits(
+        layout_chunks([a], a.len()),
+        context,
+        |offset, len| -> PolarsResult<ListChunked> {
+            let mut builder = get_list_builder(inner_dtype, len, len, "".into());
+            for a in a.slice(offset as i64, len).iter() {
+                let values = apply
+                    .resolve_nulls(a, Some(""))
+                    .ok()
+                    .and_then(|(a, _)| apply.within_budget(|| native_fn(a)));
+                match values {
+                    Some(values) => builder.append_series(&values?)?,
+                    None => builder.append_null(),
+                }
+            }
+            Ok(builder.finish())
+        },
+    );
+
+    let mut out = ListChunked::full_null_with_dtype("".into(), 0, inner_dtype);
+    for split in splits {
+        out.append(&split?)?;
+    }
+    Ok(out.into_series())
+}
+
+/// Applies `native_fn` to the list values of `inputs[0]` and `inputs[1]`,
+/// row by row. When `inputs` has four columns, `inputs[2]` and `inputs[3]`
+/// are list columns passed along as the per-element weights of each side.
+/// Rows where any input is null yield null.
+pub fn parallel_apply_lists<F, Out>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&Series, &Series, Option<(&Series, &Series)>) -> PolarsResult<Out::Native> + Sync + Send,
+    Out: PolarsNumericType,
+{
+    let lists = inputs
+        .iter()
+        .map(|s| s.list())
+        .collect::<PolarsResult<Vec<_>>>()?;
+    if lists.len() != 2 && lists.len() != 4 {
+        return Err(PolarsError::ComputeError(
+            "Expected two list columns, optionally followed by their two weight columns.".into(),
+        ));
+    }
+    if lists.iter().any(|list| list.len() != lists[0].len()) {
+        return Err(PolarsError::ShapeMismatch(
+            "List inputs and weights must all have the same length.".into(),
+        ));
+    }
+
+    let splits = map_splits(
+        layout_chunks([lists[0]], lists[0].len()),
+        context,
+        |offset, len| {
+            let rows: Vec<_> = lists
+                .iter()
+                .map(|list| {
+                    list.slice(offset as i64, len)
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            (0..len)
+                .map(|i| {
+                    let row: Option<Vec<&Series>> =
+                        rows.iter().map(|column| column[i].as_ref()).collect();
+                    let Some(row) = row else {
+                        return Ok(None);
+                    };
+                    let weights = (row.len() == 4).then(|| (row[2], row[3]));
+                    match apply.within_budget(|| native_fn(row[0], row[1], weights)) {
+                        Some(score) => score.map(Some),
+                        None => Ok(apply.timeout_value.and_then(num::NumCast::from)),
+                    }
+                })
+                .collect::<PolarsResult<ChunkedArray<Out>>>()
+        },
+    );
+
+    let mut chunks = Vec::new();
+    for split in splits {
+        chunks.extend(split?.downcast_iter().cloned());
+    }
+    Ok(ChunkedArray::<Out>::from_chunk_iter("".into(), chunks).into_series())
+}
+
+/// Applies `native_fn` to the strings of `inputs[0]` and `inputs[1]`
+/// together with the list values of `inputs[2]` and `inputs[3]` (e.g.
+/// per-character weights), row by row. Rows where any input is null yield
+/// null.
+pub fn parallel_apply_str_with_lists<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str, &Series, &Series) -> PolarsResult<f64> + Sync + Send,
+{
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let list_a = inputs[2].list()?;
+    let list_b = inputs[3].list()?;
+    if list_a.len() != rows_len || list_b.len() != rows_len {
         return Err(PolarsError::ShapeMismatch(
-            "Inputs must have the same length, or one of them must be a Utf8 literal.".into(),
+            "String inputs and their list columns must all have the same length.".into(),
         ));
     }
+
+    let splits = map_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+        let a = slice_rows(a, offset, len);
+        let b = slice_rows(b, offset, len);
+        let list_a = list_a.slice(offset as i64, len);
+        let list_b = list_b.slice(offset as i64, len);
+        rows(&a, len)
+            .zip(rows(&b, len))
+            .zip(list_a.into_iter().zip(&list_b))
+            .map(|((a, b), lists)| match (apply.resolve_nulls(a, b), lists) {
+                (Ok((a, b)), (Some(list_a), Some(list_b))) => {
+                    match apply.within_budget(|| native_fn(a, b, &list_a, &list_b)) {
+                        Some(score) => score.map(Some),
+                        None => Ok(apply.timeout_value),
+                    }
+                }
+                (Err(fixed), _) => Ok(fixed),
+                _ => Ok(None),
+            })
+            .collect::<PolarsResult<Float64Chunked>>()
+    });
+
+    let mut chunks = Vec::new();
+    for split in splits {
+        chunks.extend(split?.downcast_iter().cloned());
+    }
+    Ok(Float64Chunked::from_chunk_iter("".into(), chunks).into_series())
+}
+
+/// The string values of an input. Categorical and Enum inputs are cast to
+/// their strings; any other non-string dtype is an error.
+pub fn string_values(s: &Series) -> PolarsResult<Cow<'_, StringChunked>> {
+    match s.dtype() {
+        DataType::Categorical(_, _) | DataType::Enum(_, _) => {
+            Ok(Cow::Owned(s.cast(&DataType::String)?.str()?.clone()))
+        }
+        _ => Ok(Cow::Borrowed(s.str()?)),
+    }
+}
+
+/// The two string inputs and the number of rows to produce. Either side may
+/// hold a single value (e.g. a literal), which is then compared against
+/// every row of the other side without being materialized.
+fn string_inputs(
+    inputs: &[Series],
+) -> PolarsResult<(Cow<'_, StringChunked>, Cow<'_, StringChunked>, usize)> {
+    let a = string_values(&inputs[0])?;
+    let b = string_values(&inputs[1])?;
+    let len = broadcast_len(a.len(), b.len())?;
+    Ok((a, b, len))
+}
+
+/// The number of rows of a pairwise expression over inputs of these lengths.
+fn broadcast_len(a_len: usize, b_len: usize) -> PolarsResult<usize> {
+    match (a_len, b_len) {
+        (a_len, b_len) if a_len == b_len => Ok(a_len),
+        (1, len) | (len, 1) => Ok(len),
+        _ => Err(PolarsError::ShapeMismatch(
+            "Inputs must have the same length, or one of them must be a single value (e.g. a Utf8 literal).".into(),
+        )),
+    }
+}
+
+/// A Categorical or Enum input as its category ids and the mapping turning
+/// them back into strings.
+struct CategoricalInput<'a> {
+    cats: UInt32Chunked,
+    mapping: &'a CategoricalMapping,
+}
+
+impl CategoricalInput<'_> {
+    fn get(&self, cat: Option<u32>) -> Option<&str> {
+        self.mapping.cat_to_str(cat?)
+    }
+}
+
+fn categorical_input(s: &Series) -> PolarsResult<Option<CategoricalInput<'_>>> {
+    let (DataType::Categorical(_, mapping) | DataType::Enum(_, mapping)) = s.dtype() else {
+        return Ok(None);
+    };
+    let cats = s.to_physical_repr().cast(&DataType::UInt32)?.u32()?.clone();
+    Ok(Some(CategoricalInput { cats, mapping }))
+}
+
+/// Both inputs of a pairwise expression when they are Categorical or Enum,
+/// with the number of rows.
+fn categorical_inputs(
+    inputs: &[Series],
+) -> PolarsResult<Option<(CategoricalInput<'_>, CategoricalInput<'_>, usize)>> {
+    let (Some(a), Some(b)) = (
+        categorical_input(&inputs[0])?,
+        categorical_input(&inputs[1])?,
+    ) else {
+        return Ok(None);
+    };
+    let len = broadcast_len(a.cats.len(), b.cats.len())?;
+    Ok(Some((a, b, len)))
+}
+
+/// Rows `[offset, offset + len)` of an input, keeping a broadcast single
+/// value or the whole input as is.
+fn slice_rows<T: PolarsDataType>(
+    ca: &ChunkedArray<T>,
+    offset: usize,
+    len: usize,
+) -> Cow<'_, ChunkedArray<T>> {
+    if ca.len() == 1 || (offset == 0 && len == ca.len()) {
+        Cow::Borrowed(ca)
+    } else {
+        Cow::Owned(ca.slice(offset as i64, len))
+    }
+}
+
+/// Iterates `len` rows of a sliced input, repeating a broadcast single
+/// value.
+fn rows<T: PolarsDataType>(
+    ca: &ChunkedArray<T>,
+    len: usize,
+) -> Box<dyn Iterator<Item = Option<T::Physical<'_>>> + '_> {
+    if ca.len() == 1 {
+        Box::new(std::iter::repeat_n(ca.get(0), len))
+    } else {
+        Box::new(ca.iter())
+    }
+}
+
+/// Scores the distinct keys of the rows (e.g. their `(a, b)` pairs) once
+/// each, split like any other input, and scatters the results back to the
+/// rows in order.
+fn apply_memoized<P, K, G>(
+    row_keys: impl Iterator<Item = P>,
+    context: CallerContext,
+    score_key: G,
+) -> Vec<Option<K>>
+where
+    P: Copy + Eq + std::hash::Hash + Sync,
+    G: Fn(P) -> Option<K> + Sync + Send,
+    K: Copy + Send,
+{
+    let mut slots = HashMap::new();
+    let mut pairs = Vec::new();
+    let row_slots: Vec<usize> = row_keys
+        .map(|pair| {
+            *slots.entry(pair).or_insert_with(|| {
+                pairs.push(pair);
+                pairs.len() - 1
+            })
+        })
+        .collect();
+    let scores: Vec<Option<K>> = map_splits(vec![pairs.len()], context, |offset, len| {
+        pairs[offset..offset + len]
+            .iter()
+            .map(|&pair| score_key(pair))
+            .collect::<Vec<_>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+    row_slots.into_iter().map(|slot| scores[slot]).collect()
+}
+
+/// Runs `split_fn` over all rows when Polars already parallelizes the call,
+/// otherwise fans it out over the dedicated pool (or `POOL` if none is
+/// configured) in one split per thread, along the input chunks of
+/// `chunk_lengths` (see `layout_chunks`), and stitches the resulting chunks
+/// back together in order.
+fn apply_in_splits<Out, G>(
+    chunk_lengths: Vec<usize>,
+    context: CallerContext,
+    split_fn: G,
+) -> ChunkedArray<Out>
+where
+    G: Fn(usize, usize) -> ChunkedArray<Out> + Sync + Send,
+    Out: PolarsDataType,
+{
+    concat_splits(map_splits(chunk_lengths, context, split_fn))
+}
+
+/// The chunk lengths that the splits of `rows_len` rows follow: those of
+/// the first input that is not a broadcast single value. A single length
+/// (e.g. `vec![len]` for rows that are not an input) is split evenly.
+fn layout_chunks<'a, T: PolarsDataType + 'a>(
+    inputs: impl IntoIterator<Item = &'a ChunkedArray<T>>,
+    rows_len: usize,
+) -> Vec<usize> {
+    inputs
+        .into_iter()
+        .find(|ca| ca.len() == rows_len)
+        .map_or_else(|| vec![rows_len], |ca| ca.chunk_lengths().collect())
+}
+
+/// The outputs of consecutive splits as one array, moving their chunks
+/// rather than copying them. A single split is returned as is.
+fn concat_splits<Out: PolarsDataType>(splits: Vec<ChunkedArray<Out>>) -> ChunkedArray<Out> {
+    if splits.len() == 1 {
+        return splits.into_iter().next().unwrap();
+    }
+    ChunkedArray::<Out>::from_chunk_iter(
+        "".into(),
+        splits
+            .into_iter()
+            .flat_map(ChunkedArray::downcast_into_iter),
+    )
+}
+
+/// Number of splits for `len` rows: one per thread of the dedicated pool
+/// (or `POOL`), each holding at least `MIN_SPLIT_LEN` rows, or a single one
+/// when Polars already parallelizes the call.
+fn split_count(len: usize, context: CallerContext) -> usize {
+    let pool = dedicated_pool().unwrap_or(&POOL);
     if context.parallel() {
-        let out: ChunkedArray<Out> =
-            arity::binary_elementwise_values(a, b, |s1, s2| native_fn(s1, s2));
-        Ok(out.into_series())
+        1
     } else {
-        POOL.install(|| {
-            let splits = split_offsets(a.len(), POOL.current_num_threads());
-
-            let chunks: Vec<_> = splits
-                .into_par_iter()
-                .map(|(offset, len)| {
-                    let out: ChunkedArray<Out> = {
-                        let a = a.slice(offset as i64, len);
-                        let b = b.slice(offset as i64, len);
-                        arity::binary_elementwise_values(&a, &b, |a, b| native_fn(a, b))
+        pool.current_num_threads().min(len / MIN_SPLIT_LEN).max(1)
+    }
+}
+
+/// The split scheduling behind `apply_in_splits`, returning each split's
+/// result in order for callers that build more than one output per split.
+/// Inputs too short for two splits never leave the calling thread.
+fn map_splits<T, G>(chunk_lengths: Vec<usize>, context: CallerContext, split_fn: G) -> Vec<T>
+where
+    G: Fn(usize, usize) -> T + Sync + Send,
+    T: Send,
+{
+    let n = split_count(chunk_lengths.iter().sum(), context);
+    map_offsets(chunk_split_offsets(&chunk_lengths, n), split_fn)
+}
+
+/// Runs `split_fn` over each `(offset, len)` split, on the calling thread
+/// for a single split and over the pool otherwise.
+fn map_offsets<T, G>(offsets: Vec<(usize, usize)>, split_fn: G) -> Vec<T>
+where
+    G: Fn(usize, usize) -> T + Sync + Send,
+    T: Send,
+{
+    if let [(offset, len)] = offsets[..] {
+        return vec![split_fn(offset, len)];
+    }
+    dedicated_pool().unwrap_or(&POOL).install(|| {
+        offsets
+            .into_par_iter()
+            .map(|(offset, len)| split_fn(offset, len))
+            .collect()
+    })
+}
+
+/// Applies a metric that scores both directions of each pair in one pass and
+/// returns them as a struct with fields `ab` (`a` against `b`) and `ba`.
+/// With `score_cutoff`, each field is null where its score is below the
+/// cutoff.
+pub fn parallel_apply_both_directions<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    score_cutoff: Option<f64>,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> (f64, f64) + Sync + Send,
+{
+    let passes = |score: f64| Some(score).filter(|&score| score_cutoff.is_none_or(|c| score >= c));
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let splits = map_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+        let a = slice_rows(a, offset, len);
+        let b = slice_rows(b, offset, len);
+        let (ab, ba): (Vec<Option<f64>>, Vec<Option<f64>>) = rows(&a, len)
+            .zip(rows(&b, len))
+            .map(|(a, b)| match apply.resolve_nulls(a, b) {
+                Ok((a, b)) => match apply.within_budget(|| native_fn(a, b)) {
+                    Some((ab, ba)) => (passes(ab), passes(ba)),
+                    None => (apply.timeout_value, apply.timeout_value),
+                },
+                Err(fixed) => (fixed, fixed),
+            })
+            .unzip();
+        (
+            ab.into_iter().collect::<Float64Chunked>(),
+            ba.into_iter().collect::<Float64Chunked>(),
+        )
+    });
+
+    let (ab_splits, ba_splits): (Vec<_>, Vec<_>) = splits.into_iter().unzip();
+    let concat = |splits: Vec<Float64Chunked>, name: &str| {
+        Float64Chunked::from_chunk_iter(
+            name.into(),
+            splits
+                .iter()
+                .flat_map(|split| split.downcast_iter().cloned().collect::<Vec<_>>()),
+        )
+        .into_series()
+    };
+    let fields = [concat(ab_splits, "ab"), concat(ba_splits, "ba")];
+    Ok(StructChunked::from_series("".into(), rows_len, fields.iter())?.into_series())
+}
+
+/// Applies a distance metric that also reports its normalized distance and
+/// similarity, returned as a struct with fields `distance`, `normalized` and
+/// `similarity`, all computed from one run of `native_fn`.
+pub fn parallel_apply_distance_summary<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> (i64, f64, f64) + Sync + Send,
+{
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let splits = map_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+        let a = slice_rows(a, offset, len);
+        let b = slice_rows(b, offset, len);
+        let mut distance = Vec::with_capacity(len);
+        let mut normalized = Vec::with_capacity(len);
+        let mut similarity = Vec::with_capacity(len);
+        for (a, b) in rows(&a, len).zip(rows(&b, len)) {
+            let summary = match apply.resolve_nulls(a, b) {
+                Ok((a, b)) => match apply.within_budget(|| native_fn(a, b)) {
+                    Some((d, n, s)) => (Some(d), Some(n), Some(s)),
+                    None => (
+                        apply.timeout_value.and_then(num::NumCast::from),
+                        apply.timeout_value,
+                        apply.timeout_value,
+                    ),
+                },
+                Err(_) => (None, None, None),
+            };
+            distance.push(summary.0);
+            normalized.push(summary.1);
+            similarity.push(summary.2);
+        }
+        (
+            distance.into_iter().collect::<Int64Chunked>(),
+            normalized.into_iter().collect::<Float64Chunked>(),
+            similarity.into_iter().collect::<Float64Chunked>(),
+        )
+    });
+
+    let mut distance_splits = Vec::with_capacity(splits.len());
+    let mut normalized_splits = Vec::with_capacity(splits.len());
+    let mut similarity_splits = Vec::with_capacity(splits.len());
+    for (distance, normalized, similarity) in splits {
+        distance_splits.extend(distance.downcast_iter().cloned().collect::<Vec<_>>());
+        normalized_splits.extend(normalized.downcast_iter().cloned().collect::<Vec<_>>());
+        similarity_splits.extend(similarity.downcast_iter().cloned().collect::<Vec<_>>());
+    }
+    let fields = [
+        Int64Chunked::from_chunk_iter("distance".into(), distance_splits).into_series(),
+        Float64Chunked::from_chunk_iter("normalized".into(), normalized_splits).into_series(),
+        Float64Chunked::from_chunk_iter("similarity".into(), similarity_splits).into_series(),
+    ];
+    Ok(StructChunked::from_series("".into(), rows_len, fields.iter())?.into_series())
+}
+
+/// Applies a function scoring a pair under an integer distance and `N`
+/// float metrics at once, returned as a struct with field `int_name`
+/// followed by `float_names`.
+pub fn parallel_apply_metric_profile<F, const N: usize>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    int_name: &str,
+    float_names: [&str; N],
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> (i64, [f64; N]) + Sync + Send,
+{
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let splits = map_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+        let a = slice_rows(a, offset, len);
+        let b = slice_rows(b, offset, len);
+        let mut ints = Vec::with_capacity(len);
+        let mut floats: [Vec<Option<f64>>; N] = std::array::from_fn(|_| Vec::with_capacity(len));
+        for (a, b) in rows(&a, len).zip(rows(&b, len)) {
+            let profile = match apply.resolve_nulls(a, b) {
+                Ok((a, b)) => match apply.within_budget(|| native_fn(a, b)) {
+                    Some((int, values)) => (Some(int), values.map(Some)),
+                    None => (
+                        apply.timeout_value.and_then(num::NumCast::from),
+                        [apply.timeout_value; N],
+                    ),
+                },
+                Err(_) => (None, [None; N]),
+            };
+            ints.push(profile.0);
+            for (field, value) in floats.iter_mut().zip(profile.1) {
+                field.push(value);
+            }
+        }
+        (
+            ints.into_iter().collect::<Int64Chunked>(),
+            floats.map(|field| field.into_iter().collect::<Float64Chunked>()),
+        )
+    });
+
+    let mut int_chunks = Vec::with_capacity(splits.len());
+    let mut float_chunks: [Vec<_>; N] = std::array::from_fn(|_| Vec::new());
+    for (ints, floats) in splits {
+        int_chunks.extend(ints.downcast_iter().cloned().collect::<Vec<_>>());
+        for (chunks, field) in float_chunks.iter_mut().zip(floats) {
+            chunks.extend(field.downcast_iter().cloned().collect::<Vec<_>>());
+        }
+    }
+    let fields: Vec<Series> =
+        std::iter::once(Int64Chunked::from_chunk_iter(int_name.into(), int_chunks).into_series())
+            .chain(
+                float_chunks
+                    .into_iter()
+                    .zip(float_names)
+                    .map(|(chunks, name)| {
+                        Float64Chunked::from_chunk_iter(name.into(), chunks).into_series()
+                    }),
+            )
+            .collect();
+    Ok(StructChunked::from_series("".into(), rows_len, fields.iter())?.into_series())
+}
+
+/// Applies a partial-match function reporting the best window's distance,
+/// start and end offsets and text, returned as a struct with fields
+/// `distance`, `start`, `end` and `matched`.
+pub fn parallel_apply_partial_matches<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> (i64, u32, u32, String) + Sync + Send,
+{
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let splits = map_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+        let a = slice_rows(a, offset, len);
+        let b = slice_rows(b, offset, len);
+        let mut distance = Vec::with_capacity(len);
+        let mut start = Vec::with_capacity(len);
+        let mut end = Vec::with_capacity(len);
+        let mut matched = Vec::with_capacity(len);
+        for (a, b) in rows(&a, len).zip(rows(&b, len)) {
+            let found = match apply.resolve_nulls(a, b) {
+                Ok((a, b)) => match apply.within_budget(|| native_fn(a, b)) {
+                    Some((d, s, e, m)) => (Some(d), Some(s), Some(e), Some(m)),
+                    None => (
+                        apply.timeout_value.and_then(num::NumCast::from),
+                        None,
+                        None,
+                        None,
+                    ),
+                },
+                Err(_) => (None, None, None, None),
+            };
+            distance.push(found.0);
+            start.push(found.1);
+            end.push(found.2);
+            matched.push(found.3);
+        }
+        (
+            distance.into_iter().collect::<Int64Chunked>(),
+            start.into_iter().collect::<UInt32Chunked>(),
+            end.into_iter().collect::<UInt32Chunked>(),
+            matched.into_iter().collect::<StringChunked>(),
+        )
+    });
+
+    let mut distance_splits = Vec::with_capacity(splits.len());
+    let mut start_splits = Vec::with_capacity(splits.len());
+    let mut end_splits = Vec::with_capacity(splits.len());
+    let mut matched_splits = Vec::with_capacity(splits.len());
+    for (distance, start, end, matched) in splits {
+        distance_splits.extend(distance.downcast_iter().cloned().collect::<Vec<_>>());
+        start_splits.extend(start.downcast_iter().cloned().collect::<Vec<_>>());
+        end_splits.extend(end.downcast_iter().cloned().collect::<Vec<_>>());
+        matched_splits.extend(matched.downcast_iter().cloned().collect::<Vec<_>>());
+    }
+    let fields = [
+        Int64Chunked::from_chunk_iter("distance".into(), distance_splits).into_series(),
+        UInt32Chunked::from_chunk_iter("start".into(), start_splits).into_series(),
+        UInt32Chunked::from_chunk_iter("end".into(), end_splits).into_series(),
+        StringChunked::from_chunk_iter("matched".into(), matched_splits).into_series(),
+    ];
+    Ok(StructChunked::from_series("".into(), rows_len, fields.iter())?.into_series())
+}
+
+/// Applies a function producing `N` strings per pair (e.g. a rendered
+/// alignment), returned as a struct with the given field names.
+pub fn parallel_apply_str_fields<F, const N: usize>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    names: [&str; N],
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> [String; N] + Sync + Send,
+{
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let splits = map_splits(layout_chunks([a, b], rows_len), context, |offset, len| {
+        let a = slice_rows(a, offset, len);
+        let b = slice_rows(b, offset, len);
+        let mut fields: [Vec<Option<String>>; N] = std::array::from_fn(|_| Vec::with_capacity(len));
+        for (a, b) in rows(&a, len).zip(rows(&b, len)) {
+            let values = apply
+                .resolve_nulls(a, b)
+                .ok()
+                .and_then(|(a, b)| apply.within_budget(|| native_fn(a, b)));
+            match values {
+                Some(values) => {
+                    for (field, value) in fields.iter_mut().zip(values) {
+                        field.push(Some(value));
+                    }
+                }
+                None => fields.iter_mut().for_each(|field| field.push(None)),
+            }
+        }
+        fields.map(|field| field.into_iter().collect::<StringChunked>())
+    });
+
+    let mut chunks: [Vec<_>; N] = std::array::from_fn(|_| Vec::new());
+    for split in splits {
+        for (chunks, field) in chunks.iter_mut().zip(split) {
+            chunks.extend(field.downcast_iter().cloned().collect::<Vec<_>>());
+        }
+    }
+    let fields: Vec<Series> = chunks
+        .into_iter()
+        .zip(names)
+        .map(|(chunks, name)| StringChunked::from_chunk_iter(name.into(), chunks).into_series())
+        .collect();
+    Ok(StructChunked::from_series("".into(), rows_len, fields.iter())?.into_series())
+}
+
+/// Applies a function producing a variable-length `Series` of `inner_dtype`
+/// per pair (e.g. an edit script), returned as a list column.
+pub fn parallel_apply_list<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    inner_dtype: &DataType,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> PolarsResult<Series> + Sync + Send,
+{
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    let splits = map_splits(
+        layout_chunks([a, b], rows_len),
+        context,
+        |offset, len| -> PolarsResult<ListChunked> {
+            let a = slice_rows(a, offset, len);
+            let b = slice_rows(b, offset, len);
+            let mut builder = get_list_builder(inner_dtype, len, len, "".into());
+            for (a, b) in rows(&a, len).zip(rows(&b, len)) {
+                let values = apply
+                    .resolve_nulls(a, b)
+                    .ok()
+                    .and_then(|(a, b)| apply.within_budget(|| native_fn(a, b)));
+                match values {
+                    Some(values) => builder.append_series(&values?)?,
+                    None => builder.append_null(),
+                }
+            }
+            Ok(builder.finish())
+        },
+    );
+
+    let mut out = ListChunked::full_null_with_dtype("".into(), 0, inner_dtype);
+    for split in splits {
+        out.append(&split?)?;
+    }
+    Ok(out.into_series())
+}
+
+/// Combines the scores of the string pairs `(inputs[2 * p], inputs[2 * p +
+/// 1])` into their `weights`-weighted mean per row, scoring pair `p` with
+/// `native_fn(p, a, b)` in `[0, 1]`. Pairs are scored heaviest first and,
+/// with a `score_cutoff`, a row yields null as soon as its remaining pairs
+/// can no longer lift it to the cutoff. Any input may be a single broadcast
+/// value. A row is null when a pair's null strategy settles it as null.
+pub fn parallel_apply_weighted_pairs<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    weights: &[f64],
+    score_cutoff: Option<f64>,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(usize, &str, &str) -> f64 + Sync + Send,
+{
+    if weights.is_empty() || inputs.len() != 2 * weights.len() {
+        return Err(PolarsError::ComputeError(
+            "Expected one weight per pair of string inputs.".into(),
+        ));
+    }
+    if weights.iter().any(|&w| !w.is_finite() || w < 0.0) || weights.iter().sum::<f64>() <= 0.0 {
+        return Err(PolarsError::ComputeError(
+            "Weights must be finite and non-negative with a positive sum.".into(),
+        ));
+    }
+    let columns = inputs
+        .iter()
+        .map(string_values)
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let rows_len = columns.iter().map(|ca| ca.len()).max().unwrap_or(0);
+    if columns
+        .iter()
+        .any(|ca| ca.len() != rows_len && ca.len() != 1)
+    {
+        return Err(PolarsError::ShapeMismatch(
+            "Inputs must have the same length, or be single values.".into(),
+        ));
+    }
+    let total: f64 = weights.iter().sum();
+    let mut order: Vec<usize> = (0..weights.len()).collect();
+    order.sort_by(|&p, &q| weights[q].total_cmp(&weights[p]));
+
+    let score_row = |row: &[Option<&str>]| -> Option<f64> {
+        let (mut score, mut remaining) = (0.0, total);
+        for &p in &order {
+            let pair_score = match apply.resolve_nulls(row[2 * p], row[2 * p + 1]) {
+                Ok((a, b)) => native_fn(p, a, b),
+                Err(fixed) => fixed?,
+            };
+            score += weights[p] * pair_score;
+            remaining -= weights[p];
+            if score_cutoff.is_some_and(|cutoff| (score + remaining) / total < cutoff) {
+                return None;
+            }
+        }
+        Some(score / total)
+    };
+
+    let layout = layout_chunks(columns.iter().map(|ca| ca.as_ref()), rows_len);
+    let out: Float64Chunked = apply_in_splits(layout, context, |offset, len| {
+        let sliced: Vec<Cow<StringChunked>> = columns
+            .iter()
+            .map(|ca| slice_rows(ca, offset, len))
+            .collect();
+        let mut iters: Vec<_> = sliced.iter().map(|ca| rows(ca, len)).collect();
+        let mut row = vec![None; iters.len()];
+        (0..len)
+            .map(|_| {
+                for (value, iter) in row.iter_mut().zip(iters.iter_mut()) {
+                    *value = iter.next().flatten();
+                }
+                apply
+                    .within_budget(|| score_row(&row))
+                    .unwrap_or(apply.timeout_value)
+            })
+            .collect()
+    });
+    Ok(out.into_series())
+}
+
+/// Zips the `List[String]` values of `inputs[0]` and `inputs[1]` row by
+/// row and applies `native_fn` to each aligned element pair, returning a
+/// `List[Float64]` as long as the shorter list. Rows where either list is
+/// null yield null; null elements are resolved like null strings.
+pub fn parallel_apply_zipped_lists<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> f64 + Sync + Send,
+{
+    let (a, b) = (inputs[0].list()?, inputs[1].list()?);
+    if a.len() != b.len() {
+        return Err(PolarsError::ShapeMismatch(
+            "Both list inputs must have the same length.".into(),
+        ));
+    }
+
+    let splits = map_splits(
+        layout_chunks([a], a.len()),
+        context,
+        |offset, len| -> PolarsResult<ListChunked> {
+            let a = a.slice(offset as i64, len);
+            let b = b.slice(offset as i64, len);
+            let mut builder = get_list_builder(&DataType::Float64, len, len, "".into());
+            for (a, b) in a.into_iter().zip(&b) {
+                let (Some(a), Some(b)) = (a, b) else {
+                    builder.append_null();
+                    continue;
+                };
+                let scores: Float64Chunked = a
+                    .str()?
+                    .into_iter()
+                    .zip(b.str()?)
+                    .map(|(a, b)| match apply.resolve_nulls(a, b) {
+                        Ok((a, b)) => apply.score(|| native_fn(a, b)),
+                        Err(fixed) => fixed,
+                    })
+                    .collect();
+                builder.append_series(&scores.into_series())?;
+            }
+            Ok(builder.finish())
+        },
+    );
+
+    let mut out = ListChunked::full_null_with_dtype("".into(), 0, &DataType::Float64);
+    for split in splits {
+        out.append(&split?)?;
+    }
+    Ok(out.into_series())
+}
+
+/// Applies `native_fn` to the string of `inputs[0]` against every element
+/// of the `List[String]` of `inputs[1]` in the same row and reduces the
+/// scores with `agg`. `inputs[0]` may be a single broadcast value. Rows
+/// where the list is null, or that have no scores, yield null; null strings
+/// and elements are resolved like in the pairwise expressions.
+pub fn parallel_apply_str_with_candidates<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    agg: ScoreAggregation,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> f64 + Sync + Send,
+{
+    let a = string_values(&inputs[0])?;
+    let a = a.as_ref();
+    let candidates = inputs[1].list()?;
+    if a.len() != candidates.len() && a.len() != 1 {
+        return Err(PolarsError::ShapeMismatch(
+            "The string input must have the same length as the candidates, or be a single value."
+                .into(),
+        ));
+    }
+
+    let splits = map_splits(
+        layout_chunks([candidates], candidates.len()),
+        context,
+        |offset, len| {
+            let a = slice_rows(a, offset, len);
+            let candidates = candidates.slice(offset as i64, len);
+            rows(&a, len)
+                .zip(&candidates)
+                .map(|(a, candidates)| {
+                    let Some(candidates) = candidates else {
+                        return Ok(None);
                     };
-                    out.downcast_iter().cloned().collect::<Vec<_>>()
+                    let scores = candidates.str()?.into_iter().filter_map(|b| {
+                        match apply.resolve_nulls(a, b) {
+                            Ok((a, b)) => apply.score(|| native_fn(a, b)),
+                            Err(fixed) => fixed,
+                        }
+                    });
+                    Ok(agg.aggregate(scores))
                 })
-                .collect();
-            Ok(
-                ChunkedArray::<Out>::from_chunk_iter("".into(), chunks.into_iter().flatten())
-                    .into_series(),
-            )
+                .collect::<PolarsResult<Float64Chunked>>()
+        },
+    );
+
+    let mut chunks = Vec::new();
+    for split in splits {
+        chunks.extend(split?.downcast_iter().cloned());
+    }
+    Ok(Float64Chunked::from_chunk_iter("".into(), chunks).into_series())
+}
+
+/// Reduces every row of a `List[Float64]` of scores with `agg`, ignoring
+/// null scores. Null rows and rows without scores yield null.
+pub fn aggregate_list_scores(scores: &Series, agg: ScoreAggregation) -> PolarsResult<Series> {
+    let out: Float64Chunked = scores
+        .list()?
+        .into_iter()
+        .map(|row| -> PolarsResult<Option<f64>> {
+            match row {
+                Some(row) => Ok(agg.aggregate(row.f64()?.into_iter().flatten())),
+                None => Ok(None),
+            }
         })
+        .collect::<PolarsResult<_>>()?;
+    Ok(out.into_series())
+}
+
+/// Folds every pair where both strings are non-null into a per-split
+/// accumulator created by `init`, without materializing a per-row output.
+/// The split accumulators are returned in order for the caller to merge.
+pub fn parallel_fold<T, I, F>(
+    inputs: &[Series],
+    context: CallerContext,
+    init: I,
+    fold: F,
+) -> PolarsResult<Vec<T>>
+where
+    T: Send,
+    I: Fn() -> T + Sync + Send,
+    F: Fn(&mut T, &str, &str) + Sync + Send,
+{
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    Ok(map_splits(
+        layout_chunks([a, b], rows_len),
+        context,
+        |offset, len| {
+            let a = slice_rows(a, offset, len);
+            let b = slice_rows(b, offset, len);
+            let mut acc = init();
+            for (a, b) in rows(&a, len).zip(rows(&b, len)) {
+                if let (Some(a), Some(b)) = (a, b) {
+                    fold(&mut acc, a, b);
+                }
+            }
+            acc
+        },
+    ))
+}
+
+/// Folds every non-null string of `inputs[0]` and `inputs[1]` (each side on
+/// its own) into a per-split accumulator, e.g. to gather corpus statistics
+/// before scoring. The split accumulators are returned in order.
+pub fn parallel_fold_strings<T, I, F>(
+    inputs: &[Series],
+    context: CallerContext,
+    init: I,
+    fold: F,
+) -> PolarsResult<Vec<T>>
+where
+    T: Send,
+    I: Fn() -> T + Sync + Send,
+    F: Fn(&mut T, &str) + Sync + Send,
+{
+    let (a, b, rows_len) = string_inputs(inputs)?;
+    let (a, b) = (a.as_ref(), b.as_ref());
+    Ok(map_splits(
+        layout_chunks([a, b], rows_len),
+        context,
+        |offset, len| {
+            let mut acc = init();
+            // Slicing keeps a broadcast single value in the first split only,
+            // so it is folded once.
+            for side in [a, b] {
+                for s in side.slice(offset as i64, len).iter().flatten() {
+                    fold(&mut acc, s);
+                }
+            }
+            acc
+        },
+    ))
+}
+
+/// Scores every row of `inputs[0]` against each later row, returning per
+/// row the list of its scores against rows `i + 1..`, so the lists
+/// concatenated form the condensed upper triangle of the pairwise matrix.
+/// Rows are scheduled individually, since their lengths shrink along the
+/// column. Pairs with a null side score null.
+pub fn parallel_apply_upper_triangle<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> f64 + Sync + Send,
+{
+    let a = string_values(&inputs[0])?;
+    let a: Vec<Option<&str>> = a.iter().collect();
+    let row_scores = |i: usize| -> Vec<Option<f64>> {
+        a[i + 1..]
+            .iter()
+            .map(|&b| match apply.resolve_nulls(a[i], b) {
+                Ok((a, b)) => apply.score(|| native_fn(a, b)),
+                Err(fixed) => fixed,
+            })
+            .collect()
+    };
+    let rows: Vec<Vec<Option<f64>>> = if context.parallel() {
+        (0..a.len()).map(row_scores).collect()
+    } else {
+        let pool = dedicated_pool().unwrap_or(&POOL);
+        pool.install(|| (0..a.len()).into_par_iter().map(row_scores).collect())
+    };
+    let out: ListChunked = rows
+        .into_iter()
+        .map(|row| Some(Series::new("".into(), row)))
+        .collect();
+    Ok(out.into_series())
+}
+
+/// Finds the matches of every non-null string of `a` with `native_fn`,
+/// which gets the row's string and position and returns the matched
+/// positions on the other side with their scores. Returns `(left, right,
+/// score)` triples ordered by left row; rows that run out of time
+/// contribute no matches.
+pub fn parallel_find_matches<F>(
+    a: &StringChunked,
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> Vec<(u32, u32, f64)>
+where
+    F: Fn(&str, u32) -> Vec<(u32, f64)> + Sync + Send,
+{
+    let splits = map_splits(layout_chunks([a], a.len()), context, |offset, len| {
+        let mut matches = Vec::new();
+        for (i, s) in a.slice(offset as i64, len).iter().enumerate() {
+            let Some(s) = s else { continue };
+            let left = (offset + i) as u32;
+            let found = apply.within_budget(|| native_fn(s, left));
+            matches.extend(
+                found
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(right, score)| (left, right, score)),
+            );
+        }
+        matches
+    });
+    splits.into_iter().flatten().collect()
+}
+
+/// `parallel_find_matches` over `inputs[0]`, returned as a struct with
+/// fields `left_index`, `right_index` and `score`.
+pub fn parallel_apply_matches<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, u32) -> Vec<(u32, f64)> + Sync + Send,
+{
+    let matches = parallel_find_matches(
+        string_values(&inputs[0])?.as_ref(),
+        context,
+        apply,
+        native_fn,
+    );
+    let left_index: Vec<u32> = matches.iter().map(|m| m.0).collect();
+    let right_index: Vec<u32> = matches.iter().map(|m| m.1).collect();
+    let scores: Vec<f64> = matches.iter().map(|m| m.2).collect();
+    let fields = [
+        Series::new("left_index".into(), left_index),
+        Series::new("right_index".into(), right_index),
+        Series::new("score".into(), scores),
+    ];
+    Ok(StructChunked::from_series("".into(), matches.len(), fields.iter())?.into_series())
+}
+
+/// Warm-start variant of `parallel_apply`: `inputs[2]` holds the previous
+/// run's scores and `inputs[3]` a boolean "changed" mask. Rows whose mask is
+/// `false` and that have a previous score carry that score forward; every
+/// other row (changed, unknown or never scored) is recomputed with `native_fn`.
+pub fn parallel_apply_warm_start<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> f64 + Sync + Send,
+{
+    let a = string_values(&inputs[0])?;
+    let a = a.as_ref();
+    let b = string_values(&inputs[1])?;
+    let b = b.as_ref();
+    let previous = inputs[2].cast(&DataType::Float64)?;
+    let previous = previous.f64()?;
+    let changed = inputs[3].bool()?;
+    if a.len() != b.len() || a.len() != previous.len() || a.len() != changed.len() {
+        return Err(PolarsError::ShapeMismatch(
+            "Inputs, previous scores and changed mask must all have the same length.".into(),
+        ));
     }
+    let out: Float64Chunked =
+        apply_in_splits(layout_chunks([a], a.len()), context, |offset, len| {
+            let a = a.slice(offset as i64, len);
+            let b = b.slice(offset as i64, len);
+            let previous = previous.slice(offset as i64, len);
+            let changed = changed.slice(offset as i64, len);
+            a.iter()
+                .zip(b.iter())
+                .zip(previous.iter())
+                .zip(changed.iter())
+                .map(|(((a, b), previous), changed)| match (changed, previous) {
+                    (Some(false), Some(previous)) => Some(previous),
+                    _ => match apply.resolve_nulls(a, b) {
+                        Ok((a, b)) => apply.score(|| native_fn(a, b)),
+                        Err(fixed) => fixed,
+                    },
+                })
+                .collect()
+        });
+    Ok(out.into_series())
 }
@@ -0,0 +1,118 @@
+// Arabic name normalization
+
+/// Folds the orthographic variants of Arabic script that do not change how a
+/// name is read: hamza-carrying alef forms and alef wasla become a bare alef,
+/// hamza on waw/ya becomes the bare letter, alef maqsura becomes ya and ta
+/// marbuta becomes ha. Harakat (short-vowel marks) and tatweel are dropped.
+fn fold_arabic_char(c: char) -> Option<char> {
+    match c {
+        'أ' | 'إ' | 'آ' | 'ٱ' => Some('ا'),
+        'ؤ' => Some('و'),
+        'ئ' | 'ى' => Some('ي'),
+        'ة' => Some('ه'),
+        'ـ' | '\u{064B}'..='\u{0652}' | '\u{0670}' => None,
+        _ => Some(c),
+    }
+}
+
+/// Spelling variants of frequent names that survive the romanization
+/// skeleton below, keyed by skeleton and mapped to a single canonical form.
+const NAME_VARIANTS: &[(&str, &str)] = &[
+    ("muhamid", "muhamad"),
+    ("muhamud", "muhamad"),
+    ("mhd", "muhamad"),
+    ("ahmid", "ahmad"),
+    ("husin", "husain"),
+    ("husayn", "husain"),
+    ("yusif", "yusuf"),
+    ("usman", "uthman"),
+    ("abd", "abdul"),
+    ("abdil", "abdul"),
+    ("abdala", "abdula"),
+    ("abdila", "abdula"),
+];
+
+/// Reduces one lowercase romanized token to a skeleton that merges the most
+/// common transliteration choices: "dj" -> "j", "ph" -> "f", "q" -> "k",
+/// "ou"/"oo" -> "u", "ee" -> "i", doubled letters collapse, and "o"/"e" are
+/// read as "u"/"i". A trailing "h" after a vowel ("Fatimah") is dropped.
+fn romanized_skeleton(token: &str) -> String {
+    let token = token
+        .replace("dj", "j")
+        .replace("ph", "f")
+        .replace("ou", "u")
+        .replace("oo", "u")
+        .replace("ee", "i")
+        .replace('q', "k");
+
+    let mut skeleton = String::with_capacity(token.len());
+    let mut previous = None;
+    for c in token.chars() {
+        let c = match c {
+            'o' => 'u',
+            'e' => 'i',
+            _ => c,
+        };
+        if previous != Some(c) {
+            skeleton.push(c);
+        }
+        previous = Some(c);
+    }
+
+    if skeleton.len() > 2 && skeleton.ends_with('h') {
+        let before = skeleton[..skeleton.len() - 1].chars().last();
+        if matches!(before, Some('a' | 'i' | 'u')) {
+            skeleton.pop();
+        }
+    }
+
+    NAME_VARIANTS
+        .iter()
+        .find(|(variant, _)| *variant == skeleton)
+        .map(|(_, canonical)| canonical.to_string())
+        .unwrap_or(skeleton)
+}
+
+/// Normalizes a name written in Arabic script and/or a Latin romanization so
+/// that common spelling variants compare equal: Arabic letters are folded
+/// (see `fold_arabic_char`), Latin tokens are lowercased and reduced to a
+/// romanization skeleton (tokens in any other script are only
+/// lowercased), and the article variants "al-", "el-", "ul-" and a
+/// detached "al"/"el" are merged into the following token as "al".
+pub fn normalize_arabic_name(s: &str) -> String {
+    let folded: String = s.chars().filter_map(fold_arabic_char).collect();
+
+    let mut tokens: Vec<String> = Vec::new();
+    let mut pending_article = false;
+    for raw in folded.split_whitespace() {
+        let lower = raw.to_lowercase();
+        if matches!(lower.as_str(), "al" | "el" | "ul") {
+            pending_article = true;
+            continue;
+        }
+
+        let (article, rest) = match lower.split_once('-') {
+            Some(("al" | "el" | "ul", rest)) if !rest.is_empty() => (true, rest),
+            _ => (false, lower.as_str()),
+        };
+        let skeleton = if rest.is_ascii() {
+            romanized_skeleton(rest)
+        } else {
+            rest.to_string()
+        };
+        // "Abd al-Rahman" is usually romanized "Abdul Rahman"/"Abdel Rahman",
+        // where the article is already part of "abdul".
+        let after_abdul = tokens.last().is_some_and(|last| last == "abdul");
+        if (article || pending_article) && !after_abdul {
+            tokens.push(format!("al{skeleton}"));
+        } else {
+            tokens.push(skeleton);
+        }
+        pending_article = false;
+    }
+    if pending_article {
+        tokens.push("al".to_string());
+    }
+
+    tokens.join(" ")
+}
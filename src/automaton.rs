@@ -0,0 +1,116 @@
+// Levenshtein automata intersected with a dictionary trie
+
+use crate::deadline;
+
+/// Levenshtein automaton accepting the strings within `max_distance` edits
+/// of a query. A state is one row of the Levenshtein DP against the query,
+/// with entries capped at `max_distance + 1`, so there are finitely many
+/// states and two prefixes reaching the same row accept the same suffixes.
+pub struct LevenshteinAutomaton {
+    query: Vec<char>,
+    max_distance: usize,
+}
+
+impl LevenshteinAutomaton {
+    pub fn new(query: &str, max_distance: usize) -> LevenshteinAutomaton {
+        LevenshteinAutomaton {
+            query: query.chars().collect(),
+            max_distance,
+        }
+    }
+
+    pub fn start(&self) -> Vec<usize> {
+        (0..=self.query.len())
+            .map(|j| j.min(self.max_distance + 1))
+            .collect()
+    }
+
+    pub fn step(&self, state: &[usize], c: char) -> Vec<usize> {
+        let mut next = Vec::with_capacity(state.len());
+        next.push((state[0] + 1).min(self.max_distance + 1));
+        for (j, &q) in self.query.iter().enumerate() {
+            let distance = (state[j] + usize::from(q != c))
+                .min(state[j + 1] + 1)
+                .min(next[j] + 1)
+                .min(self.max_distance + 1);
+            next.push(distance);
+        }
+        next
+    }
+
+    /// Whether the input read so far is accepted.
+    pub fn accepts(&self, state: &[usize]) -> bool {
+        state[self.query.len()] <= self.max_distance
+    }
+
+    /// Whether some continuation of the input can still be accepted.
+    pub fn can_accept(&self, state: &[usize]) -> bool {
+        state.iter().any(|&distance| distance <= self.max_distance)
+    }
+}
+
+#[derive(Default)]
+struct TrieNode {
+    /// Children by character, sorted.
+    children: Vec<(char, u32)>,
+    /// Positions of the dictionary words ending here.
+    words: Vec<u32>,
+}
+
+/// Character trie over a dictionary, traversed in sorted order.
+pub struct Trie {
+    nodes: Vec<TrieNode>,
+}
+
+impl Trie {
+    /// Builds the trie over `words`, keeping their positions.
+    pub fn new<'a>(words: impl Iterator<Item = &'a str>) -> Trie {
+        let mut trie = Trie {
+            nodes: vec![TrieNode::default()],
+        };
+        for (position, word) in words.enumerate() {
+            let mut current = 0;
+            for c in word.chars() {
+                current = match trie.nodes[current]
+                    .children
+                    .binary_search_by_key(&c, |&(child_char, _)| child_char)
+                {
+                    Ok(i) => trie.nodes[current].children[i].1 as usize,
+                    Err(i) => {
+                        let child = trie.nodes.len();
+                        trie.nodes.push(TrieNode::default());
+                        trie.nodes[current].children.insert(i, (c, child as u32));
+                        child
+                    }
+                };
+            }
+            trie.nodes[current].words.push(position as u32);
+        }
+        trie
+    }
+
+    /// Positions of the words `automaton` accepts, in sorted word order.
+    /// Subtries where the automaton can no longer accept are never
+    /// entered. Past the deadline the words found so far are returned.
+    pub fn search(&self, automaton: &LevenshteinAutomaton) -> Vec<u32> {
+        let mut found = Vec::new();
+        let mut stack = vec![(0_usize, automaton.start())];
+        while let Some((current, state)) = stack.pop() {
+            if deadline::exceeded() {
+                break;
+            }
+            let node = &self.nodes[current];
+            if automaton.accepts(&state) {
+                found.extend_from_slice(&node.words);
+            }
+            // Pushed in reverse so children pop in sorted order.
+            for &(c, child) in node.children.iter().rev() {
+                let next = automaton.step(&state, c);
+                if automaton.can_accept(&next) {
+                    stack.push((child as usize, next));
+                }
+            }
+        }
+        found
+    }
+}
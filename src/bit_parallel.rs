@@ -0,0 +1,264 @@
+// bit-parallel (Myers / Hyyrö) edit distances
+
+use crate::buffers::with_buffers;
+use crate::ngram;
+use crate::weighted_DL;
+use std::collections::HashMap;
+
+/// Match masks of a pattern: bit `i % 64` of word `i / 64` of the mask of
+/// `c` is set when the `i`-th character of the pattern is `c`.
+struct PatternMasks<'a> {
+    words: usize,
+    /// `words` masks per ASCII character.
+    ascii: &'a [u64],
+    other: HashMap<char, Vec<u64>>,
+}
+
+impl<'a> PatternMasks<'a> {
+    /// The masks of `pattern`, with the ASCII ones in `ascii`, a pooled
+    /// buffer.
+    fn new(pattern: &[char], ascii: &'a mut Vec<u64>) -> PatternMasks<'a> {
+        let words = pattern.len().div_ceil(64);
+        ascii.resize(128 * words, 0);
+        let mut other: HashMap<char, Vec<u64>> = HashMap::new();
+        for (i, &c) in pattern.iter().enumerate() {
+            let (word, bit) = (i / 64, 1_u64 << (i % 64));
+            if c.is_ascii() {
+                ascii[c as usize * words + word] |= bit;
+            } else {
+                other.entry(c).or_insert_with(|| vec![0; words])[word] |= bit;
+            }
+        }
+        PatternMasks {
+            words,
+            ascii,
+            other,
+        }
+    }
+
+    fn get(&self, word: usize, c: char) -> u64 {
+        if c.is_ascii() {
+            self.ascii[c as usize * self.words + word]
+        } else {
+            self.other.get(&c).map_or(0, |masks| masks[word])
+        }
+    }
+}
+
+/// The vertical state of one 64-row block of the DP after a column.
+#[derive(Clone, Copy)]
+struct Block {
+    /// Rows whose value is one more than the row above.
+    vp: u64,
+    /// Rows whose value is one less than the row above.
+    vn: u64,
+    /// Rows whose value equals the diagonal predecessor's.
+    d0: u64,
+    /// The match mask of the column's character.
+    pm: u64,
+}
+
+/// Optimal string alignment distance (Damerau-Levenshtein where no
+/// substring is edited twice) with Hyyrö's extension of Myers' bit-parallel
+/// Levenshtein algorithm: the DP column over the shorter string is held as
+/// bit vectors of 64 rows per word and advanced one character of the longer
+/// string at a time in `O(words)` word operations.
+pub fn osa_distance(a: &[char], b: &[char]) -> usize {
+    let (pattern, text) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if pattern.is_empty() {
+        return text.len();
+    }
+    with_buffers(|[ascii]| {
+        let masks = PatternMasks::new(pattern, ascii);
+        if masks.words == 1 {
+            single_word_osa(&masks, pattern.len(), text)
+        } else {
+            blocked_osa(&masks, pattern.len(), text)
+        }
+    })
+}
+
+fn single_word_osa(masks: &PatternMasks, pattern_len: usize, text: &[char]) -> usize {
+    let last = 1_u64 << (pattern_len - 1);
+    let (mut vp, mut vn, mut d0, mut old_pm) = (!0_u64, 0_u64, 0_u64, 0_u64);
+    let mut distance = pattern_len;
+    for &c in text {
+        let pm = masks.get(0, c);
+        let transpositions = (((!d0) & pm) << 1) & old_pm;
+        d0 = ((((pm & vp).wrapping_add(vp)) ^ vp) | pm | vn) | transpositions;
+        let hp = vn | !(d0 | vp);
+        let hn = d0 & vp;
+        if hp & last != 0 {
+            distance += 1;
+        }
+        if hn & last != 0 {
+            distance -= 1;
+        }
+        let hp = (hp << 1) | 1;
+        let hn = hn << 1;
+        vp = hn | !(d0 | hp);
+        vn = hp & d0;
+        old_pm = pm;
+    }
+    distance
+}
+
+/// `single_word_osa` over several words, carrying the horizontal deltas and
+/// the transpositions across word boundaries.
+fn blocked_osa(masks: &PatternMasks, pattern_len: usize, text: &[char]) -> usize {
+    let words = masks.words;
+    let last = 1_u64 << ((pattern_len - 1) % 64);
+    // Entry 0 stands below the first word; only its zero `d0` and `pm` are
+    // read.
+    let start = Block {
+        vp: !0,
+        vn: 0,
+        d0: 0,
+        pm: 0,
+    };
+    let mut previous = vec![start; words + 1];
+    let mut current = previous.clone();
+    let mut distance = pattern_len;
+    for &c in text {
+        let (mut hp_carry, mut hn_carry) = (1_u64, 0_u64);
+        for word in 0..words {
+            let pm = masks.get(word, c);
+            let Block {
+                vp,
+                vn,
+                d0,
+                pm: old_pm,
+            } = previous[word + 1];
+            let carried = ((!previous[word].d0) & current[word].pm) >> 63;
+            let transpositions = ((((!d0) & pm) << 1) | carried) & old_pm;
+            let x = pm | hn_carry;
+            let d0 = (((x & vp).wrapping_add(vp)) ^ vp) | x | vn | transpositions;
+            let hp = vn | !(d0 | vp);
+            let hn = d0 & vp;
+            if word == words - 1 {
+                if hp & last != 0 {
+                    distance += 1;
+                }
+                if hn & last != 0 {
+                    distance -= 1;
+                }
+            }
+            let shifted_hp = (hp << 1) | hp_carry;
+            let shifted_hn = (hn << 1) | hn_carry;
+            hp_carry = hp >> 63;
+            hn_carry = hn >> 63;
+            current[word + 1] = Block {
+                vp: shifted_hn | !(d0 | shifted_hp),
+                vn: shifted_hp & d0,
+                d0,
+                pm,
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    distance
+}
+
+/// Unrestricted Damerau-Levenshtein distance, through `osa_distance`.
+///
+/// The OSA distance is an upper bound, and it is exact whenever it is at
+/// most 2 (a distance of 1 is always a single edit OSA also sees) or equal
+/// to `ngram::bag_distance`. Otherwise the exact distance is only computed when it
+/// can be below the OSA distance, with a DP bounded by one less.
+pub fn damerau_levenshtein(a: &[char], b: &[char]) -> usize {
+    let osa = osa_distance(a, b);
+    if osa <= 2 || osa == ngram::bag_distance(a, b) {
+        return osa;
+    }
+    weighted_DL::bounded_damerau_levenshtein(a, b, osa - 1).unwrap_or(osa)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random strings over a small alphabet, so
+    /// transpositions and repeated characters are common.
+    fn random_strings(count: usize, max_len: u64, alphabet: &[char]) -> Vec<String> {
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = |n: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % n
+        };
+        (0..count)
+            .map(|_| {
+                let len = next(max_len + 1);
+                (0..len)
+                    .map(|_| alphabet[next(alphabet.len() as u64) as usize])
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    fn assert_matches_strsim(a: &str, b: &str) {
+        let (a_chars, b_chars) = (chars(a), chars(b));
+        assert_eq!(
+            osa_distance(&a_chars, &b_chars),
+            strsim::osa_distance(a, b),
+            "osa {a:?} {b:?}"
+        );
+        assert_eq!(
+            damerau_levenshtein(&a_chars, &b_chars),
+            strsim::damerau_levenshtein(a, b),
+            "damerau_levenshtein {a:?} {b:?}"
+        );
+    }
+
+    #[test]
+    fn transpositions_with_characters_in_between() {
+        // OSA cannot edit the transposed pair again, unrestricted
+        // Damerau-Levenshtein can.
+        assert_eq!(osa_distance(&chars("ca"), &chars("abc")), 3);
+        assert_eq!(damerau_levenshtein(&chars("ca"), &chars("abc")), 2);
+        for (a, b) in [
+            ("ca", "abc"),
+            ("abcdef", "badcfe"),
+            ("a cat", "an act"),
+            ("abxcd", "bacyd"),
+            ("ab", "bxa"),
+        ] {
+            assert_matches_strsim(a, b);
+        }
+    }
+
+    #[test]
+    fn matches_strsim_within_one_word() {
+        let strings = random_strings(200, 20, &['a', 'b', 'c']);
+        for pair in strings.chunks(2) {
+            assert_matches_strsim(&pair[0], &pair[1]);
+        }
+    }
+
+    #[test]
+    fn matches_strsim_across_words() {
+        // Patterns over 64 characters run through `blocked_osa`.
+        let strings = random_strings(120, 200, &['a', 'b', 'c', 'd']);
+        for pair in strings.chunks(2) {
+            assert_matches_strsim(&pair[0], &pair[1]);
+        }
+        let a = "ab".repeat(40);
+        let b = "ba".repeat(40);
+        assert!(chars(&a).len() > 64);
+        assert_matches_strsim(&a, &b);
+        assert_matches_strsim(&a, &format!("x{b}y"));
+    }
+
+    #[test]
+    fn matches_strsim_for_non_ascii() {
+        let strings = random_strings(120, 140, &['a', 'é', '中', '👍']);
+        for pair in strings.chunks(2) {
+            assert_matches_strsim(&pair[0], &pair[1]);
+        }
+    }
+}
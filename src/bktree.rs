@@ -0,0 +1,138 @@
+// BK-tree over a vocabulary for bounded nearest-term lookups
+
+use crate::bit_parallel;
+use crate::buffers::InlineChars;
+use crate::deadline;
+use crate::weighted_DL;
+
+struct Node {
+    /// Vocabulary positions of the node's term, in insertion order; equal
+    /// terms share a node.
+    terms: Vec<usize>,
+    /// `(distance to this node's term, child node)` pairs, one per distance.
+    children: Vec<(usize, usize)>,
+    /// The largest distance among `children`.
+    max_child_distance: usize,
+}
+
+/// Burkhard-Keller tree keyed by the unrestricted Damerau-Levenshtein
+/// distance, which is a metric: by the triangle inequality only the
+/// subtrees whose edge distance is within `max_distance` of the query's
+/// distance to their parent can hold matches, so a bounded lookup visits a
+/// small part of a large vocabulary.
+pub struct BkTree {
+    terms: Vec<Vec<char>>,
+    nodes: Vec<Node>,
+}
+
+impl BkTree {
+    /// Builds the tree over `terms`, keeping vocabulary positions; a term
+    /// equal to an earlier one joins that term's node, so lookups return
+    /// every position.
+    pub fn new<'a>(terms: impl Iterator<Item = &'a str>) -> BkTree {
+        let mut tree = BkTree {
+            terms: Vec::new(),
+            nodes: Vec::new(),
+        };
+        for term in terms {
+            tree.insert(term.chars().collect());
+        }
+        tree
+    }
+
+    fn insert(&mut self, term: Vec<char>) {
+        let position = self.terms.len();
+        self.terms.push(term);
+        if self.nodes.is_empty() {
+            self.nodes.push(Node {
+                terms: vec![position],
+                children: Vec::new(),
+                max_child_distance: 0,
+            });
+            return;
+        }
+        let term = &self.terms[position];
+        let mut current = 0;
+        loop {
+            let node = &self.nodes[current];
+            let distance = bit_parallel::damerau_levenshtein(&self.terms[node.terms[0]], term);
+            if distance == 0 {
+                self.nodes[current].terms.push(position);
+                return;
+            }
+            match node.children.iter().find(|(edge, _)| *edge == distance) {
+                Some(&(_, child)) => current = child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node {
+                        terms: vec![position],
+                        children: Vec::new(),
+                        max_child_distance: 0,
+                    });
+                    let node = &mut self.nodes[current];
+                    node.children.push((distance, child));
+                    node.max_child_distance = node.max_child_distance.max(distance);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// The vocabulary positions and distances of every term within
+    /// `max_distance` of `query`, nearest first, earlier terms first on ties.
+    /// Past the deadline the terms found so far are returned.
+    pub fn within(&self, query: &str, max_distance: usize) -> Vec<(usize, usize)> {
+        let query: InlineChars = query.chars().collect();
+        let mut found = Vec::new();
+        let mut stack: Vec<usize> = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        while let Some(current) = stack.pop() {
+            if deadline::exceeded() {
+                break;
+            }
+            let node = &self.nodes[current];
+            // Past this bound neither the node nor any child can match.
+            let bound = node.max_child_distance + max_distance;
+            let Some(distance) =
+                weighted_DL::bounded_damerau_levenshtein(&query, &self.terms[node.terms[0]], bound)
+            else {
+                continue;
+            };
+            if distance <= max_distance {
+                found.extend(node.terms.iter().map(|&term| (term, distance)));
+            }
+            stack.extend(
+                node.children
+                    .iter()
+                    .filter(|(edge, _)| edge.abs_diff(distance) <= max_distance)
+                    .map(|&(_, child)| child),
+            );
+        }
+        found.sort_unstable_by_key(|&(term, distance)| (distance, term));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BkTree;
+
+    #[test]
+    fn equal_terms_keep_every_position() {
+        let tree = BkTree::new(["apple", "ample", "apple", "apply"].into_iter());
+        assert_eq!(tree.within("apple", 0), vec![(0, 0), (2, 0)]);
+        assert_eq!(
+            tree.within("apple", 1),
+            vec![(0, 0), (2, 0), (1, 1), (3, 1)]
+        );
+    }
+
+    #[test]
+    fn equal_first_term_keeps_every_position() {
+        let tree = BkTree::new(["apple", "apple"].into_iter());
+        assert_eq!(tree.within("appel", 1), vec![(0, 1), (1, 1)]);
+    }
+}
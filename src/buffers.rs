@@ -0,0 +1,78 @@
+// thread-local scratch buffers reused across rows
+
+use smallvec::SmallVec;
+use std::cell::RefCell;
+use std::thread::LocalKey;
+
+/// Buffers longer than this are freed instead of kept, so one huge pair
+/// does not pin its memory to the thread for the rest of the process.
+const MAX_RETAINED_LEN: usize = 1 << 20;
+
+/// Strings of at most this many bytes (and so chars) are decoded on the
+/// stack.
+pub const INLINE_CHARS: usize = 32;
+
+/// Decoded chars kept inline up to `INLINE_CHARS` and spilled to the heap
+/// past it.
+pub type InlineChars = SmallVec<[char; INLINE_CHARS]>;
+
+/// Element types with a per-thread pool of vectors.
+pub trait Pooled: Sized + 'static {
+    fn pool() -> &'static LocalKey<RefCell<Vec<Vec<Self>>>>;
+}
+
+macro_rules! pooled {
+    ($elem:ty, $pool:ident) => {
+        thread_local! {
+            static $pool: RefCell<Vec<Vec<$elem>>> = const { RefCell::new(Vec::new()) };
+        }
+
+        impl Pooled for $elem {
+            fn pool() -> &'static LocalKey<RefCell<Vec<Vec<$elem>>>> {
+                &$pool
+            }
+        }
+    };
+}
+
+pooled!(f64, F64_BUFFERS);
+pooled!(usize, USIZE_BUFFERS);
+pooled!(u64, U64_BUFFERS);
+pooled!(char, CHAR_BUFFERS);
+
+/// Runs `f` with `N` empty vectors taken from the current thread's pool and
+/// returns them afterwards, so the DP matrices and decoded strings of one
+/// row reuse the capacity of the previous rows on the same worker thread
+/// instead of allocating. Nested calls take distinct vectors.
+pub fn with_buffers<T: Pooled, const N: usize, R>(f: impl FnOnce([&mut Vec<T>; N]) -> R) -> R {
+    let mut buffers: [Vec<T>; N] =
+        T::pool().with_borrow_mut(|pool| std::array::from_fn(|_| pool.pop().unwrap_or_default()));
+    for buffer in &mut buffers {
+        buffer.clear();
+    }
+    let result = f(buffers.each_mut());
+    T::pool().with_borrow_mut(|pool| {
+        pool.extend(
+            buffers
+                .into_iter()
+                .filter(|buffer| buffer.capacity() <= MAX_RETAINED_LEN),
+        )
+    });
+    result
+}
+
+/// Runs `f` with the chars of `a` and `b` decoded into stack buffers when
+/// both fit in `INLINE_CHARS`, as most names and codes do, and into pooled
+/// buffers otherwise, so neither path touches the allocator.
+pub fn with_chars<R>(a: &str, b: &str, f: impl FnOnce(&[char], &[char]) -> R) -> R {
+    if a.len() <= INLINE_CHARS && b.len() <= INLINE_CHARS {
+        let a_chars: InlineChars = a.chars().collect();
+        let b_chars: InlineChars = b.chars().collect();
+        return f(&a_chars, &b_chars);
+    }
+    with_buffers(|[a_chars, b_chars]: [&mut Vec<char>; 2]| {
+        a_chars.extend(a.chars());
+        b_chars.extend(b.chars());
+        f(a_chars, b_chars)
+    })
+}
@@ -0,0 +1,83 @@
+// progressive scoring: cheap metric first, expensive metric near the threshold
+
+use crate::buffers::with_chars;
+use crate::ngram;
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum CheapMetric {
+    /// Jaccard similarity of the character q-gram sets.
+    QgramJaccard,
+    /// `1 - bag_distance / max(len)`.
+    Bag,
+}
+
+/// Cascade settings: pairs whose cheap similarity falls below `lower` or
+/// above `upper` are decided without the expensive metric and score `below`
+/// or `above` (null when unset); only pairs inside `[lower, upper]` reach it.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Cascade {
+    #[serde(default = "default_cheap_metric")]
+    metric: CheapMetric,
+    #[serde(default = "default_q")]
+    q: usize,
+    lower: f64,
+    upper: f64,
+    #[serde(default)]
+    below: Option<f64>,
+    #[serde(default)]
+    above: Option<f64>,
+}
+
+fn default_cheap_metric() -> CheapMetric {
+    CheapMetric::QgramJaccard
+}
+
+fn default_q() -> usize {
+    2
+}
+
+impl Cascade {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.q == 0 {
+            return Err("cascade q must be positive.".into());
+        }
+        if self.lower > self.upper {
+            return Err("cascade lower must not be greater than upper.".into());
+        }
+        Ok(())
+    }
+
+    /// Cheap similarity of `a` and `b` in `[0, 1]`.
+    pub fn cheap_similarity(&self, a: &str, b: &str) -> f64 {
+        match self.metric {
+            CheapMetric::QgramJaccard => ngram::qgram_jaccard(a, b, self.q),
+            CheapMetric::Bag => with_chars(a, b, |a, b| {
+                let max_len = a.len().max(b.len());
+                if max_len == 0 {
+                    return 1.0;
+                }
+                1.0 - ngram::bag_distance(a, b) as f64 / max_len as f64
+            }),
+        }
+    }
+
+    /// Wraps the expensive `native_fn` so it only runs for pairs inside the
+    /// ambiguous band.
+    pub fn wrap<F>(self, native_fn: F) -> impl Fn(&str, &str) -> Option<f64> + Sync + Send
+    where
+        F: Fn(&str, &str) -> f64 + Sync + Send,
+    {
+        move |a, b| {
+            let cheap = self.cheap_similarity(a, b);
+            if cheap < self.lower {
+                self.below
+            } else if cheap > self.upper {
+                self.above
+            } else {
+                Some(native_fn(a, b))
+            }
+        }
+    }
+}
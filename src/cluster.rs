@@ -0,0 +1,66 @@
+// connected components of similar strings
+
+/// Disjoint sets over `0..len` with path halving and union by size.
+pub struct UnionFind {
+    parent: Vec<u32>,
+    size: Vec<u32>,
+}
+
+impl UnionFind {
+    pub fn new(len: usize) -> UnionFind {
+        UnionFind {
+            parent: (0..len as u32).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    pub fn find(&mut self, mut x: u32) -> u32 {
+        while self.parent[x as usize] != x {
+            let grandparent = self.parent[self.parent[x as usize] as usize];
+            self.parent[x as usize] = grandparent;
+            x = grandparent;
+        }
+        x
+    }
+
+    pub fn union(&mut self, a: u32, b: u32) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        let (small, large) = if self.size[a as usize] < self.size[b as usize] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small as usize] = large;
+        self.size[large as usize] += self.size[small as usize];
+    }
+}
+
+/// Cluster id of every row given the matching `pairs`: the connected
+/// components numbered densely from 0 in order of their first row. Rows
+/// that are not `present` (nulls) get no id.
+pub fn cluster_ids(
+    present: &[bool],
+    pairs: impl IntoIterator<Item = (u32, u32)>,
+) -> Vec<Option<u32>> {
+    let mut components = UnionFind::new(present.len());
+    for (a, b) in pairs {
+        components.union(a, b);
+    }
+    let mut ids = vec![None; present.len()];
+    let mut next_id = 0;
+    for row in 0..present.len() {
+        if !present[row] {
+            continue;
+        }
+        let root = components.find(row as u32) as usize;
+        let id = *ids[root].get_or_insert_with(|| {
+            next_id += 1;
+            next_id - 1
+        });
+        ids[row] = Some(id);
+    }
+    ids
+}
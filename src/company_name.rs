@@ -0,0 +1,110 @@
+// company-name normalization and matching
+
+use crate::fuzz;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Legal-form designations, after folding ("S.A." -> "sa", "GmbH" -> "gmbh").
+const LEGAL_SUFFIXES: &[&str] = &[
+    "ab",
+    "ag",
+    "as",
+    "bv",
+    "co",
+    "company",
+    "corp",
+    "corporation",
+    "gmbh",
+    "inc",
+    "incorporated",
+    "kg",
+    "kk",
+    "limited",
+    "llc",
+    "llp",
+    "lp",
+    "ltd",
+    "nv",
+    "oy",
+    "plc",
+    "pte",
+    "pty",
+    "sa",
+    "sarl",
+    "sas",
+    "spa",
+    "srl",
+];
+
+/// Words that carry no identity in a company name; "&" is read as "and".
+const STOPWORDS: &[&str] = &["a", "an", "and", "of", "the"];
+
+/// The lowercased tokens of `s`, without accents: periods and apostrophes
+/// are dropped so dotted abbreviations and possessives stay one token
+/// ("S.A." -> "sa", "McDonald's" -> "mcdonalds"), "&" becomes "and" and any
+/// other punctuation separates tokens.
+fn folded_tokens(s: &str) -> Vec<String> {
+    let folded: String = s
+        .nfkd()
+        .filter(|&c| !is_combining_mark(c) && !matches!(c, '.' | '\'' | '\u{2019}'))
+        .flat_map(|c| match c {
+            '&' => " and ".chars().collect::<Vec<_>>(),
+            _ => c.to_lowercase().collect(),
+        })
+        .collect();
+    folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reduces company names to the tokens that identify the company.
+pub struct CompanyNameNormalizer {
+    /// Legal suffixes recognized on top of `LEGAL_SUFFIXES`, folded.
+    extra_suffixes: Vec<String>,
+}
+
+impl CompanyNameNormalizer {
+    pub fn new(extra_suffixes: &[String]) -> CompanyNameNormalizer {
+        CompanyNameNormalizer {
+            extra_suffixes: extra_suffixes
+                .iter()
+                .flat_map(|suffix| folded_tokens(suffix))
+                .collect(),
+        }
+    }
+
+    fn is_legal_suffix(&self, token: &str) -> bool {
+        LEGAL_SUFFIXES.contains(&token) || self.extra_suffixes.iter().any(|extra| extra == token)
+    }
+
+    /// The folded tokens of `s` (see `folded_tokens`) without the trailing
+    /// run of legal suffixes and stopwords ("Siemens GmbH & Co. KG" ->
+    /// "siemens") and without stopwords elsewhere, joined with spaces.
+    /// Suffixes are only stripped at the end, so "Co-operative Bank" keeps
+    /// "co". A name made of nothing but suffixes and stopwords is kept whole.
+    pub fn normalize(&self, s: &str) -> String {
+        let tokens = folded_tokens(s);
+        let kept = tokens
+            .iter()
+            .rposition(|token| !self.is_legal_suffix(token) && !STOPWORDS.contains(&token.as_str()))
+            .map_or(0, |last| last + 1);
+        if kept == 0 {
+            return tokens.join(" ");
+        }
+        tokens[..kept]
+            .iter()
+            .filter(|token| !STOPWORDS.contains(&token.as_str()))
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// `fuzz::token_set_ratio` of the normalized names, so word order and a
+    /// name that only adds words to the other ("Acme" vs "Acme Holdings")
+    /// are tolerated.
+    pub fn similarity(&self, a: &str, b: &str) -> f64 {
+        fuzz::token_set_ratio(&self.normalize(a), &self.normalize(b))
+    }
+}
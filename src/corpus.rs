@@ -0,0 +1,90 @@
+// column-level corpus statistics for corpus-aware scorers
+
+use std::collections::HashMap;
+
+/// Lowercased whitespace tokens of `s`.
+pub fn tokens(s: &str) -> Vec<String> {
+    s.split_whitespace().map(str::to_lowercase).collect()
+}
+
+/// Document frequencies of the tokens of a corpus, where every non-null
+/// string of the input columns is one document. Built per split and merged.
+#[derive(Clone, Default)]
+pub struct TokenStats {
+    documents: usize,
+    document_frequency: HashMap<String, usize>,
+}
+
+impl TokenStats {
+    pub fn add_document(&mut self, s: &str) {
+        self.documents += 1;
+        let mut seen = tokens(s);
+        seen.sort_unstable();
+        seen.dedup();
+        for token in seen {
+            *self.document_frequency.entry(token).or_insert(0) += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: TokenStats) {
+        self.documents += other.documents;
+        for (token, count) in other.document_frequency {
+            *self.document_frequency.entry(token).or_insert(0) += count;
+        }
+    }
+
+    /// `ln(N / df)`; tokens unseen in the corpus are treated as occurring once.
+    pub fn idf(&self, token: &str) -> f64 {
+        let df = self
+            .document_frequency
+            .get(token)
+            .copied()
+            .unwrap_or(1)
+            .max(1);
+        (self.documents.max(1) as f64 / df as f64).ln()
+    }
+
+    /// Unit-length TF-IDF vector of `s`, with `ln(tf + 1) * idf` weights.
+    /// Empty when no token carries weight.
+    pub fn tfidf(&self, s: &str) -> Vec<(String, f64)> {
+        let mut tf: HashMap<String, usize> = HashMap::new();
+        for token in tokens(s) {
+            *tf.entry(token).or_insert(0) += 1;
+        }
+        let mut vector: Vec<(String, f64)> = tf
+            .into_iter()
+            .map(|(token, count)| {
+                let weight = ((count + 1) as f64).ln() * self.idf(&token);
+                (token, weight)
+            })
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+        let norm = vector.iter().map(|(_, w)| w * w).sum::<f64>().sqrt();
+        for (_, weight) in &mut vector {
+            *weight /= norm;
+        }
+        vector
+    }
+}
+
+/// Cohen et al.'s Soft-TFIDF: the TF-IDF cosine where a token of `a` also
+/// matches its most similar token of `b` when their Jaro-Winkler similarity
+/// exceeds `threshold`, scaled by that similarity.
+pub fn soft_tfidf(stats: &TokenStats, a: &str, b: &str, threshold: f64) -> f64 {
+    let a_vector = stats.tfidf(a);
+    let b_vector = stats.tfidf(b);
+
+    let mut score = 0.0;
+    for (a_token, a_weight) in &a_vector {
+        let closest = b_vector
+            .iter()
+            .map(|(b_token, b_weight)| (strsim::jaro_winkler(a_token, b_token), b_weight))
+            .max_by(|x, y| x.0.total_cmp(&y.0));
+        if let Some((similarity, b_weight)) = closest {
+            if similarity > threshold {
+                score += a_weight * b_weight * similarity;
+            }
+        }
+    }
+    score.min(1.0)
+}
@@ -0,0 +1,154 @@
+// substitution cost models for custom_cost_damerau_levenshtein
+
+use crate::weighted_DL::SubstitutionScale;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The `cost_model` kwarg: a keyboard layout or OCR confusions.
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum CostModel {
+    Qwerty,
+    Azerty,
+    Qwertz,
+    Ocr,
+}
+
+/// Bundled keyboard layouts.
+#[derive(Clone, Copy, Debug)]
+pub enum KeyboardLayout {
+    /// US ANSI.
+    Qwerty,
+    /// French ISO.
+    Azerty,
+    /// German ISO.
+    Qwertz,
+}
+
+/// Each row as (unshifted keys, shifted keys, offset of the first key in
+/// key widths). A space in the shifted row marks a key without a shifted
+/// character.
+type Rows = [(&'static str, &'static str, f64); 4];
+
+const QWERTY: Rows = [
+    ("`1234567890-=", "~!@#$%^&*()_+", 0.0),
+    ("qwertyuiop[]\\", "QWERTYUIOP{}|", 1.5),
+    ("asdfghjkl;'", "ASDFGHJKL:\"", 1.75),
+    ("zxcvbnm,./", "ZXCVBNM<>?", 2.25),
+];
+
+const AZERTY: Rows = [
+    ("²&é\"'(-è_çà)=", " 1234567890°+", 0.0),
+    ("azertyuiop^$", "AZERTYUIOP¨£", 1.5),
+    ("qsdfghjklmù*", "QSDFGHJKLM%µ", 1.75),
+    ("<wxcvbn,;:!", ">WXCVBN?./§", 1.25),
+];
+
+const QWERTZ: Rows = [
+    ("^1234567890ß´", "°!\"§$%&/()=?`", 0.0),
+    ("qwertzuiopü+", "QWERTZUIOPÜ*", 1.5),
+    ("asdfghjklöä#", "ASDFGHJKLÖÄ'", 1.75),
+    ("<yxcvbnm,.-", ">YXCVBNM;:_", 1.25),
+];
+
+/// Distance (in key widths) at which a substitution costs the full
+/// `sub_cost`; closer keys cost proportionally less.
+const FULL_COST_DISTANCE: f64 = 2.0;
+
+/// Distance assigned to the two characters of one key (e.g. "a" and "A").
+const SHIFT_DISTANCE: f64 = 0.5;
+
+/// Physical key positions of a layout, used to scale substitution costs by
+/// how far apart the two keys are.
+pub struct KeyboardCosts {
+    keys: HashMap<char, (f64, f64)>,
+}
+
+impl KeyboardCosts {
+    pub fn new(layout: KeyboardLayout) -> Self {
+        let rows = match layout {
+            KeyboardLayout::Qwerty => &QWERTY,
+            KeyboardLayout::Azerty => &AZERTY,
+            KeyboardLayout::Qwertz => &QWERTZ,
+        };
+        let mut keys = HashMap::new();
+        for (row, (unshifted, shifted, offset)) in rows.iter().enumerate() {
+            for (col, (lower, upper)) in unshifted.chars().zip(shifted.chars()).enumerate() {
+                let position = (offset + col as f64, row as f64);
+                keys.entry(lower).or_insert(position);
+                if upper != ' ' {
+                    keys.entry(upper).or_insert(position);
+                }
+            }
+        }
+        KeyboardCosts { keys }
+    }
+
+    /// Share of the substitution cost charged for replacing `a` with `b`, in
+    /// `(0, 1]`. Characters missing from the layout cost the full amount.
+    pub fn substitution_scale(&self, a: char, b: char) -> f64 {
+        match (self.keys.get(&a), self.keys.get(&b)) {
+            (Some(&(ax, ay)), Some(&(bx, by))) => {
+                let distance = (ax - bx).hypot(ay - by).max(SHIFT_DISTANCE);
+                (distance / FULL_COST_DISTANCE).min(1.0)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+/// Characters OCR engines commonly mistake for one another; any two
+/// characters of one group are confusable.
+const OCR_GROUPS: [&str; 9] = [
+    "0OoDQ", "1lI|i!", "5Ss", "2Zz", "8B", "6Gb", "9gq", "uv", "ce",
+];
+
+/// Character pairs OCR engines commonly read as a single character.
+const OCR_MERGES: [(char, char, char); 3] = [('r', 'n', 'm'), ('c', 'l', 'd'), ('v', 'v', 'w')];
+
+/// Share of the substitution cost charged for an OCR confusion.
+const OCR_CONFUSION_SCALE: f64 = 0.25;
+
+/// Substitution costs for `cost_model`, resolved once per expression.
+pub enum CharCosts {
+    Keyboard(KeyboardCosts),
+    Ocr(HashMap<char, usize>),
+}
+
+impl CharCosts {
+    pub fn new(model: CostModel) -> Self {
+        match model {
+            CostModel::Qwerty => CharCosts::Keyboard(KeyboardCosts::new(KeyboardLayout::Qwerty)),
+            CostModel::Azerty => CharCosts::Keyboard(KeyboardCosts::new(KeyboardLayout::Azerty)),
+            CostModel::Qwertz => CharCosts::Keyboard(KeyboardCosts::new(KeyboardLayout::Qwertz)),
+            CostModel::Ocr => CharCosts::Ocr(
+                OCR_GROUPS
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(group, chars)| chars.chars().map(move |c| (c, group)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl SubstitutionScale<char> for CharCosts {
+    fn scale(&self, a: &char, b: &char) -> f64 {
+        match self {
+            CharCosts::Keyboard(keyboard) => keyboard.substitution_scale(*a, *b),
+            CharCosts::Ocr(groups) => match (groups.get(a), groups.get(b)) {
+                (Some(a_group), Some(b_group)) if a_group == b_group => OCR_CONFUSION_SCALE,
+                _ => 1.0,
+            },
+        }
+    }
+
+    fn merge_scale(&self, first: &char, second: &char, merged: &char) -> Option<f64> {
+        match self {
+            CharCosts::Keyboard(_) => None,
+            CharCosts::Ocr(_) => OCR_MERGES
+                .contains(&(*first, *second, *merged))
+                .then_some(OCR_CONFUSION_SCALE),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+// cooperative per-row time budgets
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static ROW_DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Whether the row currently being scored on this thread has run out of its
+/// time budget. Long-running native loops poll this between outer iterations
+/// and bail out early; the value they return is then discarded by
+/// `within_budget`. Always `false` when no budget is set.
+pub fn exceeded() -> bool {
+    ROW_DEADLINE.with(|deadline| deadline.get().is_some_and(|d| Instant::now() >= d))
+}
+
+/// Runs `f` with a per-row deadline of `budget` from now and returns `None`
+/// if the deadline passed before `f` finished.
+pub fn within_budget<K>(budget: Duration, f: impl FnOnce() -> K) -> Option<K> {
+    let deadline = Instant::now() + budget;
+    ROW_DEADLINE.with(|d| d.set(Some(deadline)));
+    let result = f();
+    ROW_DEADLINE.with(|d| d.set(None));
+    (Instant::now() < deadline).then_some(result)
+}
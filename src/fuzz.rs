@@ -0,0 +1,114 @@
+// rapidfuzz-compatible scorers
+
+use crate::buffers::InlineChars;
+use crate::deadline;
+use std::collections::BTreeSet;
+
+/// Length of the longest common subsequence of `a` and `b`, computed with a
+/// single rolling row.
+pub fn lcs_len<Elem: Eq>(a: &[Elem], b: &[Elem]) -> usize {
+    if a.is_empty() || b.is_empty() {
+        return 0;
+    }
+
+    let mut row = vec![0usize; b.len() + 1];
+    for a_elem in a {
+        if deadline::exceeded() {
+            break;
+        }
+        let mut diagonal = 0;
+        for (j, b_elem) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if a_elem == b_elem {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Indel distance: the number of insertions and deletions (no substitutions)
+/// needed to turn `a` into `b`.
+pub fn indel_distance<Elem: Eq>(a: &[Elem], b: &[Elem]) -> usize {
+    a.len() + b.len() - 2 * lcs_len(a, b)
+}
+
+/// Normalized Indel similarity in `[0, 1]`, i.e. rapidfuzz's `fuzz.ratio / 100`.
+/// Two empty sequences are considered identical.
+pub fn ratio<Elem: Eq>(a: &[Elem], b: &[Elem]) -> f64 {
+    let total = a.len() + b.len();
+    if total == 0 {
+        return 1.0;
+    }
+    1.0 - indel_distance(a, b) as f64 / total as f64
+}
+
+/// `ratio` over the chars of two strings.
+pub fn str_ratio(a: &str, b: &str) -> f64 {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+    ratio(&a_chars, &b_chars)
+}
+
+/// Whitespace tokens of `s`, sorted and re-joined with single spaces.
+pub fn sorted_tokens(s: &str) -> String {
+    let mut tokens: Vec<&str> = s.split_whitespace().collect();
+    tokens.sort_unstable();
+    tokens.join(" ")
+}
+
+/// The unique whitespace tokens shared by two strings and the ones unique to
+/// each side, every part sorted and re-joined with single spaces.
+pub struct TokenSet {
+    pub intersection: String,
+    pub diff_ab: String,
+    pub diff_ba: String,
+}
+
+pub fn token_set(a: &str, b: &str) -> TokenSet {
+    let a_tokens: BTreeSet<&str> = a.split_whitespace().collect();
+    let b_tokens: BTreeSet<&str> = b.split_whitespace().collect();
+
+    let join = |tokens: Vec<&str>| tokens.join(" ");
+    TokenSet {
+        intersection: join(a_tokens.intersection(&b_tokens).copied().collect()),
+        diff_ab: join(a_tokens.difference(&b_tokens).copied().collect()),
+        diff_ba: join(b_tokens.difference(&a_tokens).copied().collect()),
+    }
+}
+
+/// `ratio` of the two strings after sorting their tokens.
+pub fn token_sort_ratio(a: &str, b: &str) -> f64 {
+    str_ratio(&sorted_tokens(a), &sorted_tokens(b))
+}
+
+/// fuzzywuzzy/rapidfuzz `token_set_ratio`: compares the shared tokens with
+/// each side's shared-plus-unique tokens and keeps the best of the three
+/// pairings. A string whose tokens are a subset of the other scores 1.
+pub fn token_set_ratio(a: &str, b: &str) -> f64 {
+    let set = token_set(a, b);
+    if set.diff_ab.is_empty() || set.diff_ba.is_empty() {
+        return if set.intersection.is_empty() {
+            0.0
+        } else {
+            1.0
+        };
+    }
+
+    let combine = |diff: &str| {
+        if set.intersection.is_empty() {
+            diff.to_string()
+        } else {
+            format!("{} {}", set.intersection, diff)
+        }
+    };
+    let combined_ab = combine(&set.diff_ab);
+    let combined_ba = combine(&set.diff_ba);
+
+    str_ratio(&set.intersection, &combined_ab)
+        .max(str_ratio(&set.intersection, &combined_ba))
+        .max(str_ratio(&combined_ab, &combined_ba))
+}
@@ -0,0 +1,112 @@
+// blocked fuzzy joins
+
+use crate::minhash;
+use crate::ngram;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// How right-side keys are grouped so a left key is only scored against
+/// the right keys sharing a block with it.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Blocking {
+    /// Every pair is scored.
+    None,
+    /// Keys sharing their first `prefix_len` lowercased characters.
+    Prefix,
+    /// Keys sharing at least one lowercased character `q`-gram.
+    Qgram,
+    /// Keys whose MinHash signatures of lowercased character `q`-grams
+    /// agree on every slot of at least one LSH band.
+    Minhash,
+}
+
+/// Inverted index from blocking keys to the positions of the right-side
+/// keys that produce them.
+pub struct BlockIndex {
+    blocking: Blocking,
+    prefix_len: usize,
+    q: usize,
+    lsh_bands: usize,
+    lsh_rows: usize,
+    all: Vec<u32>,
+    blocks: HashMap<String, Vec<u32>>,
+}
+
+impl BlockIndex {
+    /// Indexes `keys` by position; null keys are never candidates.
+    /// `lsh_bands` and `lsh_rows` only apply to `Blocking::Minhash`, whose
+    /// signatures have `lsh_bands * lsh_rows` slots.
+    pub fn new<'a>(
+        blocking: Blocking,
+        prefix_len: usize,
+        q: usize,
+        (lsh_bands, lsh_rows): (usize, usize),
+        keys: impl Iterator<Item = Option<&'a str>>,
+    ) -> BlockIndex {
+        let mut index = BlockIndex {
+            blocking,
+            prefix_len,
+            q,
+            lsh_bands,
+            lsh_rows,
+            all: Vec::new(),
+            blocks: HashMap::new(),
+        };
+        for (position, key) in keys.enumerate() {
+            let Some(key) = key else { continue };
+            let position = position as u32;
+            if blocking == Blocking::None {
+                index.all.push(position);
+                continue;
+            }
+            for block in index.block_keys(key) {
+                index.blocks.entry(block).or_default().push(position);
+            }
+        }
+        index
+    }
+
+    /// The distinct blocks `s` belongs to.
+    fn block_keys(&self, s: &str) -> Vec<String> {
+        let lowercase: String = s.chars().flat_map(char::to_lowercase).collect();
+        match self.blocking {
+            Blocking::None => Vec::new(),
+            Blocking::Prefix => vec![lowercase.chars().take(self.prefix_len).collect()],
+            Blocking::Qgram => {
+                let mut grams: Vec<String> = ngram::qgrams(&lowercase, self.q)
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect();
+                grams.sort_unstable();
+                grams.dedup();
+                grams
+            }
+            Blocking::Minhash => {
+                let signature =
+                    minhash::signature(&lowercase, self.q, self.lsh_bands * self.lsh_rows, 0);
+                minhash::band_keys(&signature, self.lsh_rows)
+                    .into_iter()
+                    .map(|key| format!("{key:016x}"))
+                    .collect()
+            }
+        }
+    }
+
+    /// Positions of the right-side keys sharing a block with `s`, ascending.
+    pub fn candidates(&self, s: &str) -> Vec<u32> {
+        if self.blocking == Blocking::None {
+            return self.all.clone();
+        }
+        let mut candidates: Vec<u32> = self
+            .block_keys(s)
+            .iter()
+            .filter_map(|block| self.blocks.get(block))
+            .flatten()
+            .copied()
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+}
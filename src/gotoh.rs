@@ -0,0 +1,69 @@
+// affine-gap alignment
+
+use crate::deadline;
+
+/// Gotoh's affine-gap edit distance between `a` and `b`.
+///
+/// A run of `L` consecutive insertions (or deletions) costs
+/// `gap_open + (L - 1) * gap_extend`, so one long gap (a missing middle name)
+/// is much cheaper than the same number of scattered edits. Substituting two
+/// different elements costs `mismatch`; matches are free.
+///
+/// Three rolling rows are kept: `matched` ends in a (mis)match, `deleting`
+/// ends in a gap consuming `a`, `inserting` ends in a gap consuming `b`.
+pub fn affine_gap_distance<Elem: Eq>(
+    a: &[Elem],
+    b: &[Elem],
+    gap_open: f64,
+    gap_extend: f64,
+    mismatch: f64,
+) -> f64 {
+    let gap = |len: usize| {
+        if len == 0 {
+            0.0
+        } else {
+            gap_open + (len - 1) as f64 * gap_extend
+        }
+    };
+    if a.is_empty() || b.is_empty() {
+        return gap(a.len().max(b.len()));
+    }
+
+    let width = b.len() + 1;
+    let mut matched = vec![f64::INFINITY; width];
+    let mut deleting = vec![f64::INFINITY; width];
+    let mut inserting = vec![f64::INFINITY; width];
+    matched[0] = 0.0;
+    for (j, cost) in inserting.iter_mut().enumerate().skip(1) {
+        *cost = gap(j);
+    }
+
+    for (i, a_elem) in a.iter().enumerate() {
+        if deadline::exceeded() {
+            return f64::INFINITY;
+        }
+        let mut next_matched = vec![f64::INFINITY; width];
+        let mut next_deleting = vec![f64::INFINITY; width];
+        let mut next_inserting = vec![f64::INFINITY; width];
+        next_deleting[0] = gap(i + 1);
+
+        for (j, b_elem) in b.iter().enumerate() {
+            let substitution = if a_elem == b_elem { 0.0 } else { mismatch };
+            next_matched[j + 1] = matched[j].min(deleting[j]).min(inserting[j]) + substitution;
+            next_deleting[j + 1] = (matched[j + 1] + gap_open)
+                .min(inserting[j + 1] + gap_open)
+                .min(deleting[j + 1] + gap_extend);
+            next_inserting[j + 1] = (next_matched[j] + gap_open)
+                .min(next_deleting[j] + gap_open)
+                .min(next_inserting[j] + gap_extend);
+        }
+
+        matched = next_matched;
+        deleting = next_deleting;
+        inserting = next_inserting;
+    }
+
+    matched[b.len()]
+        .min(deleting[b.len()])
+        .min(inserting[b.len()])
+}
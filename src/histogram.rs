@@ -0,0 +1,53 @@
+// fixed-bucket score histograms
+
+/// Equal-width histogram over `[lower, upper]`. Values below `lower` or above
+/// `upper` are clamped into the first/last bucket; NaNs are skipped.
+#[derive(Clone)]
+pub struct Histogram {
+    lower: f64,
+    upper: f64,
+    counts: Vec<u64>,
+}
+
+impl Histogram {
+    pub fn new(bins: usize, lower: f64, upper: f64) -> Self {
+        Histogram {
+            lower,
+            upper,
+            counts: vec![0; bins],
+        }
+    }
+
+    pub fn add(&mut self, value: f64) {
+        if value.is_nan() || self.counts.is_empty() {
+            return;
+        }
+        let bins = self.counts.len();
+        let position = (value - self.lower) / (self.upper - self.lower) * bins as f64;
+        let bucket = if position.is_finite() {
+            (position.max(0.0) as usize).min(bins - 1)
+        } else if value > self.lower {
+            bins - 1
+        } else {
+            0
+        };
+        self.counts[bucket] += 1;
+    }
+
+    pub fn merge(&mut self, other: &Histogram) {
+        for (count, other_count) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other_count;
+        }
+    }
+
+    /// Upper edge of every bucket.
+    pub fn breakpoints(&self) -> Vec<f64> {
+        let bins = self.counts.len();
+        let width = (self.upper - self.lower) / bins as f64;
+        (1..=bins).map(|i| self.lower + width * i as f64).collect()
+    }
+
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+}
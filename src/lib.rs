@@ -1,3 +1,31 @@
+pub mod acronym;
+pub mod address;
+pub mod alignment;
 pub mod apply_utils;
+pub mod arabic;
+pub mod automaton;
+pub mod bit_parallel;
+pub mod bktree;
+pub mod buffers;
+pub mod cascade;
+pub mod cluster;
+pub mod company_name;
+pub mod corpus;
+pub mod cost_model;
+pub mod deadline;
+pub mod fuzz;
+pub mod fuzzy_join;
+pub mod gotoh;
+pub mod histogram;
+pub mod minhash;
+pub mod ngram;
+pub mod partial;
+pub mod person_name;
+pub mod phonetic;
+pub mod preprocess;
+pub mod preprocess_cache;
+pub mod segment;
 pub mod strsim_func_wrapper;
+pub mod subsequence;
+pub mod transliterate;
 pub mod weighted_DL;
@@ -0,0 +1,61 @@
+// MinHash signatures of character shingles
+
+use crate::ngram;
+
+/// 64-bit FNV-1a, stable across platforms and runs so signatures can be
+/// stored and compared later.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The SplitMix64 finalizer, used to derive one independent hash function
+/// per signature slot from a single shingle hash.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// The MinHash signature of the character `shingle_size`-grams of `s`
+/// (`ngram::qgrams`): for each of `num_hashes` hash functions derived from
+/// `seed`, the least hash of any shingle. A string without shingles has
+/// every slot at `u64::MAX`.
+pub fn signature(s: &str, shingle_size: usize, num_hashes: usize, seed: u64) -> Vec<u64> {
+    let seeds: Vec<u64> = (0..num_hashes as u64).map(|i| mix(seed ^ mix(i))).collect();
+    let mut signature = vec![u64::MAX; num_hashes];
+    for shingle in ngram::qgrams(s, shingle_size) {
+        let hash = fnv1a(shingle.as_bytes());
+        for (slot, &seed) in signature.iter_mut().zip(&seeds) {
+            *slot = (*slot).min(mix(hash ^ seed));
+        }
+    }
+    signature
+}
+
+/// One key per band of `rows` consecutive slots of `signature`, hashing
+/// the band's position together with its slots: two signatures share a key
+/// when they agree on every slot of the same band.
+pub fn band_keys(signature: &[u64], rows: usize) -> Vec<u64> {
+    signature
+        .chunks(rows)
+        .enumerate()
+        .map(|(band, slots)| {
+            slots
+                .iter()
+                .fold(mix(band as u64), |key, &slot| mix(key ^ slot))
+        })
+        .collect()
+}
+
+/// The fraction of slots where two signatures of the same length agree, an
+/// unbiased estimate of the Jaccard similarity of their shingle sets.
+pub fn estimated_jaccard(a: &[u64], b: &[u64]) -> f64 {
+    if a.is_empty() {
+        return 1.0;
+    }
+    let agreeing = a.iter().zip(b).filter(|(a, b)| a == b).count();
+    agreeing as f64 / a.len() as f64
+}
@@ -0,0 +1,153 @@
+// character q-gram profiles
+
+use crate::buffers::{with_buffers, Pooled};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Overlapping character q-grams of `s`, as slices of `s`. A non-empty
+/// string shorter than `q` yields itself as its only q-gram.
+pub fn qgrams(s: &str, q: usize) -> Vec<&str> {
+    let boundaries: Vec<usize> = s
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(s.len()))
+        .collect();
+    let char_count = boundaries.len() - 1;
+    if char_count == 0 {
+        return Vec::new();
+    }
+    if char_count < q {
+        return vec![s];
+    }
+    (0..=char_count - q)
+        .map(|i| &s[boundaries[i]..boundaries[i + q]])
+        .collect()
+}
+
+/// `s` surrounded by `q - 1` copies of `pad` on each side, so the q-grams
+/// at its boundaries are distinct from those in its middle.
+pub fn padded(s: &str, q: usize, pad: char) -> String {
+    let padding: String = std::iter::repeat_n(pad, q.saturating_sub(1)).collect();
+    format!("{padding}{s}{padding}")
+}
+
+/// Count of every distinct q-gram of `s`.
+pub fn qgram_profile(s: &str, q: usize) -> HashMap<&str, usize> {
+    let mut profile = HashMap::new();
+    for gram in qgrams(s, q) {
+        *profile.entry(gram).or_insert(0) += 1;
+    }
+    profile
+}
+
+/// Jaccard similarity of the q-gram sets of `a` and `b`. Two empty strings
+/// are identical.
+pub fn qgram_jaccard(a: &str, b: &str, q: usize) -> f64 {
+    let a: HashSet<&str> = qgrams(a, q).into_iter().collect();
+    let b: HashSet<&str> = qgrams(b, q).into_iter().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 1.0;
+    }
+    a.intersection(&b).count() as f64 / union as f64
+}
+
+/// `qgrams` over a slice of elements.
+fn slice_qgrams<Elem>(s: &[Elem], q: usize) -> Vec<&[Elem]> {
+    match s.len() {
+        0 => Vec::new(),
+        len if len < q => vec![s],
+        _ => s.windows(q).collect(),
+    }
+}
+
+/// Sørensen-Dice similarity of the q-gram multisets of two element
+/// sequences, with q-grams as in `qgrams`. Two empty sequences are
+/// identical.
+pub fn dice<Elem: Eq + Hash>(a: &[Elem], b: &[Elem], q: usize) -> f64 {
+    let (a, b) = (slice_qgrams(a, q), slice_qgrams(b, q));
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    let mut counts: HashMap<&[Elem], usize> = HashMap::new();
+    for gram in &a {
+        *counts.entry(gram).or_insert(0) += 1;
+    }
+    let mut shared = 0;
+    for gram in &b {
+        if let Some(count) = counts.get_mut(gram).filter(|count| **count > 0) {
+            *count -= 1;
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / (a.len() + b.len()) as f64
+}
+
+/// q-gram distance: the L1 distance between the q-gram count profiles of
+/// `a` and `b`.
+pub fn qgram_distance(a: &str, b: &str, q: usize) -> usize {
+    let mut counts = qgram_profile(a, q)
+        .into_iter()
+        .map(|(gram, count)| (gram, count as isize))
+        .collect::<HashMap<&str, isize>>();
+    for gram in qgrams(b, q) {
+        *counts.entry(gram).or_insert(0) -= 1;
+    }
+    counts.values().map(|count| count.unsigned_abs()).sum()
+}
+
+/// Number of character bigrams `a` and `b` have in common, counted as
+/// multisets. Bigrams are sorted in pooled buffers rather than hashed.
+pub fn shared_bigram_count(a: &str, b: &str) -> usize {
+    let bigrams = |s: &str, out: &mut Vec<u64>| {
+        out.extend(
+            s.chars()
+                .zip(s.chars().skip(1))
+                .map(|(first, second)| (first as u64) << 32 | second as u64),
+        );
+        out.sort_unstable();
+    };
+    with_buffers(|[a_bigrams, b_bigrams]| {
+        bigrams(a, a_bigrams);
+        bigrams(b, b_bigrams);
+        let (mut i, mut j, mut shared) = (0, 0, 0);
+        while i < a_bigrams.len() && j < b_bigrams.len() {
+            match a_bigrams[i].cmp(&b_bigrams[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    shared += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        shared
+    })
+}
+
+/// Bag distance: the larger of the two multiset differences between the
+/// elements (e.g. characters) of `a` and `b`. A cheap lower bound of the
+/// edit distance. Both sides are sorted in pooled buffers rather than
+/// hashed.
+pub fn bag_distance<T: Pooled + Ord + Copy>(a: &[T], b: &[T]) -> usize {
+    with_buffers(|[sorted_a, sorted_b]| {
+        sorted_a.extend_from_slice(a);
+        sorted_b.extend_from_slice(b);
+        sorted_a.sort_unstable();
+        sorted_b.sort_unstable();
+        let (mut i, mut j, mut common) = (0, 0, 0);
+        while i < sorted_a.len() && j < sorted_b.len() {
+            match sorted_a[i].cmp(&sorted_b[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    common += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        a.len().max(b.len()) - common
+    })
+}
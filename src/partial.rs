@@ -0,0 +1,118 @@
+// best-window (partial) Damerau-Levenshtein
+
+use crate::deadline;
+use crate::weighted_DL;
+use std::hash::Hash;
+
+/// For every end position `j` of `text` (`0..=text.len()`), the least
+/// unrestricted Damerau-Levenshtein distance between `pattern` and any
+/// substring of `text` ending at `j`. This is the Lowrance-Wagner DP with a
+/// free start in `text`: the column of the empty pattern is all zeros, so
+/// each row continues the alignments of every earlier start at once, in
+/// `O(|text| * |pattern|)` overall.
+///
+/// Every entry is at most `|pattern|`, so a transposition reaching back more
+/// than `|pattern| + 1` rows can never win, and only the last
+/// `|pattern| + 3` rows are kept. Past the deadline the remaining bounds are
+/// `0`.
+pub fn substring_lower_bounds<Elem: Eq>(pattern: &[Elem], text: &[Elem]) -> Vec<usize> {
+    let pattern_len = pattern.len();
+    let width = pattern_len + 1;
+    let slots = pattern_len + 3;
+    let row = |t: usize| (t % slots) * width;
+
+    // rows[row(t) + j]: the distance for the first `t` elements of `text`
+    // and the first `j` of `pattern`.
+    let mut rows = vec![0_usize; slots * width];
+    for (j, distance) in rows[..width].iter_mut().enumerate() {
+        *distance = j;
+    }
+    // last_match[j]: the last position `t` with `text[t - 1] == pattern[j - 1]`.
+    let mut last_match = vec![0_usize; pattern_len + 1];
+
+    let mut bounds = vec![0_usize; text.len() + 1];
+    bounds[0] = pattern_len;
+    for t in 1..=text.len() {
+        if deadline::exceeded() {
+            break;
+        }
+        let (current, previous) = (row(t), row(t - 1));
+        rows[current] = 0;
+        let mut db = 0;
+        for j in 1..=pattern_len {
+            let is_match = text[t - 1] == pattern[j - 1];
+            let mut distance = (rows[previous + j - 1] + usize::from(!is_match))
+                .min(rows[previous + j] + 1)
+                .min(rows[current + j - 1] + 1);
+            let k = last_match[j];
+            if k > 0 && db > 0 && t - k - 1 <= pattern_len {
+                let transposition = rows[row(k - 1) + db - 1] + (t - k - 1) + 1 + (j - db - 1);
+                distance = distance.min(transposition);
+            }
+            rows[current + j] = distance;
+            if is_match {
+                db = j;
+            }
+        }
+        for j in 1..=pattern_len {
+            if text[t - 1] == pattern[j - 1] {
+                last_match[j] = t;
+            }
+        }
+        bounds[t] = rows[current + pattern_len];
+    }
+    bounds
+}
+
+/// The distance and start of the window of `window_len` elements of
+/// `longer`, among those starting at `starts`, with the least
+/// Damerau-Levenshtein distance to `shorter`; the earliest window wins ties.
+/// `None` if no window is within `max_distance`.
+///
+/// Rather than running a full DP per window, the free-start DP of
+/// `substring_lower_bounds` bounds every window from below in one pass. The
+/// window with the lowest bound is scored first, and every other window is
+/// only scored when its bound can still beat the best so far, with a DP
+/// bounded by that best. On long documents almost every window is skipped.
+pub fn best_window<Elem>(
+    shorter: &[Elem],
+    longer: &[Elem],
+    window_len: usize,
+    starts: &[usize],
+    max_distance: Option<usize>,
+) -> Option<(usize, usize)>
+where
+    Elem: Eq + Hash + Clone,
+{
+    let lower_bounds = substring_lower_bounds(shorter, longer);
+    let lower_bound = |start: usize| lower_bounds[start + window_len];
+    let seed = starts
+        .iter()
+        .copied()
+        .min_by_key(|&start| (lower_bound(start), start));
+
+    let mut best: Option<(usize, usize)> = None;
+    for start in seed.into_iter().chain(starts.iter().copied()) {
+        if deadline::exceeded() {
+            break;
+        }
+        // An earlier window only has to tie the best, a later one beat it.
+        let bound = match best {
+            Some((distance, best_start)) if start < best_start => distance,
+            Some((distance, best_start)) if start > best_start => match distance.checked_sub(1) {
+                Some(bound) => bound,
+                None => continue,
+            },
+            Some(_) => continue,
+            None => max_distance.unwrap_or(usize::MAX),
+        };
+        if lower_bound(start) > bound {
+            continue;
+        }
+        let window = &longer[start..start + window_len];
+        if let Some(distance) = weighted_DL::bounded_damerau_levenshtein(window, shorter, bound) {
+            best = Some((distance, start));
+        }
+    }
+    best
+}
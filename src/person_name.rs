@@ -0,0 +1,176 @@
+// person-name matching heuristics
+
+use crate::phonetic;
+use crate::weighted_DL;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Titles and generational suffixes that do not identify a person.
+const IGNORED_TOKENS: &[&str] = &[
+    "mr", "mrs", "ms", "miss", "dr", "prof", "sir", "jr", "sr", "ii", "iii", "iv",
+];
+
+/// Tuning of `person_name_similarity`.
+#[derive(Clone, Copy, Debug)]
+pub struct PersonNameOptions {
+    /// Score of an initial against a name starting with it ("J" vs "John").
+    pub initial_score: f64,
+    /// Least score of two similarly spelled names with a common Double
+    /// Metaphone key ("Catherine" vs "Kathryn").
+    pub phonetic_score: f64,
+    /// Weight of a name token left without a partner, e.g. a middle name
+    /// present on one side only; `1.0` counts it as a full mismatch.
+    pub unmatched_token_weight: f64,
+}
+
+/// The lowercased name tokens of `s`, without accents: apostrophes are
+/// dropped ("O'Brien" -> "obrien") and hyphens, periods, commas and other
+/// punctuation separate tokens ("Smith-Jones" -> "smith", "jones"). Titles
+/// and generational suffixes are removed unless nothing else is left.
+fn name_tokens(s: &str) -> Vec<String> {
+    let folded: String = s
+        .nfkd()
+        .filter(|&c| !is_combining_mark(c) && !matches!(c, '\'' | '\u{2019}' | '\u{02BC}' | '`'))
+        .flat_map(char::to_lowercase)
+        .collect();
+    let tokens: Vec<String> = folded
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect();
+    let significant: Vec<String> = tokens
+        .iter()
+        .filter(|token| !IGNORED_TOKENS.contains(&token.as_str()))
+        .cloned()
+        .collect();
+    if significant.is_empty() {
+        tokens
+    } else {
+        significant
+    }
+}
+
+fn sounds_alike(a: &str, b: &str) -> bool {
+    let (a_primary, a_alternate) = phonetic::double_metaphone(a);
+    let (b_primary, b_alternate) = phonetic::double_metaphone(b);
+    [a_primary, a_alternate]
+        .iter()
+        .any(|a_key| !a_key.is_empty() && (*a_key == b_primary || *a_key == b_alternate))
+}
+
+/// Similarity of two name tokens: an initial scores `initial_score` against
+/// any name it starts, and other tokens score their normalized
+/// Damerau-Levenshtein similarity, raised to `phonetic_score` when they
+/// sound alike and are spelled at least half alike; Double Metaphone keys
+/// are too coarse on their own ("John" and "Jane" share one).
+fn token_similarity(a: &str, b: &str, options: PersonNameOptions) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let (shorter, longer) = if a.chars().count() <= b.chars().count() {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    if shorter.chars().count() == 1 {
+        return if longer.starts_with(shorter) {
+            options.initial_score
+        } else {
+            0.0
+        };
+    }
+    let spelled = strsim::normalized_damerau_levenshtein(a, b);
+    if spelled >= 0.5 && sounds_alike(a, b) {
+        spelled.max(options.phonetic_score)
+    } else {
+        spelled
+    }
+}
+
+/// Similarity of two person names in `[0, 1]`.
+///
+/// The `name_tokens` of both names are scored with
+/// `weighted_DL::assigned_token_similarity` under `token_similarity`, so
+/// "Smith, John" matches "John Smith" and "J. Smith" matches "John Smith".
+/// Tokens that were split differently ("Mary-Ann" vs "Maryann") are covered
+/// by also comparing the concatenated tokens with normalized
+/// Damerau-Levenshtein; the higher score is kept. `0.0` when either name
+/// has no tokens.
+pub fn person_name_similarity(a: &str, b: &str, options: PersonNameOptions) -> f64 {
+    let a_tokens = name_tokens(a);
+    let b_tokens = name_tokens(b);
+    if a_tokens.is_empty() || b_tokens.is_empty() {
+        return 0.0;
+    }
+    let reordered = weighted_DL::assigned_token_similarity(
+        &a_tokens,
+        &b_tokens,
+        |a, b| token_similarity(a, b, options),
+        options.unmatched_token_weight,
+    );
+
+    let joined = strsim::normalized_damerau_levenshtein(&a_tokens.concat(), &b_tokens.concat());
+    reordered.max(joined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS: PersonNameOptions = PersonNameOptions {
+        initial_score: 0.9,
+        phonetic_score: 0.8,
+        unmatched_token_weight: 0.5,
+    };
+
+    fn similarity(a: &str, b: &str) -> f64 {
+        person_name_similarity(a, b, OPTIONS)
+    }
+
+    #[test]
+    fn initial_matches_full_name() {
+        // "j" vs "john" scores `initial_score`, "smith" matches exactly.
+        assert!((similarity("J. Smith", "John Smith") - 0.95).abs() < 1e-12);
+        assert!(similarity("K. Smith", "John Smith") < 0.95);
+    }
+
+    #[test]
+    fn token_order_is_ignored() {
+        assert_eq!(similarity("Smith, John", "John Smith"), 1.0);
+        assert_eq!(similarity("John Smith", "Smith, John"), 1.0);
+    }
+
+    #[test]
+    fn apostrophes_are_dropped() {
+        assert_eq!(name_tokens("O'Brien"), ["obrien"]);
+        assert_eq!(name_tokens("O\u{2019}Brien"), ["obrien"]);
+        assert_eq!(similarity("O'Brien", "OBrien"), 1.0);
+        assert_eq!(similarity("O'Brien", "O Brien"), 1.0);
+    }
+
+    #[test]
+    fn split_tokens_match_joined() {
+        assert_eq!(name_tokens("Mary-Ann"), ["mary", "ann"]);
+        assert_eq!(similarity("Mary-Ann", "Maryann"), 1.0);
+        assert_eq!(similarity("Mary-Ann Smith", "Maryann Smith"), 1.0);
+    }
+
+    #[test]
+    fn phonetic_spellings_are_raised() {
+        let spelled = strsim::normalized_damerau_levenshtein("catherine", "kathryn");
+        assert!(spelled < OPTIONS.phonetic_score);
+        assert_eq!(similarity("Catherine", "Kathryn"), OPTIONS.phonetic_score);
+        // "John" and "Jane" share a Double Metaphone key but are spelled
+        // less than half alike.
+        assert!(similarity("John", "Jane") < 0.5);
+    }
+
+    #[test]
+    fn titles_and_suffixes_are_stripped() {
+        assert_eq!(name_tokens("Dr. John Smith Jr."), ["john", "smith"]);
+        assert_eq!(similarity("Dr. John Smith Jr.", "John Smith"), 1.0);
+        assert_eq!(similarity("Mrs Mary Smith", "Mary Smith III"), 1.0);
+        // A name made only of titles keeps them.
+        assert_eq!(name_tokens("Mr"), ["mr"]);
+    }
+}
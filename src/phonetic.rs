@@ -0,0 +1,918 @@
+// Phonetic encodings
+
+use serde::Deserialize;
+
+/// Phonetic encoders selectable by name from the Python side.
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PhoneticAlgorithm {
+    Soundex,
+    /// The primary Double Metaphone key.
+    Metaphone,
+    Nysiis,
+    ColognePhonetics,
+}
+
+impl PhoneticAlgorithm {
+    pub fn encode(self, s: &str) -> String {
+        match self {
+            PhoneticAlgorithm::Soundex => soundex(s),
+            PhoneticAlgorithm::Metaphone => double_metaphone(s).0,
+            PhoneticAlgorithm::Nysiis => nysiis(s, Some(6)),
+            PhoneticAlgorithm::ColognePhonetics => cologne_phonetics(s),
+        }
+    }
+}
+
+/// American Soundex digit of an uppercase ASCII letter; vowels and "H", "W",
+/// "Y" have none.
+fn soundex_digit(c: char) -> Option<char> {
+    match c {
+        'B' | 'F' | 'P' | 'V' => Some('1'),
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some('2'),
+        'D' | 'T' => Some('3'),
+        'L' => Some('4'),
+        'M' | 'N' => Some('5'),
+        'R' => Some('6'),
+        _ => None,
+    }
+}
+
+/// American Soundex code of `s`: the first letter followed by three digits,
+/// padded with "0". Adjacent letters with the same digit are coded once, also
+/// when separated by "H" or "W" (but not by a vowel). Characters other than
+/// ASCII letters are ignored; a string without any yields an empty code.
+pub fn soundex(s: &str) -> String {
+    let mut letters = s
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase());
+    let Some(first) = letters.next() else {
+        return String::new();
+    };
+
+    let mut code = String::with_capacity(4);
+    code.push(first);
+    let mut previous = soundex_digit(first);
+    for c in letters {
+        if code.len() == 4 {
+            break;
+        }
+        let digit = soundex_digit(c);
+        if let Some(d) = digit {
+            if previous != digit {
+                code.push(d);
+            }
+        }
+        if !matches!(c, 'H' | 'W') {
+            previous = digit;
+        }
+    }
+    while code.len() < 4 {
+        code.push('0');
+    }
+    code
+}
+
+const DOUBLE_METAPHONE_LEN: usize = 4;
+
+/// Primary and alternate keys being built by `double_metaphone`, each capped
+/// at `DOUBLE_METAPHONE_LEN` characters.
+struct MetaphoneKeys {
+    primary: String,
+    alternate: String,
+}
+
+impl MetaphoneKeys {
+    fn push_primary(&mut self, code: &str) {
+        push_capped(&mut self.primary, code);
+    }
+
+    fn push_alternate(&mut self, code: &str) {
+        push_capped(&mut self.alternate, code);
+    }
+
+    fn push(&mut self, code: &str) {
+        self.push_both(code, code);
+    }
+
+    fn push_both(&mut self, primary: &str, alternate: &str) {
+        self.push_primary(primary);
+        self.push_alternate(alternate);
+    }
+
+    fn is_complete(&self) -> bool {
+        self.primary.len() >= DOUBLE_METAPHONE_LEN && self.alternate.len() >= DOUBLE_METAPHONE_LEN
+    }
+}
+
+fn push_capped(key: &mut String, code: &str) {
+    let room = DOUBLE_METAPHONE_LEN.saturating_sub(key.len());
+    key.extend(code.chars().take(room));
+}
+
+/// Uppercased input of `double_metaphone` with the out-of-range-safe lookups
+/// the rules are written in terms of.
+struct MetaphoneInput {
+    chars: Vec<char>,
+    slavo_germanic: bool,
+}
+
+impl MetaphoneInput {
+    fn len(&self) -> isize {
+        self.chars.len() as isize
+    }
+
+    fn at(&self, index: isize) -> char {
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| self.chars.get(i))
+            .copied()
+            .unwrap_or('\0')
+    }
+
+    fn is_vowel_at(&self, index: isize) -> bool {
+        matches!(self.at(index), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    /// Whether the `len` characters starting at `start` equal one of `options`.
+    fn contains(&self, start: isize, len: usize, options: &[&str]) -> bool {
+        let Ok(start) = usize::try_from(start) else {
+            return false;
+        };
+        let Some(window) = self.chars.get(start..start + len) else {
+            return false;
+        };
+        options
+            .iter()
+            .any(|option| option.chars().eq(window.iter().copied()))
+    }
+
+    fn is_last(&self, index: isize) -> bool {
+        index == self.len() - 1
+    }
+
+    fn germanic_prefix(&self) -> bool {
+        self.contains(0, 4, &["VAN ", "VON "]) || self.contains(0, 3, &["SCH"])
+    }
+}
+
+/// Double Metaphone (Lawrence Philips) primary and alternate keys of `s`, up
+/// to four characters each, following the rules of the reference
+/// implementation. Letters outside A-Z (other than "Ç" and "Ñ") are skipped.
+pub fn double_metaphone(s: &str) -> (String, String) {
+    let upper = s.trim().to_uppercase();
+    let input = MetaphoneInput {
+        slavo_germanic: ["W", "K", "CZ", "WITZ"]
+            .iter()
+            .any(|marker| upper.contains(marker)),
+        chars: upper.chars().collect(),
+    };
+    let mut keys = MetaphoneKeys {
+        primary: String::new(),
+        alternate: String::new(),
+    };
+
+    let silent_start = input.contains(0, 2, &["GN", "KN", "PN", "WR", "PS"]);
+    let mut index: isize = if silent_start { 1 } else { 0 };
+    while !keys.is_complete() && index < input.len() {
+        index = match input.at(index) {
+            'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                if index == 0 {
+                    keys.push("A");
+                }
+                index + 1
+            }
+            'B' => double_letter(&input, &mut keys, index, "P"),
+            'Ç' => {
+                keys.push("S");
+                index + 1
+            }
+            'C' => metaphone_c(&input, &mut keys, index),
+            'D' => metaphone_d(&input, &mut keys, index),
+            'F' => double_letter(&input, &mut keys, index, "F"),
+            'G' => metaphone_g(&input, &mut keys, index),
+            'H' => {
+                if (index == 0 || input.is_vowel_at(index - 1)) && input.is_vowel_at(index + 1) {
+                    keys.push("H");
+                    index + 2
+                } else {
+                    index + 1
+                }
+            }
+            'J' => metaphone_j(&input, &mut keys, index),
+            'K' => double_letter(&input, &mut keys, index, "K"),
+            'L' => metaphone_l(&input, &mut keys, index),
+            'M' => {
+                keys.push("M");
+                let silent_b = input.contains(index - 1, 3, &["UMB"])
+                    && (input.is_last(index + 1) || input.contains(index + 2, 2, &["ER"]));
+                if input.at(index + 1) == 'M' || silent_b {
+                    index + 2
+                } else {
+                    index + 1
+                }
+            }
+            'N' => double_letter(&input, &mut keys, index, "N"),
+            'Ñ' => {
+                keys.push("N");
+                index + 1
+            }
+            'P' => {
+                if input.at(index + 1) == 'H' {
+                    keys.push("F");
+                    index + 2
+                } else {
+                    keys.push("P");
+                    if input.contains(index + 1, 1, &["P", "B"]) {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+            }
+            'Q' => double_letter(&input, &mut keys, index, "K"),
+            'R' => {
+                if input.is_last(index)
+                    && !input.slavo_germanic
+                    && input.contains(index - 2, 2, &["IE"])
+                    && !input.contains(index - 4, 2, &["ME", "MA"])
+                {
+                    keys.push_alternate("R");
+                } else {
+                    keys.push("R");
+                }
+                if input.at(index + 1) == 'R' {
+                    index + 2
+                } else {
+                    index + 1
+                }
+            }
+            'S' => metaphone_s(&input, &mut keys, index),
+            'T' => metaphone_t(&input, &mut keys, index),
+            'V' => double_letter(&input, &mut keys, index, "F"),
+            'W' => metaphone_w(&input, &mut keys, index),
+            'X' => {
+                if index == 0 {
+                    keys.push("S");
+                    index + 1
+                } else {
+                    let silent_final = input.is_last(index)
+                        && (input.contains(index - 3, 3, &["IAU", "EAU"])
+                            || input.contains(index - 2, 2, &["AU", "OU"]));
+                    if !silent_final {
+                        keys.push("KS");
+                    }
+                    if input.contains(index + 1, 1, &["C", "X"]) {
+                        index + 2
+                    } else {
+                        index + 1
+                    }
+                }
+            }
+            'Z' => metaphone_z(&input, &mut keys, index),
+            _ => index + 1,
+        };
+    }
+
+    (keys.primary, keys.alternate)
+}
+
+/// Codes a letter that is skipped when doubled ("BB", "FF", ...).
+fn double_letter(
+    input: &MetaphoneInput,
+    keys: &mut MetaphoneKeys,
+    index: isize,
+    code: &str,
+) -> isize {
+    keys.push(code);
+    if input.at(index + 1) == input.at(index) {
+        index + 2
+    } else {
+        index + 1
+    }
+}
+
+fn metaphone_c(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    if germanic_ch(input, index) {
+        keys.push("K");
+        index + 2
+    } else if index == 0 && input.contains(index, 6, &["CAESAR"]) {
+        keys.push("S");
+        index + 2
+    } else if input.contains(index, 2, &["CH"]) {
+        metaphone_ch(input, keys, index)
+    } else if input.contains(index, 2, &["CZ"]) && !input.contains(index - 2, 4, &["WICZ"]) {
+        keys.push_both("S", "X");
+        index + 2
+    } else if input.contains(index + 1, 3, &["CIA"]) {
+        keys.push("X");
+        index + 3
+    } else if input.contains(index, 2, &["CC"]) && !(index == 1 && input.at(0) == 'M') {
+        if input.contains(index + 2, 1, &["I", "E", "H"]) && !input.contains(index + 2, 2, &["HU"])
+        {
+            if (index == 1 && input.at(index - 1) == 'A')
+                || input.contains(index - 1, 5, &["UCCEE", "UCCES"])
+            {
+                keys.push("KS");
+            } else {
+                keys.push("X");
+            }
+            index + 3
+        } else {
+            keys.push("K");
+            index + 2
+        }
+    } else if input.contains(index, 2, &["CK", "CG", "CQ"]) {
+        keys.push("K");
+        index + 2
+    } else if input.contains(index, 2, &["CI", "CE", "CY"]) {
+        if input.contains(index, 3, &["CIO", "CIE", "CIA"]) {
+            keys.push_both("S", "X");
+        } else {
+            keys.push("S");
+        }
+        index + 2
+    } else {
+        keys.push("K");
+        if input.contains(index + 1, 2, &[" C", " Q", " G"]) {
+            index + 3
+        } else if input.contains(index + 1, 1, &["C", "K", "Q"])
+            && !input.contains(index + 1, 2, &["CE", "CI"])
+        {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+}
+
+/// "CH" read as "K" after a consonant + "A", as in "Bacher" and "Macher".
+fn germanic_ch(input: &MetaphoneInput, index: isize) -> bool {
+    if input.contains(index, 4, &["CHIA"]) {
+        true
+    } else if index <= 1 || input.is_vowel_at(index - 2) || !input.contains(index - 1, 3, &["ACH"])
+    {
+        false
+    } else {
+        let next = input.at(index + 2);
+        (next != 'I' && next != 'E') || input.contains(index - 2, 6, &["BACHER", "MACHER"])
+    }
+}
+
+fn metaphone_ch(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    let greek_start = index == 0
+        && (input.contains(index + 1, 5, &["HARAC", "HARIS"])
+            || input.contains(index + 1, 3, &["HOR", "HYM", "HIA", "HEM"]))
+        && !input.contains(0, 5, &["CHORE"]);
+    let hard = input.germanic_prefix()
+        || input.contains(index - 2, 6, &["ORCHES", "ARCHIT", "ORCHID"])
+        || input.contains(index + 2, 1, &["T", "S"])
+        || ((input.contains(index - 1, 1, &["A", "O", "U", "E"]) || index == 0)
+            && (input.contains(
+                index + 2,
+                1,
+                &["L", "R", "N", "M", "B", "H", "F", "V", "W", " "],
+            ) || input.is_last(index + 1)));
+
+    if index > 0 && input.contains(index, 4, &["CHAE"]) {
+        keys.push_both("K", "X");
+    } else if greek_start || hard {
+        keys.push("K");
+    } else if index > 0 {
+        if input.contains(0, 2, &["MC"]) {
+            keys.push("K");
+        } else {
+            keys.push_both("X", "K");
+        }
+    } else {
+        keys.push("X");
+    }
+    index + 2
+}
+
+fn metaphone_d(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    if input.contains(index, 2, &["DG"]) {
+        if input.contains(index + 2, 1, &["I", "E", "Y"]) {
+            keys.push("J");
+            index + 3
+        } else {
+            keys.push("TK");
+            index + 2
+        }
+    } else if input.contains(index, 2, &["DT", "DD"]) {
+        keys.push("T");
+        index + 2
+    } else {
+        keys.push("T");
+        index + 1
+    }
+}
+
+/// Pairs after an initial "G" that may be read either hard or soft ("Gesell").
+const G_SOFT_AFTER_START: [&str; 11] = [
+    "ES", "EP", "EB", "EL", "EY", "IB", "IL", "IN", "IE", "EI", "ER",
+];
+
+fn metaphone_g(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    let next = input.at(index + 1);
+    if next == 'H' {
+        metaphone_gh(input, keys, index)
+    } else if next == 'N' {
+        if index == 1 && input.is_vowel_at(0) && !input.slavo_germanic {
+            keys.push_both("KN", "N");
+        } else if !input.contains(index + 2, 2, &["EY"]) && !input.slavo_germanic {
+            keys.push_both("N", "KN");
+        } else {
+            keys.push("KN");
+        }
+        index + 2
+    } else if input.contains(index + 1, 2, &["LI"]) && !input.slavo_germanic {
+        keys.push_both("KL", "L");
+        index + 2
+    } else if (index == 0 && (next == 'Y' || input.contains(index + 1, 2, &G_SOFT_AFTER_START)))
+        || ((input.contains(index + 1, 2, &["ER"]) || next == 'Y')
+            && !input.contains(0, 6, &["DANGER", "RANGER", "MANGER"])
+            && !input.contains(index - 1, 1, &["E", "I"])
+            && !input.contains(index - 1, 3, &["RGY", "OGY"]))
+    {
+        keys.push_both("K", "J");
+        index + 2
+    } else if input.contains(index + 1, 1, &["E", "I", "Y"])
+        || input.contains(index - 1, 4, &["AGGI", "OGGI"])
+    {
+        if input.germanic_prefix() || input.contains(index + 1, 2, &["ET"]) {
+            keys.push("K");
+        } else if input.contains(index + 1, 3, &["IER"]) {
+            keys.push("J");
+        } else {
+            keys.push_both("J", "K");
+        }
+        index + 2
+    } else if next == 'G' {
+        keys.push("K");
+        index + 2
+    } else {
+        keys.push("K");
+        index + 1
+    }
+}
+
+fn metaphone_gh(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    if index > 0 && !input.is_vowel_at(index - 1) {
+        keys.push("K");
+    } else if index == 0 {
+        keys.push(if input.at(index + 2) == 'I' { "J" } else { "K" });
+    } else if (index > 1 && input.contains(index - 2, 1, &["B", "H", "D"]))
+        || (index > 2 && input.contains(index - 3, 1, &["B", "H", "D"]))
+        || (index > 3 && input.contains(index - 4, 1, &["B", "H"]))
+    {
+        // silent, as in "Hugh" and "bough"
+    } else if index > 2
+        && input.at(index - 1) == 'U'
+        && input.contains(index - 3, 1, &["C", "G", "L", "R", "T"])
+    {
+        keys.push("F");
+    } else if input.at(index - 1) != 'I' {
+        keys.push("K");
+    }
+    index + 2
+}
+
+fn metaphone_j(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    if input.contains(index, 4, &["JOSE"]) || input.contains(0, 4, &["SAN "]) {
+        if (index == 0 && input.at(index + 4) == ' ')
+            || input.len() == 4
+            || input.contains(0, 4, &["SAN "])
+        {
+            keys.push("H");
+        } else {
+            keys.push_both("J", "H");
+        }
+        return index + 1;
+    }
+
+    if index == 0 {
+        keys.push_both("J", "A");
+    } else if input.is_vowel_at(index - 1)
+        && !input.slavo_germanic
+        && matches!(input.at(index + 1), 'A' | 'O')
+    {
+        keys.push_both("J", "H");
+    } else if input.is_last(index) {
+        keys.push_primary("J");
+    } else if !input.contains(index + 1, 1, &["L", "T", "K", "S", "N", "M", "B", "Z"])
+        && !input.contains(index - 1, 1, &["S", "K", "L"])
+    {
+        keys.push("J");
+    }
+    if input.at(index + 1) == 'J' {
+        index + 2
+    } else {
+        index + 1
+    }
+}
+
+fn metaphone_l(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    if input.at(index + 1) != 'L' {
+        keys.push("L");
+        return index + 1;
+    }
+    // Spanish "-illo", "-illa", "-alle" where the "LL" is not pronounced as "L"
+    let len = input.len();
+    let spanish_ll = (index == len - 3 && input.contains(index - 1, 4, &["ILLO", "ILLA", "ALLE"]))
+        || ((input.contains(len - 2, 2, &["AS", "OS"]) || input.contains(len - 1, 1, &["A", "O"]))
+            && input.contains(index - 1, 4, &["ALLE"]));
+    if spanish_ll {
+        keys.push_primary("L");
+    } else {
+        keys.push("L");
+    }
+    index + 2
+}
+
+fn metaphone_s(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    if input.contains(index - 1, 3, &["ISL", "YSL"]) {
+        index + 1
+    } else if index == 0 && input.contains(index, 5, &["SUGAR"]) {
+        keys.push_both("X", "S");
+        index + 1
+    } else if input.contains(index, 2, &["SH"]) {
+        if input.contains(index + 1, 4, &["HEIM", "HOEK", "HOLM", "HOLZ"]) {
+            keys.push("S");
+        } else {
+            keys.push("X");
+        }
+        index + 2
+    } else if input.contains(index, 3, &["SIO", "SIA"]) || input.contains(index, 4, &["SIAN"]) {
+        if input.slavo_germanic {
+            keys.push("S");
+        } else {
+            keys.push_both("S", "X");
+        }
+        index + 3
+    } else if (index == 0 && input.contains(index + 1, 1, &["M", "N", "L", "W"]))
+        || input.contains(index + 1, 1, &["Z"])
+    {
+        keys.push_both("S", "X");
+        if input.contains(index + 1, 1, &["Z"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    } else if input.contains(index, 2, &["SC"]) {
+        if input.at(index + 2) == 'H' {
+            if input.contains(index + 3, 2, &["OO", "ER", "EN", "UY", "ED", "EM"]) {
+                if input.contains(index + 3, 2, &["ER", "EN"]) {
+                    keys.push_both("X", "SK");
+                } else {
+                    keys.push("SK");
+                }
+            } else if index == 0 && !input.is_vowel_at(3) && input.at(3) != 'W' {
+                keys.push_both("X", "S");
+            } else {
+                keys.push("X");
+            }
+        } else if input.contains(index + 2, 1, &["I", "E", "Y"]) {
+            keys.push("S");
+        } else {
+            keys.push("SK");
+        }
+        index + 3
+    } else {
+        if input.is_last(index) && input.contains(index - 2, 2, &["AI", "OI"]) {
+            keys.push_alternate("S");
+        } else {
+            keys.push("S");
+        }
+        if input.contains(index + 1, 1, &["S", "Z"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+}
+
+fn metaphone_t(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    if input.contains(index, 4, &["TION"]) || input.contains(index, 3, &["TIA", "TCH"]) {
+        keys.push("X");
+        index + 3
+    } else if input.contains(index, 2, &["TH"]) || input.contains(index, 3, &["TTH"]) {
+        if input.contains(index + 2, 2, &["OM", "AM"]) || input.germanic_prefix() {
+            keys.push("T");
+        } else {
+            keys.push_both("0", "T");
+        }
+        index + 2
+    } else {
+        keys.push("T");
+        if input.contains(index + 1, 1, &["T", "D"]) {
+            index + 2
+        } else {
+            index + 1
+        }
+    }
+}
+
+fn metaphone_w(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    if input.contains(index, 2, &["WR"]) {
+        keys.push("R");
+        index + 2
+    } else if index == 0 && (input.is_vowel_at(index + 1) || input.contains(index, 2, &["WH"])) {
+        if input.is_vowel_at(index + 1) {
+            keys.push_both("A", "F");
+        } else {
+            keys.push("A");
+        }
+        index + 1
+    } else if (input.is_last(index) && input.is_vowel_at(index - 1))
+        || input.contains(index - 1, 5, &["EWSKI", "EWSKY", "OWSKI", "OWSKY"])
+        || input.contains(0, 3, &["SCH"])
+    {
+        keys.push_alternate("F");
+        index + 1
+    } else if input.contains(index, 4, &["WICZ", "WITZ"]) {
+        keys.push_both("TS", "FX");
+        index + 4
+    } else {
+        index + 1
+    }
+}
+
+fn metaphone_z(input: &MetaphoneInput, keys: &mut MetaphoneKeys, index: isize) -> isize {
+    if input.at(index + 1) == 'H' {
+        keys.push("J");
+        return index + 2;
+    }
+    if input.contains(index + 1, 2, &["ZO", "ZI", "ZA"])
+        || (input.slavo_germanic && index > 0 && input.at(index - 1) != 'T')
+    {
+        keys.push_both("S", "TS");
+    } else {
+        keys.push("S");
+    }
+    if input.at(index + 1) == 'Z' {
+        index + 2
+    } else {
+        index + 1
+    }
+}
+
+fn is_nysiis_vowel(c: char) -> bool {
+    matches!(c, 'A' | 'E' | 'I' | 'O' | 'U')
+}
+
+/// Replacement for `current` in the body of a NYSIIS key, given its
+/// neighbours (`' '` past either end). The replacement overwrites `current`
+/// and, when longer than one character, the characters after it.
+fn nysiis_transcode(previous: char, current: char, next: char, after_next: char) -> Vec<char> {
+    match current {
+        'E' if next == 'V' => vec!['A', 'F'],
+        c if is_nysiis_vowel(c) => vec!['A'],
+        'Q' => vec!['G'],
+        'Z' => vec!['S'],
+        'M' => vec!['N'],
+        'K' if next == 'N' => vec!['N', 'N'],
+        'K' => vec!['C'],
+        'S' if next == 'C' && after_next == 'H' => vec!['S', 'S', 'S'],
+        'P' if next == 'H' => vec!['F', 'F'],
+        'H' if !is_nysiis_vowel(previous) || !is_nysiis_vowel(next) => vec![previous],
+        'W' if is_nysiis_vowel(previous) => vec![previous],
+        c => vec![c],
+    }
+}
+
+/// NYSIIS (New York State Identification and Intelligence System) code of a
+/// person name, truncated to `max_length` characters when given (the
+/// original system uses 6). Characters other than ASCII letters are ignored.
+pub fn nysiis(s: &str, max_length: Option<usize>) -> String {
+    let mut name: String = s
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if name.is_empty() {
+        return name;
+    }
+
+    for (prefix, replacement) in [
+        ("MAC", "MCC"),
+        ("KN", "NN"),
+        ("K", "C"),
+        ("PH", "FF"),
+        ("PF", "FF"),
+        ("SCH", "SSS"),
+    ] {
+        if let Some(rest) = name.strip_prefix(prefix) {
+            name = format!("{replacement}{rest}");
+            break;
+        }
+    }
+    for (suffix, replacement) in [
+        ("EE", "Y"),
+        ("IE", "Y"),
+        ("DT", "D"),
+        ("RT", "D"),
+        ("RD", "D"),
+        ("NT", "D"),
+        ("ND", "D"),
+    ] {
+        if let Some(rest) = name.strip_suffix(suffix) {
+            name = format!("{rest}{replacement}");
+            break;
+        }
+    }
+
+    let mut chars: Vec<char> = name.chars().collect();
+    let mut key = vec![chars[0]];
+    for i in 1..chars.len() {
+        let at = |j: usize| chars.get(j).copied().unwrap_or(' ');
+        let transcoded = nysiis_transcode(chars[i - 1], chars[i], at(i + 1), at(i + 2));
+        for (j, c) in transcoded.into_iter().enumerate() {
+            if let Some(slot) = chars.get_mut(i + j) {
+                *slot = c;
+            }
+        }
+        if chars[i] != chars[i - 1] {
+            key.push(chars[i]);
+        }
+    }
+
+    if key.len() > 1 {
+        if key.last() == Some(&'S') {
+            key.pop();
+        }
+        if key.len() > 2 && key.ends_with(&['A', 'Y']) {
+            key.remove(key.len() - 2);
+        }
+        if key.last() == Some(&'A') {
+            key.pop();
+        }
+    }
+    if let Some(max_length) = max_length {
+        key.truncate(max_length);
+    }
+    key.into_iter().collect()
+}
+
+/// Match Rating Approach codex of `s`: uppercase ASCII letters with every
+/// vowel but a leading one removed and doubled letters collapsed, reduced to
+/// its first and last three letters when longer than six.
+pub fn mra_codex(s: &str) -> String {
+    let letters: Vec<char> = s
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    let mut codex: Vec<char> = Vec::with_capacity(letters.len());
+    for (i, &c) in letters.iter().enumerate() {
+        if i > 0 && matches!(c, 'A' | 'E' | 'I' | 'O' | 'U') {
+            continue;
+        }
+        if codex.last() != Some(&c) {
+            codex.push(c);
+        }
+    }
+
+    if codex.len() > 6 {
+        codex.drain(3..codex.len() - 3);
+    }
+    codex.into_iter().collect()
+}
+
+/// Minimum similarity rating for two MRA codices whose lengths sum to
+/// `length_sum` to be considered a match.
+fn mra_minimum_rating(length_sum: usize) -> usize {
+    match length_sum {
+        0..=4 => 5,
+        5..=7 => 4,
+        8..=11 => 3,
+        12 => 2,
+        _ => 1,
+    }
+}
+
+/// Match Rating Approach similarity rating of two names and the minimum
+/// rating their codex lengths require, or `None` when the rules say the names
+/// cannot be compared (an empty or one-character name, or codices whose
+/// lengths differ by three or more).
+///
+/// Characters equal at the same position from the left, then from the right,
+/// are struck from both codices; the rating is `6` minus the number of
+/// letters left in the longer codex.
+pub fn mra_rating(a: &str, b: &str) -> Option<(usize, usize)> {
+    if a.trim().chars().count() <= 1 || b.trim().chars().count() <= 1 {
+        return None;
+    }
+    let a: Vec<char> = mra_codex(a).chars().collect();
+    let b: Vec<char> = mra_codex(b).chars().collect();
+    if a.is_empty() || b.is_empty() || a.len().abs_diff(b.len()) >= 3 {
+        return None;
+    }
+
+    let mut a_struck = vec![false; a.len()];
+    let mut b_struck = vec![false; b.len()];
+    for i in 0..a.len().min(b.len()) {
+        if a[i] == b[i] {
+            a_struck[i] = true;
+            b_struck[i] = true;
+        }
+        let (a_end, b_end) = (a.len() - 1 - i, b.len() - 1 - i);
+        if a[a_end] == b[b_end] {
+            a_struck[a_end] = true;
+            b_struck[b_end] = true;
+        }
+    }
+
+    let remaining = |struck: &[bool]| struck.iter().filter(|&&s| !s).count();
+    let unmatched = remaining(&a_struck).max(remaining(&b_struck));
+    Some((
+        6usize.abs_diff(unmatched),
+        mra_minimum_rating(a.len() + b.len()),
+    ))
+}
+
+/// Match Rating Approach comparison: whether the similarity rating of `a`
+/// and `b` reaches the minimum rating for their codex lengths. Names equal
+/// ignoring case always match.
+pub fn mra_comparison(a: &str, b: &str) -> bool {
+    if a.trim().chars().count() > 1 && a.eq_ignore_ascii_case(b) {
+        return true;
+    }
+    mra_rating(a, b).is_some_and(|(rating, minimum)| rating >= minimum)
+}
+
+/// Kölner Phonetik code of one letter given its neighbours (`None` at the
+/// string ends), or `None` for letters without a code ("H") and non-letters.
+fn cologne_code(previous: Option<char>, current: char, next: Option<char>) -> Option<&'static str> {
+    let next_in = |letters: &str| next.is_some_and(|c| letters.contains(c));
+    let previous_in = |letters: &str| previous.is_some_and(|c| letters.contains(c));
+    let code = match current {
+        'A' | 'E' | 'I' | 'J' | 'O' | 'U' | 'Y' => "0",
+        'B' => "1",
+        'P' if next == Some('H') => "3",
+        'P' => "1",
+        'D' | 'T' if next_in("CSZ") => "8",
+        'D' | 'T' => "2",
+        'F' | 'V' | 'W' => "3",
+        'G' | 'K' | 'Q' => "4",
+        'C' if previous.is_none() => {
+            if next_in("AHKLOQRUX") {
+                "4"
+            } else {
+                "8"
+            }
+        }
+        'C' if next_in("AHKOQUX") && !previous_in("SZ") => "4",
+        'C' => "8",
+        'X' if previous_in("CKQ") => "8",
+        'X' => "48",
+        'L' => "5",
+        'M' | 'N' => "6",
+        'R' => "7",
+        'S' | 'Z' => "8",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Kölner Phonetik (Cologne phonetics) code of `s`, a digit string for
+/// German-language names. Umlauts are read as their base vowel and "ß" as
+/// "ss"; characters other than letters are skipped. Repeated digits are
+/// coded once (unless separated by an uncoded letter) and "0" is only kept
+/// for a leading vowel.
+pub fn cologne_phonetics(s: &str) -> String {
+    let letters: Vec<char> = s
+        .to_uppercase()
+        .chars()
+        .map(|c| match c {
+            'Ä' => 'A',
+            'Ö' => 'O',
+            'Ü' => 'U',
+            'ẞ' => 'S',
+            _ => c,
+        })
+        .filter(char::is_ascii_alphabetic)
+        .collect();
+
+    let mut digits = String::with_capacity(letters.len());
+    let mut last_digit = None;
+    for (i, &current) in letters.iter().enumerate() {
+        let previous = i.checked_sub(1).map(|j| letters[j]);
+        let code = cologne_code(previous, current, letters.get(i + 1).copied());
+        // "X" codes as "48"; only its final digit takes part in deduplication.
+        let (lead, digit) = match code {
+            Some(code) => (&code[..code.len() - 1], code.chars().last()),
+            None => ("", None),
+        };
+        digits.push_str(lead);
+        if let Some(digit) = digit {
+            if last_digit != Some(digit) && (digit != '0' || i == 0) {
+                digits.push(digit);
+            }
+        }
+        last_digit = digit;
+    }
+    digits
+}
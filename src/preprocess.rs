@@ -0,0 +1,410 @@
+// string preprocessing applied before every metric
+
+use crate::arabic;
+use crate::preprocess_cache::PreprocessCache;
+use crate::transliterate;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock};
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Preprocessing applied to both strings inside the native functions, before
+/// any metric sees them. Every string-pair expression accepts these kwargs
+/// through `#[serde(flatten)]`, so the source columns are never rewritten.
+#[derive(Deserialize, Default, Clone, Debug)]
+pub struct PreprocessKwargs {
+    /// Unicode normalization form applied before every other option.
+    #[serde(default)]
+    unicode_normalize: Option<NormalizationForm>,
+    /// Drop combining marks (accents, diacritics) after normalization, so
+    /// "café" compares equal to "cafe".
+    #[serde(default)]
+    strip_accents: bool,
+    /// Fold accented Latin, Cyrillic and Greek text to ASCII after
+    /// `strip_accents`, so "Москва" compares equal to "Moskva".
+    #[serde(default)]
+    transliterate: bool,
+    /// Fold Arabic-script letter variants and common romanizations, see
+    /// `arabic::normalize_arabic_name`.
+    #[serde(default)]
+    arabic_romanization: bool,
+    /// Prefixes (e.g. "the ") removed before comparison, matched ASCII
+    /// case-insensitively and repeatedly, see `strip_affixes`.
+    #[serde(default)]
+    strip_prefixes: Vec<String>,
+    /// Suffixes (e.g. " ltd", ".L") removed like `strip_prefixes`, so
+    /// "The Foo Ltd." loses both "the " and " ltd.".
+    #[serde(default)]
+    strip_suffixes: Vec<String>,
+    /// Replace every character that is neither alphanumeric, whitespace nor
+    /// a combining mark with a space, after affix stripping.
+    #[serde(default)]
+    strip_punctuation: bool,
+    /// Trim and collapse whitespace runs into single spaces, after
+    /// `strip_punctuation`.
+    #[serde(default)]
+    normalize_whitespace: bool,
+    /// Compare Unicode case-folded strings, after the other options.
+    #[serde(default)]
+    case_insensitive: bool,
+    /// Sort the characters of each string, last, so the metric compares
+    /// character multisets and anagrams are identical.
+    #[serde(default)]
+    sort_chars: bool,
+    /// Pairs that are equal at this tier score `identical_score` without
+    /// running the metric.
+    #[serde(default)]
+    exact_tier: ExactTier,
+    /// Keep the preprocessed form of each distinct string in a
+    /// `PreprocessCache` for the evaluation, instead of recomputing it for
+    /// every row it appears in.
+    #[serde(default)]
+    cache_preprocessed: bool,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalizationForm {
+    Nfc,
+    Nfkc,
+    Nfkd,
+}
+
+impl NormalizationForm {
+    fn apply(self, s: &str) -> String {
+        match self {
+            NormalizationForm::Nfc => s.nfc().collect(),
+            NormalizationForm::Nfkc => s.nfkc().collect(),
+            NormalizationForm::Nfkd => s.nfkd().collect(),
+        }
+    }
+}
+
+/// Graded equality pre-check run before a metric: each tier also accepts
+/// the pairs of the tiers before it.
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ExactTier {
+    #[default]
+    Off,
+    /// Byte equality.
+    Bytes,
+    /// Equality after Unicode case folding.
+    Casefold,
+    /// Casefolded equality after trimming and collapsing whitespace runs.
+    Whitespace,
+}
+
+impl ExactTier {
+    /// Whether `a` and `b` are equal at this tier. Empty strings never
+    /// match, since several metrics give them a score of their own.
+    fn matches(self, a: &str, b: &str) -> bool {
+        if self == ExactTier::Off || a.is_empty() || b.is_empty() {
+            return false;
+        }
+        a == b
+            || (self >= ExactTier::Casefold && casefold(a).eq(casefold(b)))
+            || (self >= ExactTier::Whitespace && casefold_words(a).eq(casefold_words(b)))
+    }
+}
+
+/// Unicode full case folding: lowercasing, except for the characters that
+/// fold to something else (e.g. "ß" to "ss", final "ς" to "σ").
+fn casefold(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(|c| {
+        let folded = special_folding(c);
+        let lowercase = folded.is_none().then(|| c.to_lowercase());
+        folded
+            .into_iter()
+            .flat_map(str::chars)
+            .chain(lowercase.into_iter().flatten())
+    })
+}
+
+/// The case foldings that differ from `char::to_lowercase`, from Unicode's
+/// `CaseFolding.txt` (statuses C and F) for the Latin and Greek scripts.
+fn special_folding(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' | 'ẞ' => "ss",
+        'ſ' => "s",
+        'ŉ' => "ʼn",
+        'ﬀ' => "ff",
+        'ﬁ' => "fi",
+        'ﬂ' => "fl",
+        'ﬃ' => "ffi",
+        'ﬄ' => "ffl",
+        'ﬅ' | 'ﬆ' => "st",
+        'ς' => "σ",
+        'ϐ' => "β",
+        'ϑ' => "θ",
+        'ϕ' => "φ",
+        'ϖ' => "π",
+        'ϰ' => "κ",
+        'ϱ' => "ρ",
+        'ϵ' => "ε",
+        '\u{345}' | '\u{1fbe}' => "ι",
+        _ => return None,
+    })
+}
+
+/// `casefold` with every whitespace run (including leading ones) read as a
+/// single space and trailing whitespace dropped.
+fn casefold_words(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.split_whitespace()
+        .flat_map(|word| std::iter::once(' ').chain(casefold(word)))
+}
+
+fn is_punctuation(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace() && !is_combining_mark(c)
+}
+
+/// Repeatedly removes any of `prefixes` from the start and any of `suffixes`
+/// from the end of `s` (ASCII case-insensitive) until none matches, never
+/// stripping the whole string.
+fn strip_affixes<'a>(mut s: &'a str, prefixes: &[String], suffixes: &[String]) -> &'a str {
+    // `rest` is what is left of the string, so the guard applies to it.
+    let matches = |rest: &str, part: Option<&str>, affix: &str| {
+        !affix.is_empty()
+            && affix.len() < rest.len()
+            && part.is_some_and(|p| p.eq_ignore_ascii_case(affix))
+    };
+    loop {
+        let prefix = prefixes.iter().find(|p| matches(s, s.get(..p.len()), p));
+        let suffix = suffixes
+            .iter()
+            .find(|p| matches(s, s.len().checked_sub(p.len()).and_then(|i| s.get(i..)), p));
+        match (prefix, suffix) {
+            (Some(prefix), _) => s = &s[prefix.len()..],
+            (None, Some(suffix)) => s = &s[..s.len() - suffix.len()],
+            (None, None) => return s,
+        }
+    }
+}
+
+impl PreprocessKwargs {
+    /// Whether no option is set at all, including `exact_tier` and
+    /// `cache_preprocessed`, which `is_noop` ignores since they never
+    /// rewrite a string.
+    pub fn is_unset(&self) -> bool {
+        self.is_noop() && self.exact_tier == ExactTier::Off && !self.cache_preprocessed
+    }
+
+    pub fn is_noop(&self) -> bool {
+        self.unicode_normalize.is_none()
+            && !self.strip_accents
+            && !self.transliterate
+            && !self.strip_punctuation
+            && !self.normalize_whitespace
+            && !self.arabic_romanization
+            && !self.case_insensitive
+            && !self.sort_chars
+            && self.strip_prefixes.is_empty()
+            && self.strip_suffixes.is_empty()
+    }
+
+    pub fn apply<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        let mut s = match self.normalize(s) {
+            Cow::Borrowed(s) => {
+                Cow::Borrowed(strip_affixes(s, &self.strip_prefixes, &self.strip_suffixes))
+            }
+            Cow::Owned(s) => Cow::Owned(
+                strip_affixes(&s, &self.strip_prefixes, &self.strip_suffixes).to_string(),
+            ),
+        };
+        if self.strip_punctuation && s.chars().any(is_punctuation) {
+            s = Cow::Owned(
+                s.chars()
+                    .map(|c| if is_punctuation(c) { ' ' } else { c })
+                    .collect(),
+            );
+        }
+        if self.normalize_whitespace {
+            let collapsed = s.split_whitespace().collect::<Vec<_>>().join(" ");
+            if collapsed != s {
+                s = Cow::Owned(collapsed);
+            }
+        }
+        if self.arabic_romanization {
+            s = Cow::Owned(arabic::normalize_arabic_name(&s));
+        }
+        if self.case_insensitive {
+            s = Cow::Owned(casefold(&s).collect());
+        }
+        if self.sort_chars {
+            let mut chars: Vec<char> = s.chars().collect();
+            chars.sort_unstable();
+            s = Cow::Owned(chars.into_iter().collect());
+        }
+        s
+    }
+
+    /// `unicode_normalize`, `strip_accents` and `transliterate`, the first
+    /// steps of `apply`.
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        let mut s = Cow::Borrowed(s);
+        if let Some(form) = self.unicode_normalize {
+            s = Cow::Owned(form.apply(&s));
+        }
+        if self.strip_accents && !s.is_ascii() {
+            let stripped = s.nfd().filter(|&c| !is_combining_mark(c));
+            s = Cow::Owned(match self.unicode_normalize {
+                Some(NormalizationForm::Nfkd) => stripped.collect(),
+                _ => stripped.nfc().collect(),
+            });
+        }
+        if self.transliterate && !s.is_ascii() {
+            s = Cow::Owned(transliterate::transliterate(&s));
+        }
+        s
+    }
+
+    /// Wraps `native_fn` so both of its inputs are preprocessed first, and
+    /// pairs matching `exact_tier` after preprocessing score
+    /// `identical_score` (the metric's score for two equal strings) directly.
+    pub fn wrap<F, K>(
+        self,
+        identical_score: K,
+        native_fn: F,
+    ) -> impl Fn(&str, &str) -> K + Sync + Send
+    where
+        F: Fn(&str, &str) -> K + Sync + Send,
+        K: Copy + Sync + Send,
+    {
+        self.wrap_with_identical(move |_| identical_score, native_fn)
+    }
+
+    /// `wrap` for metrics whose score for two equal strings depends on the
+    /// string, computed from the first input by `identical_score`.
+    pub fn wrap_with_identical<I, F, K>(
+        self,
+        identical_score: I,
+        native_fn: F,
+    ) -> impl Fn(&str, &str) -> K + Sync + Send
+    where
+        I: Fn(&str) -> K + Sync + Send,
+        F: Fn(&str, &str) -> K + Sync + Send,
+    {
+        let exact_tier = self.exact_tier;
+        let native_fn = move |a: &str, b: &str| {
+            if exact_tier.matches(a, b) {
+                identical_score(a)
+            } else {
+                native_fn(a, b)
+            }
+        };
+        let cache = self.cache_preprocessed.then(PreprocessCache::default);
+        let literals: [Literal; 2] = Default::default();
+        move |a, b| {
+            if self.is_noop() {
+                native_fn(a, b)
+            } else {
+                let a = self.prepare(a, &literals[0], cache.as_ref());
+                let b = self.prepare(b, &literals[1], cache.as_ref());
+                native_fn(&a, &b)
+            }
+        }
+    }
+
+    /// `apply` for one side of a wrapped pair, reusing that side's
+    /// `Literal` when `s` is its string and the evaluation's cache, if any,
+    /// otherwise.
+    fn prepare<'a>(
+        &self,
+        s: &'a str,
+        literal: &'a Literal,
+        cache: Option<&PreprocessCache>,
+    ) -> Prepared<'a> {
+        let (raw, prepared) = literal.get_or_init(|| (Box::from(s), self.apply(s).into_owned()));
+        if **raw == *s {
+            Prepared::Str(Cow::Borrowed(prepared))
+        } else if let Some(cache) = cache {
+            Prepared::Shared(cache.get_or_insert(s, |s| self.apply(s).into_owned()))
+        } else {
+            Prepared::Str(self.apply(s))
+        }
+    }
+}
+
+/// The first string seen on one side of a wrapped pair with its
+/// preprocessed form. A broadcast literal is that string on every row, so
+/// it is preprocessed once per evaluation instead of once per row.
+type Literal = OnceLock<(Box<str>, String)>;
+
+/// A preprocessed string, borrowed, computed for this row or shared with
+/// the `PreprocessCache`.
+enum Prepared<'a> {
+    Str(Cow<'a, str>),
+    Shared(Arc<str>),
+}
+
+impl Deref for Prepared<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Prepared::Str(s) => s,
+            Prepared::Shared(s) => s,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn affixes(list: &[&str]) -> Vec<String> {
+        list.iter().map(|affix| affix.to_string()).collect()
+    }
+
+    #[test]
+    fn repeated_affixes_keep_the_last_occurrence() {
+        assert_eq!(strip_affixes("abab", &affixes(&["ab"]), &[]), "ab");
+        assert_eq!(strip_affixes("ababx", &affixes(&["ab"]), &[]), "x");
+        assert_eq!(strip_affixes("x.L.L", &[], &affixes(&[".l"])), "x");
+        assert_eq!(strip_affixes(".L.L", &[], &affixes(&[".l"])), ".L");
+    }
+
+    #[test]
+    fn prefix_and_suffix_never_cover_the_whole_string() {
+        let prefixes = affixes(&["the "]);
+        let suffixes = affixes(&["ltd"]);
+        assert_eq!(strip_affixes("The Ltd", &prefixes, &suffixes), "Ltd");
+        assert_eq!(strip_affixes("The Foo Ltd", &prefixes, &suffixes), "Foo ");
+        assert_eq!(strip_affixes("ltd", &[], &suffixes), "ltd");
+    }
+
+    #[test]
+    fn non_ascii_boundaries() {
+        // Affixes that would end inside a multi-byte character never match.
+        assert_eq!(strip_affixes("éa", &affixes(&["x"]), &[]), "éa");
+        assert_eq!(strip_affixes("ab€", &[], &affixes(&["xy"])), "ab€");
+        assert_eq!(strip_affixes("é€ x", &affixes(&["é€"]), &[]), " x");
+        assert_eq!(
+            strip_affixes("x 株式会社", &[], &affixes(&[" 株式会社"])),
+            "x"
+        );
+        // Matching is ASCII case-insensitive only.
+        assert_eq!(strip_affixes("Éa", &affixes(&["é"]), &[]), "Éa");
+    }
+
+    #[test]
+    fn broadcast_literal_is_reused_only_for_its_own_string() {
+        for cache_preprocessed in [false, true] {
+            let preprocess = PreprocessKwargs {
+                case_insensitive: true,
+                cache_preprocessed,
+                ..Default::default()
+            };
+            let wrapped =
+                preprocess.wrap_with_identical(|_| String::new(), |a, b| format!("{a}|{b}"));
+            for row in ["Acme", "ACME Corp", "Acme", "acme corp"] {
+                assert_eq!(
+                    wrapped(row, "ACME Corp"),
+                    format!("{}|acme corp", row.to_lowercase())
+                );
+            }
+        }
+    }
+}
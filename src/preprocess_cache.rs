@@ -0,0 +1,48 @@
+// per-evaluation cache of preprocessed strings
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+const SHARDS: usize = 64;
+
+type Shard = Mutex<HashMap<Box<str>, Arc<str>>>;
+
+/// Preprocessed forms of the strings seen by one expression evaluation,
+/// sharded so the pool threads scoring its splits rarely contend. It is
+/// owned by the closure `PreprocessKwargs::wrap` returns and freed with it
+/// when the evaluation finishes, so nothing outlives the expression and a
+/// repeated value is only preprocessed once per evaluation.
+pub struct PreprocessCache {
+    shards: Vec<Shard>,
+}
+
+impl Default for PreprocessCache {
+    fn default() -> PreprocessCache {
+        PreprocessCache {
+            shards: (0..SHARDS).map(|_| Mutex::new(HashMap::new())).collect(),
+        }
+    }
+}
+
+impl PreprocessCache {
+    /// Returns the cached preprocessed form of `s`, computing and storing
+    /// it with `preprocess` on a miss. `preprocess` runs outside the lock.
+    pub fn get_or_insert(&self, s: &str, preprocess: impl FnOnce(&str) -> String) -> Arc<str> {
+        let mut hasher = DefaultHasher::new();
+        s.hash(&mut hasher);
+        let shard = &self.shards[hasher.finish() as usize % SHARDS];
+        if let Some(hit) = shard.lock().unwrap().get(s) {
+            return hit.clone();
+        }
+
+        let value: Arc<str> = Arc::from(preprocess(s));
+        shard
+            .lock()
+            .unwrap()
+            .entry(Box::from(s))
+            .or_insert(value)
+            .clone()
+    }
+}
@@ -0,0 +1,146 @@
+// string segmentation into comparison units
+
+use crate::buffers::InlineChars;
+use crate::weighted_DL;
+use regex::Regex;
+use serde::Deserialize;
+use std::hash::Hash;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What counts as one element of a string in the DP.
+#[derive(Deserialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Unit {
+    /// Extended grapheme clusters, i.e. user-perceived characters such as
+    /// emoji sequences or letters with combining accents.
+    Grapheme,
+    /// Unicode scalar values.
+    #[default]
+    Char,
+    /// UTF-8 bytes.
+    Byte,
+    /// Characters, except that each maximal run of ASCII digits is a single
+    /// element, equal only to the identical run: `"v10"` and `"v11"` differ
+    /// by one substitution rather than by one digit.
+    DigitRun,
+    /// Like `DigitRun`, but digit runs compare by numeric value, so `"007"`
+    /// and `"7"` are the same element.
+    NumericRun,
+}
+
+/// The number of `unit`s in `s`.
+pub fn len(s: &str, unit: Unit) -> usize {
+    match unit {
+        Unit::Grapheme => s.graphemes(true).count(),
+        Unit::Char => s.chars().count(),
+        Unit::Byte => s.len(),
+        Unit::DigitRun | Unit::NumericRun => digit_runs(s, unit == Unit::NumericRun).len(),
+    }
+}
+
+/// The elements of `s` under `Unit::DigitRun`: every non-digit character
+/// alone, and every maximal run of ASCII digits as one slice. With `numeric`
+/// the leading zeros of a run are dropped (keeping a lone `"0"`), so runs of
+/// equal value are equal slices.
+pub fn digit_runs(s: &str, numeric: bool) -> Vec<&str> {
+    let mut runs = Vec::new();
+    let mut rest = s;
+    while let Some(c) = rest.chars().next() {
+        let end = if c.is_ascii_digit() {
+            rest.find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len())
+        } else {
+            c.len_utf8()
+        };
+        let (run, tail) = rest.split_at(end);
+        runs.push(if numeric && c.is_ascii_digit() {
+            match run.trim_start_matches('0') {
+                "" => "0",
+                value => value,
+            }
+        } else {
+            run
+        });
+        rest = tail;
+    }
+    runs
+}
+
+/// Damerau-Levenshtein distance between `a` and `b` counted in `unit`s, or
+/// `None` once it exceeds `max_distance`.
+pub fn damerau_levenshtein(
+    a: &str,
+    b: &str,
+    unit: Unit,
+    max_distance: Option<usize>,
+) -> Option<usize> {
+    match unit {
+        Unit::Grapheme => {
+            let a: Vec<&str> = a.graphemes(true).collect();
+            let b: Vec<&str> = b.graphemes(true).collect();
+            elems_damerau_levenshtein(&a, &b, max_distance)
+        }
+        Unit::Char => {
+            let a: InlineChars = a.chars().collect();
+            let b: InlineChars = b.chars().collect();
+            elems_damerau_levenshtein(&a, &b, max_distance)
+        }
+        Unit::Byte => elems_damerau_levenshtein(a.as_bytes(), b.as_bytes(), max_distance),
+        Unit::DigitRun | Unit::NumericRun => {
+            let numeric = unit == Unit::NumericRun;
+            let a = digit_runs(a, numeric);
+            let b = digit_runs(b, numeric);
+            elems_damerau_levenshtein(&a, &b, max_distance)
+        }
+    }
+}
+
+/// The non-empty tokens of `s`, split at whitespace or, when given, at any
+/// of the characters of `separators`.
+pub fn tokens<'a>(s: &'a str, separators: Option<&str>) -> Vec<&'a str> {
+    match separators {
+        Some(separators) => s
+            .split(|c| separators.contains(c))
+            .filter(|token| !token.is_empty())
+            .collect(),
+        None => s.split_whitespace().collect(),
+    }
+}
+
+/// How a string is split into words.
+pub enum Tokenizer {
+    Whitespace,
+    /// At any of these characters.
+    Separators(String),
+    /// At every match of the pattern.
+    Pattern(Regex),
+}
+
+impl Tokenizer {
+    /// The non-empty tokens of `s`.
+    pub fn tokens<'a>(&self, s: &'a str) -> Vec<&'a str> {
+        match self {
+            Tokenizer::Whitespace => tokens(s, None),
+            Tokenizer::Separators(separators) => tokens(s, Some(separators)),
+            Tokenizer::Pattern(pattern) => {
+                pattern.split(s).filter(|token| !token.is_empty()).collect()
+            }
+        }
+    }
+}
+
+/// Damerau-Levenshtein distance between two element sequences, bounded by
+/// `max_distance` when given.
+pub fn elems_damerau_levenshtein<Elem>(
+    a: &[Elem],
+    b: &[Elem],
+    max_distance: Option<usize>,
+) -> Option<usize>
+where
+    Elem: Eq + Hash + Clone,
+{
+    match max_distance {
+        Some(max_distance) => weighted_DL::bounded_damerau_levenshtein(a, b, max_distance),
+        None => Some(strsim::generic_damerau_levenshtein(a, b)),
+    }
+}
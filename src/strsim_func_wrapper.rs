@@ -1,25 +1,293 @@
-use crate::apply_utils::parallel_apply;
+use crate::acronym;
+use crate::address;
+use crate::alignment::{self, EditOp};
+use crate::apply_utils::{
+    aggregate_list_scores, max_distance_for_cutoff, parallel_apply_bool_with,
+    parallel_apply_both_directions, parallel_apply_bytes, parallel_apply_distance_summary,
+    parallel_apply_list, parallel_apply_lists, parallel_apply_matches,
+    parallel_apply_metric_profile, parallel_apply_opt_with, parallel_apply_partial_matches,
+    parallel_apply_str, parallel_apply_str_fields, parallel_apply_str_list, parallel_apply_str_opt,
+    parallel_apply_str_pair, parallel_apply_str_scored, parallel_apply_str_with_candidates,
+    parallel_apply_str_with_lists, parallel_apply_upper_triangle, parallel_apply_warm_start,
+    parallel_apply_weighted_pairs, parallel_apply_with, parallel_apply_zipped_lists,
+    parallel_find_matches, parallel_fold, parallel_fold_strings, prefiltered, string_values,
+    ApplyKwargs, ScoreAggregation, Threshold,
+};
+use crate::automaton::{LevenshteinAutomaton, Trie};
+use crate::bit_parallel;
+use crate::bktree::BkTree;
+use crate::buffers::{with_buffers, with_chars, InlineChars};
+use crate::cascade::Cascade;
+use crate::cluster;
+use crate::company_name::CompanyNameNormalizer;
+use crate::corpus::{self, TokenStats};
+use crate::cost_model::{CharCosts, CostModel};
+use crate::deadline;
+use crate::fuzz;
+use crate::fuzzy_join::{BlockIndex, Blocking};
+use crate::gotoh;
+use crate::histogram::Histogram;
+use crate::minhash;
+use crate::ngram;
+use crate::partial;
+use crate::person_name::{self, PersonNameOptions};
+use crate::phonetic;
+use crate::preprocess::PreprocessKwargs;
+use crate::segment::{self, Unit};
+use crate::subsequence;
 use crate::weighted_DL;
 use polars::prelude::*;
-use polars_core::datatypes::{Float64Type, Int64Type};
+use polars_core::datatypes::{Float32Type, Float64Type, Int64Type};
 use pyo3_polars::derive::polars_expr;
 use pyo3_polars::derive::CallerContext;
 
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::hash::Hash;
 
 pub(super) fn native_damerau_levenshtein(a: &str, b: &str) -> i64 {
-    strsim::damerau_levenshtein(a, b) as i64
+    with_chars(a, b, bit_parallel::damerau_levenshtein) as i64
 }
 
 pub(super) fn native_normalized_damerau_levenshtein(a: &str, b: &str) -> f64 {
-    let count_a = a.chars().count();
-    let count_b = b.chars().count();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
 
-    if count_a == 0 || count_b == 0 {
+    with_chars(a, b, |a_chars, b_chars| {
+        let distance = bit_parallel::damerau_levenshtein(a_chars, b_chars);
+        1.0 - distance as f64 / a_chars.len().max(b_chars.len()) as f64
+    })
+}
+
+/// `native_normalized_damerau_levenshtein`, returning 0.0 without finishing
+/// the DP once the similarity is known to be below `score_cutoff`.
+pub(super) fn native_cutoff_normalized_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    score_cutoff: f64,
+) -> f64 {
+    let max_len = a.chars().count().max(b.chars().count());
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let max_distance = max_distance_for_cutoff(score_cutoff, max_len);
+    match native_bounded_damerau_levenshtein(a, b, max_distance) {
+        Some(distance) => 1.0 - distance as f64 / max_len as f64,
+        None => 0.0,
+    }
+}
+
+pub(super) fn native_token_damerau_levenshtein(a: &str, b: &str, separators: Option<&str>) -> i64 {
+    let a_tokens = segment::tokens(a, separators);
+    let b_tokens = segment::tokens(b, separators);
+    strsim::generic_damerau_levenshtein(&a_tokens, &b_tokens) as i64
+}
+
+/// `native_cutoff_normalized_damerau_levenshtein` with the distance and
+/// lengths counted in `unit`s, without a cutoff when `score_cutoff` is
+/// `None`.
+pub(super) fn native_unit_normalized_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    unit: Unit,
+    score_cutoff: Option<f64>,
+) -> f64 {
+    if a.is_empty() || b.is_empty() {
         return 0.0;
     }
+    let max_len = segment::len(a, unit).max(segment::len(b, unit));
+    let max_distance = score_cutoff.map(|cutoff| max_distance_for_cutoff(cutoff, max_len));
+    match segment::damerau_levenshtein(a, b, unit, max_distance) {
+        Some(distance) => 1.0 - distance as f64 / max_len as f64,
+        None => 0.0,
+    }
+}
+
+/// Damerau-Levenshtein distance, the distance divided by the longer length
+/// and the similarity as `native_normalized_damerau_levenshtein` reports it
+/// (0.0 when either string is empty), from a single DP.
+pub(super) fn native_damerau_levenshtein_summary(a: &str, b: &str) -> (i64, f64, f64) {
+    let (distance, max_len) = with_chars(a, b, |a_chars, b_chars| {
+        let distance = bit_parallel::damerau_levenshtein(a_chars, b_chars);
+        (distance, a_chars.len().max(b_chars.len()))
+    });
+    if max_len == 0 {
+        return (0, 0.0, 0.0);
+    }
+    let normalized = distance as f64 / max_len as f64;
+    let similarity = if a.is_empty() || b.is_empty() {
+        0.0
+    } else {
+        1.0 - normalized
+    };
+    (distance as i64, normalized, similarity)
+}
+
+const SIMILARITY_PROFILE_METRICS: [&str; 4] =
+    ["normalized_dl", "jaro_winkler", "dice", "lcs_ratio"];
+
+/// Damerau-Levenshtein distance together with, in the order of
+/// `SIMILARITY_PROFILE_METRICS`, the normalized Damerau-Levenshtein
+/// similarity (0.0 when either string is empty), Jaro-Winkler, the Dice
+/// similarity of the character bigrams and the LCS ratio (`fuzz::ratio`),
+/// all over one decoding of both strings into chars.
+pub(super) fn native_similarity_profile(a: &str, b: &str) -> (i64, [f64; 4]) {
+    with_buffers(|[a_chars, b_chars]: [&mut Vec<char>; 2]| {
+        a_chars.extend(a.chars());
+        b_chars.extend(b.chars());
+        let distance = bit_parallel::damerau_levenshtein(a_chars, b_chars);
+        let normalized = if a_chars.is_empty() || b_chars.is_empty() {
+            0.0
+        } else {
+            1.0 - distance as f64 / a_chars.len().max(b_chars.len()) as f64
+        };
+        (
+            distance as i64,
+            [
+                normalized,
+                strsim::generic_jaro_winkler(&*a_chars, &*b_chars),
+                ngram::dice(a_chars, b_chars, 2),
+                fuzz::ratio(a_chars, b_chars),
+            ],
+        )
+    })
+}
+
+#[derive(Deserialize)]
+pub struct MetricKwargs {
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct TokenDLKwargs {
+    /// Characters separating tokens; whitespace when unset.
+    #[serde(default)]
+    separators: Option<String>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct SimilarityKwargs {
+    /// Scores below this come back as null.
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    /// What the distance behind the score counts
+    /// (`normalized_damerau_levenshtein` only).
+    #[serde(default)]
+    unit: Unit,
+    #[serde(flatten)]
+    window: PartialWindowKwargs,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+/// Windows of the longer string the partial expressions compare the shorter
+/// one against.
+#[derive(Deserialize, Clone, Copy)]
+pub struct PartialWindowKwargs {
+    /// Window length, the shorter string's length by default; longer windows
+    /// tolerate insertions in the longer string.
+    #[serde(default)]
+    window: Option<usize>,
+    /// Step between window starts.
+    #[serde(default = "default_stride")]
+    stride: usize,
+}
+
+impl Default for PartialWindowKwargs {
+    fn default() -> Self {
+        PartialWindowKwargs {
+            window: None,
+            stride: default_stride(),
+        }
+    }
+}
+
+impl PartialWindowKwargs {
+    fn validated(self) -> PolarsResult<Self> {
+        if self.window == Some(0) || self.stride == 0 {
+            return Err(PolarsError::ComputeError(
+                "window and stride must be positive.".into(),
+            ));
+        }
+        Ok(self)
+    }
+
+    /// The length of the windows of a `longer_len` character string
+    /// compared against a `shorter_len` character one.
+    fn len(&self, shorter_len: usize, longer_len: usize) -> usize {
+        self.window.unwrap_or(shorter_len).min(longer_len)
+    }
+}
+
+fn default_stride() -> usize {
+    1
+}
+
+/// Kwargs of `partial_damerau_levenshtein_locate`, whose offsets refer to
+/// the strings as given, so it takes no preprocessing options.
+#[derive(Deserialize)]
+pub struct PartialLocateKwargs {
+    #[serde(flatten)]
+    window: PartialWindowKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+/// Kwargs of the alignment expressions, whose positions refer to the
+/// strings as given, so they take no preprocessing options.
+#[derive(Deserialize)]
+pub struct AlignmentKwargs {
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct DistanceKwargs {
+    /// Stop the DP once the distance is known to exceed this bound and return
+    /// `max_distance + 1` instead (null with `null_above_max_distance`).
+    #[serde(default)]
+    max_distance: Option<usize>,
+    #[serde(default)]
+    null_above_max_distance: bool,
+    /// What the distance counts (`damerau_levenshtein` only).
+    #[serde(default)]
+    unit: Unit,
+    #[serde(flatten)]
+    window: PartialWindowKwargs,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+impl DistanceKwargs {
+    /// The result for a distance from a bounded native function, `None`
+    /// when it exceeded `max_distance`.
+    fn bounded_result(&self, max_distance: usize, distance: Option<usize>) -> Option<i64> {
+        match distance {
+            Some(distance) => Some(distance as i64),
+            None if self.null_above_max_distance => None,
+            None => Some(max_distance as i64 + 1),
+        }
+    }
 
-    strsim::normalized_damerau_levenshtein(a, b) as f64
+    /// `bounded_result` when a `max_distance` is set.
+    fn result(&self, distance: Option<usize>) -> Option<i64> {
+        match self.max_distance {
+            Some(max_distance) => self.bounded_result(max_distance, distance),
+            None => distance.map(|distance| distance as i64),
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -28,6 +296,63 @@ pub struct WeightedDLKwargs {
     weighted_geometric_ratio: f64,
     #[serde(default = "default_normalized")]
     normalized: bool,
+    #[serde(default)]
+    unit_edits: bool,
+    /// "geometric" (by `weighted_geometric_ratio` and `plateau_len`), "linear",
+    /// "exponential" (by `exponential_decay`), "gaussian" (by
+    /// `gaussian_center` and `gaussian_width`) or "step" (by
+    /// `step_breakpoints` and `step_weights`).
+    #[serde(default = "default_profile")]
+    profile: String,
+    #[serde(default)]
+    plateau_len: usize,
+    #[serde(default = "default_exponential_decay")]
+    exponential_decay: f64,
+    #[serde(default = "default_gaussian_center")]
+    gaussian_center: f64,
+    #[serde(default = "default_gaussian_width")]
+    gaussian_width: f64,
+    #[serde(default)]
+    step_breakpoints: Vec<usize>,
+    #[serde(default)]
+    step_weights: Vec<f64>,
+    #[serde(default = "default_direction")]
+    direction: String,
+    /// Per-character multipliers of the positional weights, taking
+    /// precedence over the character-class weights below.
+    #[serde(default)]
+    char_weights: HashMap<char, f64>,
+    #[serde(default = "default_class_weight")]
+    digit_weight: f64,
+    #[serde(default = "default_class_weight")]
+    alpha_weight: f64,
+    #[serde(default = "default_class_weight")]
+    punct_weight: f64,
+    #[serde(default)]
+    cascade: Option<Cascade>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+/// List items are compared as given, so lists take no preprocessing options.
+#[derive(Deserialize)]
+pub struct ListDLKwargs {
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct ListWeightedDLKwargs {
+    #[serde(default = "default_weighted_geometric_ratio")]
+    weighted_geometric_ratio: f64,
+    #[serde(default = "default_normalized")]
+    normalized: bool,
+    #[serde(default)]
+    unit_edits: bool,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
 }
 
 #[derive(Deserialize)]
@@ -36,215 +361,3290 @@ pub struct WeightedDLByWordsKwargs {
     weighted_geometric_ratio: f64,
     #[serde(default = "default_normalized")]
     normalized: bool,
+    #[serde(default)]
+    unit_edits: bool,
     #[serde(default = "default_agg")]
     agg: String,
+    #[serde(default = "default_word_alignment")]
+    word_alignment: String,
+    #[serde(default = "default_weighted_geometric_ratio")]
+    word_weighted_geometric_ratio: f64,
+    /// Distance counted for every surplus word of the longer string.
+    #[serde(default)]
+    unmatched_word_distance: Option<f64>,
+    /// Characters splitting the strings into words instead of whitespace.
+    #[serde(default)]
+    separators: Option<String>,
+    /// Regex splitting the strings into words instead of whitespace.
+    #[serde(default)]
+    separator_pattern: Option<String>,
+    #[serde(default)]
+    cascade: Option<Cascade>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_weighted_geometric_ratio() -> f64 {
+    1.0
+}
+
+fn default_normalized() -> bool {
+    false
+}
+
+fn default_agg() -> String {
+    "mean".to_string()
+}
+
+fn default_word_alignment() -> String {
+    "positional".to_string()
+}
+
+fn default_profile() -> String {
+    "geometric".to_string()
+}
+
+fn default_exponential_decay() -> f64 {
+    1.0
+}
+
+fn default_gaussian_center() -> f64 {
+    0.5
+}
+
+fn default_gaussian_width() -> f64 {
+    0.25
+}
+
+fn default_direction() -> String {
+    "descending".to_string()
+}
+
+fn default_class_weight() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+pub struct WarmStartKwargs {
+    #[serde(default = "default_metric")]
+    metric: String,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct NearestTermsKwargs {
+    /// The vocabulary, unless it is passed as a second column.
+    #[serde(default)]
+    vocabulary: Option<Vec<String>>,
+    #[serde(default = "default_max_term_distance")]
+    max_distance: usize,
+    /// Keep only the `k` nearest terms per row.
+    #[serde(default)]
+    k: Option<usize>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_max_term_distance() -> usize {
+    2
+}
+
+#[derive(Deserialize)]
+pub struct DictionarySearchKwargs {
+    /// The dictionary, unless it is passed as a second column.
+    #[serde(default)]
+    dictionary: Option<Vec<String>>,
+    #[serde(default = "default_max_edits")]
+    max_distance: usize,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_max_edits() -> usize {
+    1
+}
+
+#[derive(Deserialize)]
+pub struct SuggestCorrectionKwargs {
+    /// The dictionary, unless it is passed as a second column.
+    #[serde(default)]
+    dictionary: Option<Vec<String>>,
+    #[serde(default = "default_max_term_distance")]
+    max_distance: usize,
+    /// Word frequencies breaking ties between equally close words; missing
+    /// words count as 0.
+    #[serde(default)]
+    frequencies: HashMap<String, f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct BestMatchKwargs {
+    /// The controlled vocabulary every row is matched against.
+    candidates: Vec<String>,
+    #[serde(default = "default_metric")]
+    metric: String,
+    /// Return the `k` best candidates per row instead of the best one.
+    #[serde(default)]
+    k: Option<usize>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+/// Kwargs of the expressions matching strings against a blocked index
+/// (`fuzzy_join_indices`, `similarity_cluster`).
+#[derive(Deserialize)]
+pub struct BlockedMatchKwargs {
+    #[serde(default = "default_metric")]
+    metric: String,
+    /// Pairs match at or above this score for similarities, at or below it
+    /// for distances.
+    threshold: f64,
+    #[serde(default = "default_blocking")]
+    blocking: Blocking,
+    #[serde(default = "default_block_size")]
+    block_prefix_len: usize,
+    #[serde(default = "default_block_size")]
+    block_q: usize,
+    #[serde(default = "default_lsh_bands")]
+    lsh_bands: usize,
+    #[serde(default = "default_lsh_rows")]
+    lsh_rows: usize,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_blocking() -> Blocking {
+    Blocking::Qgram
+}
+
+fn default_block_size() -> usize {
+    3
+}
+
+fn default_lsh_bands() -> usize {
+    32
+}
+
+fn default_lsh_rows() -> usize {
+    4
+}
+
+fn default_metric() -> String {
+    "normalized_damerau_levenshtein".to_string()
+}
+
+#[derive(Deserialize)]
+pub struct ScoreHistogramKwargs {
+    #[serde(default = "default_metric")]
+    metric: String,
+    #[serde(default = "default_bins")]
+    bins: usize,
+    #[serde(default)]
+    lower: f64,
+    #[serde(default = "default_upper")]
+    upper: f64,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct PairwiseMatrixKwargs {
+    #[serde(default = "default_metric")]
+    metric: String,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct ZippedListKwargs {
+    #[serde(default = "default_metric")]
+    metric: String,
+    #[serde(default)]
+    agg: Option<ScoreAggregation>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct CandidateListKwargs {
+    #[serde(default = "default_metric")]
+    metric: String,
+    #[serde(default)]
+    agg: Option<ScoreAggregation>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct CompositeSimilarityKwargs {
+    metrics: Vec<String>,
+    weights: Vec<f64>,
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct EnsembleSimilarityKwargs {
+    metrics: Vec<String>,
+    weights: Vec<f64>,
+    /// Added to the weighted sum, e.g. a model's intercept.
+    #[serde(default)]
+    bias: f64,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_bins() -> usize {
+    10
+}
+
+fn default_upper() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+pub struct BoundedMismatchKwargs {
+    #[serde(default = "default_max_mismatches")]
+    max_mismatches: usize,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_max_mismatches() -> usize {
+    1
+}
+
+#[derive(Deserialize)]
+pub struct SubsequenceKwargs {
+    #[serde(default)]
+    max_gap: Option<usize>,
+    #[serde(default)]
+    both_directions: bool,
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct AbbreviationKwargs {
+    #[serde(default)]
+    both_directions: bool,
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct AffineGapKwargs {
+    #[serde(default = "default_gap_open")]
+    gap_open: f64,
+    #[serde(default = "default_gap_extend")]
+    gap_extend: f64,
+    #[serde(default = "default_mismatch")]
+    mismatch: f64,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_gap_open() -> f64 {
+    1.0
+}
+
+fn default_gap_extend() -> f64 {
+    0.5
+}
+
+fn default_mismatch() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+pub struct CustomCostDLKwargs {
+    #[serde(default = "default_edit_cost")]
+    ins_cost: f64,
+    #[serde(default = "default_edit_cost")]
+    del_cost: f64,
+    #[serde(default = "default_edit_cost")]
+    sub_cost: f64,
+    #[serde(default = "default_edit_cost")]
+    trans_cost: f64,
+    /// Scale `sub_cost` by the distance between the two keys on a keyboard
+    /// layout, or lower it for OCR confusions.
+    #[serde(default)]
+    cost_model: Option<CostModel>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_edit_cost() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+pub struct FuzzRatioKwargs {
+    #[serde(default = "default_normalized")]
+    normalized: bool,
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct MraKwargs {
+    /// Return the similarity rating instead of the match decision.
+    #[serde(default)]
+    score: bool,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct IsSimilarKwargs {
+    #[serde(default = "default_metric")]
+    metric: String,
+    /// Pairs are similar at or above this score for similarities, at or
+    /// below it for distances.
+    threshold: f64,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct PhoneticSimilarityKwargs {
+    #[serde(default = "default_phonetic_algorithm")]
+    algorithm: phonetic::PhoneticAlgorithm,
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_phonetic_algorithm() -> phonetic::PhoneticAlgorithm {
+    phonetic::PhoneticAlgorithm::Soundex
+}
+
+#[derive(Deserialize)]
+pub struct PersonNameKwargs {
+    #[serde(default = "default_initial_score")]
+    initial_score: f64,
+    #[serde(default = "default_phonetic_score")]
+    phonetic_score: f64,
+    #[serde(default = "default_unmatched_token_weight")]
+    unmatched_token_weight: f64,
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_initial_score() -> f64 {
+    0.9
+}
+
+fn default_phonetic_score() -> f64 {
+    0.8
+}
+
+fn default_unmatched_token_weight() -> f64 {
+    0.5
+}
+
+#[derive(Deserialize)]
+pub struct CompanyNameKwargs {
+    /// Legal suffixes stripped on top of the built-in ones.
+    #[serde(default)]
+    legal_suffixes: Vec<String>,
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct AddressKwargs {
+    #[serde(default = "default_unmatched_token_weight")]
+    unmatched_token_weight: f64,
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct QgramKwargs {
+    #[serde(default = "default_q")]
+    q: usize,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_q() -> usize {
+    2
+}
+
+#[derive(Deserialize)]
+pub struct NgramKwargs {
+    #[serde(default = "default_q")]
+    n: usize,
+    /// Pads both ends of the string with `n - 1` copies of this character.
+    #[serde(default)]
+    pad: Option<char>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_num_hashes() -> usize {
+    128
+}
+
+fn default_shingle_size() -> usize {
+    3
+}
+
+#[derive(Deserialize)]
+pub struct MinHashKwargs {
+    #[serde(default = "default_num_hashes")]
+    num_hashes: usize,
+    #[serde(default = "default_shingle_size")]
+    shingle_size: usize,
+    /// Seeds the hash functions; signatures are only comparable when they
+    /// share `num_hashes`, `shingle_size` and `seed`.
+    #[serde(default)]
+    seed: u64,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct MinHashSimilarityKwargs {
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+#[derive(Deserialize)]
+pub struct SoftTfidfKwargs {
+    /// Jaro-Winkler similarity above which two tokens count as matching.
+    #[serde(default = "default_soft_tfidf_threshold")]
+    threshold: f64,
+    #[serde(default)]
+    score_cutoff: Option<f64>,
+    #[serde(flatten)]
+    preprocess: PreprocessKwargs,
+    #[serde(flatten)]
+    apply: ApplyKwargs,
+}
+
+fn default_soft_tfidf_threshold() -> f64 {
+    0.9
+}
+
+#[derive(Deserialize)]
+pub struct NysiisKwargs {
+    #[serde(default = "default_nysiis_max_length")]
+    max_length: Option<usize>,
+}
+
+fn default_nysiis_max_length() -> Option<usize> {
+    Some(6)
+}
+
+pub(super) fn native_geometric_weighted_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    profile: &weighted_DL::WeightProfile,
+    direction: &str,
+    char_weights: &weighted_DL::CharWeights,
+    normalized: bool,
+    unit_edits: bool,
+) -> f64 {
+    let direction = match direction {
+        "ascending" => weighted_DL::WeightDirection::Ascending,
+        _ => weighted_DL::WeightDirection::Descending,
+    };
+    weighted_DL::profile_weighted_damerau_levenshtein(
+        a,
+        b,
+        profile,
+        direction,
+        char_weights,
+        normalized,
+        unit_edits,
+    )
+}
+
+/// Weighted Damerau-Levenshtein between two list values. String items
+/// compare by value and every other non-float dtype (integers,
+/// categoricals, ...) by its physical representation. Items are weighted
+/// by the given weight lists, or by the geometric weights of the string
+/// variant when there are none.
+pub(super) fn native_list_weighted_damerau_levenshtein(
+    a: &Series,
+    b: &Series,
+    weights: Option<(&Series, &Series)>,
+    weighted_geometric_ratio: f64,
+    normalized: bool,
+    unit_edits: bool,
+) -> PolarsResult<f64> {
+    match list_items(a, b)? {
+        ListItems::Strings(a, b) => list_distance(
+            &a,
+            &b,
+            weights,
+            weighted_geometric_ratio,
+            normalized,
+            unit_edits,
+        ),
+        ListItems::Physical(a, b) => list_distance(
+            &a,
+            &b,
+            weights,
+            weighted_geometric_ratio,
+            normalized,
+            unit_edits,
+        ),
+    }
+}
+
+/// Unweighted Damerau-Levenshtein distance between two list values, with
+/// items compared like in `native_list_weighted_damerau_levenshtein`.
+pub(super) fn native_list_damerau_levenshtein(a: &Series, b: &Series) -> PolarsResult<i64> {
+    let distance = match list_items(a, b)? {
+        ListItems::Strings(a, b) => strsim::generic_damerau_levenshtein(&a, &b),
+        ListItems::Physical(a, b) => strsim::generic_damerau_levenshtein(&a, &b),
+    };
+    Ok(distance as i64)
+}
+
+/// Estimated Jaccard similarity of two MinHash signatures.
+pub(super) fn native_minhash_similarity(a: &Series, b: &Series) -> PolarsResult<f64> {
+    if a.len() != b.len() {
+        return Err(PolarsError::ShapeMismatch(
+            format!(
+                "MinHash signatures must have the same length, got {} and {}.",
+                a.len(),
+                b.len()
+            )
+            .into(),
+        ));
+    }
+    let a: Vec<u64> = a.u64()?.into_no_null_iter().collect();
+    let b: Vec<u64> = b.u64()?.into_no_null_iter().collect();
+    Ok(minhash::estimated_jaccard(&a, &b))
+}
+
+/// The items of two list values in a form that compares by equality.
+enum ListItems<'a> {
+    Strings(Vec<Option<&'a str>>, Vec<Option<&'a str>>),
+    /// The physical values of any other non-float dtype.
+    Physical(Vec<Option<i64>>, Vec<Option<i64>>),
+}
+
+fn list_items<'a>(a: &'a Series, b: &'a Series) -> PolarsResult<ListItems<'a>> {
+    if a.dtype() != b.dtype() {
+        return Err(PolarsError::SchemaMismatch(
+            format!(
+                "List inputs must have the same inner dtype, got {} and {}.",
+                a.dtype(),
+                b.dtype()
+            )
+            .into(),
+        ));
+    }
+    match a.dtype() {
+        DataType::String => Ok(ListItems::Strings(
+            a.str()?.iter().collect(),
+            b.str()?.iter().collect(),
+        )),
+        dtype if dtype.is_float() => Err(PolarsError::ComputeError(
+            "Float list items cannot be compared for equality; cast them first.".into(),
+        )),
+        _ => {
+            let a = a.to_physical_repr().cast(&DataType::Int64)?;
+            let b = b.to_physical_repr().cast(&DataType::Int64)?;
+            Ok(ListItems::Physical(
+                a.i64()?.iter().collect(),
+                b.i64()?.iter().collect(),
+            ))
+        }
+    }
+}
+
+fn list_distance<Elem: Eq + Hash + Clone>(
+    a: &[Elem],
+    b: &[Elem],
+    weights: Option<(&Series, &Series)>,
+    weighted_geometric_ratio: f64,
+    normalized: bool,
+    unit_edits: bool,
+) -> PolarsResult<f64> {
+    Ok(match weights {
+        Some((weight_a, weight_b)) => weighted_DL::explicitly_weighted_damerau_levenshtein(
+            a,
+            b,
+            &list_weights(weight_a, a.len())?,
+            &list_weights(weight_b, b.len())?,
+            normalized,
+            unit_edits,
+        ),
+        None => weighted_DL::descending_weighted_damerau_levenshtein_elems(
+            a,
+            b,
+            weighted_geometric_ratio,
+            normalized,
+            unit_edits,
+        ),
+    })
+}
+
+/// Weighted Damerau-Levenshtein between two strings with caller-supplied
+/// weights, one per character of each string.
+pub(super) fn native_row_weighted_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    weight_a: &Series,
+    weight_b: &Series,
+    normalized: bool,
+    unit_edits: bool,
+) -> PolarsResult<f64> {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+    Ok(weighted_DL::explicitly_weighted_damerau_levenshtein(
+        &a_chars,
+        &b_chars,
+        &list_weights(weight_a, a_chars.len())?,
+        &list_weights(weight_b, b_chars.len())?,
+        normalized,
+        unit_edits,
+    ))
+}
+
+/// Weights of one list value, which must match its sequence's length.
+fn list_weights(weights: &Series, len: usize) -> PolarsResult<Vec<f64>> {
+    let weights = weights.cast(&DataType::Float64)?;
+    match weights.f64()?.iter().collect::<Option<Vec<f64>>>() {
+        Some(weights) if weights.len() == len => Ok(weights),
+        _ => Err(PolarsError::ComputeError(
+            "Each weight list must have one non-null weight per list item or character.".into(),
+        )),
+    }
+}
+
+pub(super) fn native_geometric_weighted_damerau_levenshtein_bywords(
+    a: &str,
+    b: &str,
+    weighted_geometric_ratio: f64,
+    normalized: bool,
+    unit_edits: bool,
+    options: weighted_DL::ByWordsOptions,
+    tokenizer: &segment::Tokenizer,
+) -> f64 {
+    weighted_DL::normalized_descending_weighted_damerau_levenshtein_bywords(
+        a,
+        b,
+        weighted_geometric_ratio,
+        normalized,
+        unit_edits,
+        options,
+        tokenizer,
+    )
+}
+
+pub(super) fn native_geometric_weighted_damerau_levenshtein_word_distances(
+    a: &str,
+    b: &str,
+    weighted_geometric_ratio: f64,
+    normalized: bool,
+    unit_edits: bool,
+    options: weighted_DL::ByWordsOptions,
+    tokenizer: &segment::Tokenizer,
+) -> Vec<f64> {
+    weighted_DL::descending_weighted_damerau_levenshtein_word_distances(
+        a,
+        b,
+        weighted_geometric_ratio,
+        normalized,
+        unit_edits,
+        options,
+        tokenizer,
+    )
+}
+
+fn by_words_tokenizer(kwargs: &WeightedDLByWordsKwargs) -> PolarsResult<segment::Tokenizer> {
+    match (&kwargs.separators, &kwargs.separator_pattern) {
+        (Some(_), Some(_)) => Err(PolarsError::ComputeError(
+            "Pass either separators or separator_pattern, not both.".into(),
+        )),
+        (Some(separators), None) => Ok(segment::Tokenizer::Separators(separators.clone())),
+        (None, Some(pattern)) => regex::Regex::new(pattern)
+            .map(segment::Tokenizer::Pattern)
+            .map_err(|e| {
+                PolarsError::ComputeError(format!("Invalid separator_pattern: {e}").into())
+            }),
+        (None, None) => Ok(segment::Tokenizer::Whitespace),
+    }
+}
+
+fn by_words_options(kwargs: &WeightedDLByWordsKwargs) -> PolarsResult<weighted_DL::ByWordsOptions> {
+    let word_ratio = kwargs.word_weighted_geometric_ratio;
+    if !word_ratio.is_finite() || word_ratio <= 0.0 {
+        return Err(PolarsError::ComputeError(
+            "word_weighted_geometric_ratio must be a positive number.".into(),
+        ));
+    }
+    if let Some(distance) = kwargs.unmatched_word_distance {
+        if !distance.is_finite() || distance < 0.0 {
+            return Err(PolarsError::ComputeError(
+                "unmatched_word_distance must be a non-negative number.".into(),
+            ));
+        }
+    }
+    let agg = match kwargs.agg.as_str() {
+        "max" => weighted_DL::ByWordsAggregation::Max,
+        "min" => weighted_DL::ByWordsAggregation::Min,
+        "weighted_mean" => weighted_DL::ByWordsAggregation::WeightedMean,
+        _ => weighted_DL::ByWordsAggregation::Mean,
+    };
+    let alignment = match kwargs.word_alignment.as_str() {
+        "optimal" => weighted_DL::WordAlignment::Optimal,
+        "assignment" => weighted_DL::WordAlignment::Assignment,
+        _ => weighted_DL::WordAlignment::Positional,
+    };
+    Ok(weighted_DL::ByWordsOptions {
+        agg,
+        alignment,
+        word_ratio,
+        unmatched_distance: kwargs.unmatched_word_distance,
+    })
+}
+
+fn get_all_substrings<'a>(s: &'a str, k: usize) -> Result<Vec<&'a str>, String> {
+    if k == 0 {
+        return Err("k must be greater than 0".to_string());
+    }
+
+    let char_count = s.chars().count();
+    if char_count < k {
+        return Err("longer string must be longer than k".to_string());
+    }
+
+    let mut indices: Vec<usize> = s.char_indices().map(|(i, _)| i).collect();
+    indices.push(s.len());
+
+    let result: Vec<&'a str> = window_starts(char_count, k, 1)
+        .into_iter()
+        .map(|i| &s[indices[i]..indices[i + k]])
+        .collect();
+
+    if result.is_empty() {
+        return Err("no substrings found".to_string());
+    }
+
+    Ok(result)
+}
+
+/// The character offsets of the windows of `k` characters of a
+/// `char_count` character string starting every `stride` characters, plus
+/// the last window when the stride skips over it.
+fn window_starts(char_count: usize, k: usize, stride: usize) -> Vec<usize> {
+    let last = char_count - k;
+    let mut starts: Vec<usize> = (0..=last).step_by(stride).collect();
+    if !last.is_multiple_of(stride) {
+        starts.push(last);
+    }
+    starts
+}
+
+/// `native_damerau_levenshtein`, or `None` once the distance is known to
+/// exceed `max_distance`.
+pub(super) fn native_bounded_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    max_distance: usize,
+) -> Option<usize> {
+    with_chars(a, b, |a_chars, b_chars| {
+        weighted_DL::bounded_damerau_levenshtein(a_chars, b_chars, max_distance)
+    })
+}
+
+/// Byte-level Damerau-Levenshtein distance of Binary values, bounded like
+/// `native_bounded_damerau_levenshtein` when `max_distance` is given.
+pub(super) fn native_bytes_damerau_levenshtein(
+    a: &[u8],
+    b: &[u8],
+    max_distance: Option<usize>,
+) -> Option<usize> {
+    segment::elems_damerau_levenshtein(a, b, max_distance)
+}
+
+/// Byte-level `native_cutoff_normalized_damerau_levenshtein`, with `None`
+/// for scores below `score_cutoff`.
+pub(super) fn native_bytes_normalized_damerau_levenshtein(
+    a: &[u8],
+    b: &[u8],
+    score_cutoff: Option<f64>,
+) -> Option<f64> {
+    let max_len = a.len().max(b.len());
+    let score = if a.is_empty() || b.is_empty() {
+        0.0
+    } else {
+        let max_distance = score_cutoff.map(|cutoff| max_distance_for_cutoff(cutoff, max_len));
+        match native_bytes_damerau_levenshtein(a, b, max_distance) {
+            Some(distance) => 1.0 - distance as f64 / max_len as f64,
+            None => 0.0,
+        }
+    };
+    Some(score).filter(|score| score_cutoff.is_none_or(|cutoff| *score >= cutoff))
+}
+
+/// The best window of the longer of `a` and `b` (`b` when both are equally
+/// long) against the shorter one, see `partial::best_window`.
+struct PartialMatch {
+    shorter_len: usize,
+    longer: InlineChars,
+    window_len: usize,
+    /// `(distance, start)`, `None` when no window is within the bound.
+    best: Option<(usize, usize)>,
+}
+
+/// `None` when either string is empty.
+fn partial_match(
+    a: &str,
+    b: &str,
+    window: PartialWindowKwargs,
+    max_distance: Option<usize>,
+) -> Option<PartialMatch> {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+
+    if a_chars.is_empty() || b_chars.is_empty() {
+        return None;
+    }
+
+    let (shorter, longer) = if a_chars.len() <= b_chars.len() {
+        (a_chars, b_chars)
+    } else {
+        (b_chars, a_chars)
+    };
+    let window_len = window.len(shorter.len(), longer.len());
+    let starts = window_starts(longer.len(), window_len, window.stride);
+    let best = partial::best_window(&shorter, &longer, window_len, &starts, max_distance);
+    Some(PartialMatch {
+        shorter_len: shorter.len(),
+        longer,
+        window_len,
+        best,
+    })
+}
+
+pub(super) fn native_partial_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    window: PartialWindowKwargs,
+) -> i64 {
+    partial_match(a, b, window, None)
+        .and_then(|found| found.best)
+        .map_or(0, |(distance, _)| distance as i64)
+}
+
+/// The best window of `native_partial_damerau_levenshtein` as its distance,
+/// its start and end character offsets in the longer string (`b` when both
+/// are equally long) and its text. The earliest window wins ties.
+pub(super) fn native_partial_damerau_levenshtein_locate(
+    a: &str,
+    b: &str,
+    window: PartialWindowKwargs,
+) -> (i64, u32, u32, String) {
+    let Some(found) = partial_match(a, b, window, None) else {
+        return (0, 0, 0, String::new());
+    };
+    let (distance, start) = found.best.unwrap_or((0, 0));
+    let end = start + found.window_len;
+    (
+        distance as i64,
+        start as u32,
+        end as u32,
+        found.longer[start..end].iter().collect(),
+    )
+}
+
+/// `native_partial_damerau_levenshtein`, or `None` if no window is within
+/// `max_distance`.
+pub(super) fn native_bounded_partial_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    max_distance: usize,
+    window: PartialWindowKwargs,
+) -> Option<usize> {
+    match partial_match(a, b, window, Some(max_distance)) {
+        Some(found) => found.best.map(|(distance, _)| distance),
+        None => Some(0),
+    }
+}
+
+pub(super) fn native_partial_normalized_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    window: PartialWindowKwargs,
+) -> f64 {
+    let Some(found) = partial_match(a, b, window, None) else {
+        return 0.0;
+    };
+    match found.best {
+        Some((distance, _)) => {
+            1.0 - distance as f64 / found.window_len.max(found.shorter_len) as f64
+        }
+        None => 0.0,
+    }
+}
+
+/// `native_partial_normalized_damerau_levenshtein`, returning 0.0 once no
+/// window can reach `score_cutoff`.
+pub(super) fn native_cutoff_partial_normalized_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    score_cutoff: f64,
+    window: PartialWindowKwargs,
+) -> f64 {
+    let (count_a, count_b) = (a.chars().count(), b.chars().count());
+    let shorter_len = count_a.min(count_b);
+    if shorter_len == 0 {
+        return 0.0;
+    }
+    // Every window is normalized by the longer of itself and the shorter
+    // string.
+    let len = window
+        .len(shorter_len, count_a.max(count_b))
+        .max(shorter_len);
+    let max_distance = max_distance_for_cutoff(score_cutoff, len);
+    match native_bounded_partial_damerau_levenshtein(a, b, max_distance, window) {
+        Some(distance) => 1.0 - distance as f64 / len as f64,
+        None => 0.0,
+    }
+}
+
+fn edit_op_fields() -> Vec<Field> {
+    vec![
+        Field::new("op".into(), DataType::String),
+        Field::new("pos_a".into(), DataType::UInt32),
+        Field::new("pos_b".into(), DataType::UInt32),
+        Field::new("char".into(), DataType::String),
+    ]
+}
+
+/// The edits of an optimal Damerau-Levenshtein alignment of `a` against `b`
+/// as a struct series of `edit_op_fields`, in order and without matches.
+/// `char` is the deleted character of `a` or the inserted or substituted
+/// character of `b`; a transposition is one edit at its first swapped pair,
+/// with `char` holding the swapped characters as they appear in `b`.
+pub(super) fn native_damerau_levenshtein_ops(a: &str, b: &str) -> PolarsResult<Series> {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+    let steps = alignment::damerau_levenshtein_alignment(&a_chars, &b_chars);
+
+    let mut ops = Vec::new();
+    let mut pos_a = Vec::new();
+    let mut pos_b = Vec::new();
+    let mut chars = Vec::new();
+    let mut open_transposition = None;
+    for step in &steps {
+        let text = match step.op {
+            EditOp::Match => continue,
+            EditOp::Delete => a_chars[step.pos_a].to_string(),
+            EditOp::Insert | EditOp::Substitute => b_chars[step.pos_b].to_string(),
+            EditOp::Transpose => match open_transposition.take() {
+                None => {
+                    open_transposition = Some(ops.len());
+                    b_chars[step.pos_b].to_string()
+                }
+                Some(index) => {
+                    let first: &mut String = &mut chars[index];
+                    first.push(b_chars[step.pos_b]);
+                    continue;
+                }
+            },
+        };
+        ops.push(step.op.name());
+        pos_a.push(step.pos_a as u32);
+        pos_b.push(step.pos_b as u32);
+        chars.push(text);
+    }
+
+    let fields = [
+        Series::new("op".into(), ops),
+        Series::new("pos_a".into(), pos_a),
+        Series::new("pos_b".into(), pos_b),
+        Series::new("char".into(), chars),
+    ];
+    Ok(StructChunked::from_series("".into(), fields[0].len(), fields.iter())?.into_series())
+}
+
+/// An optimal Damerau-Levenshtein alignment of `a` against `b` rendered as
+/// two gapped strings and a marker line of the same width: `|` for a match,
+/// `.` for a substitution, `~` for the two columns of a transposition and a
+/// space where one side has a gap (`-`).
+pub(super) fn native_damerau_levenshtein_alignment(a: &str, b: &str) -> [String; 3] {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+    let mut rendered = [String::new(), String::new(), String::new()];
+    for step in alignment::damerau_levenshtein_alignment(&a_chars, &b_chars) {
+        let (a_char, b_char, marker) = match step.op {
+            EditOp::Match => (a_chars[step.pos_a], b_chars[step.pos_b], '|'),
+            EditOp::Substitute => (a_chars[step.pos_a], b_chars[step.pos_b], '.'),
+            EditOp::Transpose => (a_chars[step.pos_a], b_chars[step.pos_b], '~'),
+            EditOp::Delete => (a_chars[step.pos_a], '-', ' '),
+            EditOp::Insert => ('-', b_chars[step.pos_b], ' '),
+        };
+        rendered[0].push(a_char);
+        rendered[1].push(b_char);
+        rendered[2].push(marker);
+    }
+    rendered
+}
+
+/// Character positions of `a` that take part in an edit of an optimal
+/// Damerau-Levenshtein alignment against `b` (substituted, deleted or
+/// transposed), in ascending order. Insertions touch no position of `a`.
+pub(super) fn native_damerau_levenshtein_mismatch_positions(a: &str, b: &str) -> Series {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+    let positions: Vec<u32> = alignment::damerau_levenshtein_alignment(&a_chars, &b_chars)
+        .into_iter()
+        .filter(|step| !matches!(step.op, EditOp::Match | EditOp::Insert))
+        .map(|step| step.pos_a as u32)
+        .collect();
+    Series::new("".into(), positions)
+}
+
+/// Position-by-position mismatch count for fixed-structure codes: no
+/// insertions or deletions are allowed, so strings of different lengths can
+/// never match. Stops scanning after `max_mismatches + 1` mismatches and
+/// returns that cap for every string that exceeds the bound.
+pub(super) fn native_bounded_mismatch(a: &str, b: &str, max_mismatches: usize) -> i64 {
+    let cap = max_mismatches + 1;
+    let mut a_chars = a.chars();
+    let mut b_chars = b.chars();
+    let mut mismatches = 0;
+    loop {
+        match (a_chars.next(), b_chars.next()) {
+            (Some(a_char), Some(b_char)) => {
+                if a_char != b_char {
+                    mismatches += 1;
+                    if mismatches == cap {
+                        return cap as i64;
+                    }
+                }
+            }
+            (None, None) => return mismatches as i64,
+            _ => return cap as i64,
+        }
+    }
+}
+
+pub(super) fn native_subsequence_containment(a: &str, b: &str, max_gap: Option<usize>) -> f64 {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+    subsequence::subsequence_containment(&a_chars, &b_chars, max_gap)
+}
+
+pub(super) fn native_subsequence_containment_both(
+    a: &str,
+    b: &str,
+    max_gap: Option<usize>,
+) -> (f64, f64) {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+    (
+        subsequence::subsequence_containment(&a_chars, &b_chars, max_gap),
+        subsequence::subsequence_containment(&b_chars, &a_chars, max_gap),
+    )
+}
+
+pub(super) fn native_affine_gap_distance(
+    a: &str,
+    b: &str,
+    gap_open: f64,
+    gap_extend: f64,
+    mismatch: f64,
+) -> f64 {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+    gotoh::affine_gap_distance(&a_chars, &b_chars, gap_open, gap_extend, mismatch)
+}
+
+pub(super) fn native_custom_cost_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    costs: &weighted_DL::EditCosts,
+    cost_model: Option<&CharCosts>,
+) -> f64 {
+    let a_chars: InlineChars = a.chars().collect();
+    let b_chars: InlineChars = b.chars().collect();
+    match cost_model {
+        Some(cost_model) => {
+            weighted_DL::custom_cost_damerau_levenshtein(&a_chars, &b_chars, costs, cost_model)
+        }
+        None => weighted_DL::custom_cost_damerau_levenshtein(
+            &a_chars,
+            &b_chars,
+            costs,
+            &weighted_DL::UniformSubstitution,
+        ),
+    }
+}
+
+/// Best `fuzz::ratio` of `shorter` against every window of `longer` with the
+/// length of `shorter`, plus the shorter prefix/suffix windows where
+/// `shorter` only partially overlaps either end of `longer`.
+fn best_partial_ratio(shorter: &str, longer: &str) -> f64 {
+    let shorter_chars: InlineChars = shorter.chars().collect();
+    let k = shorter_chars.len();
+
+    let mut indices: Vec<usize> = longer.char_indices().map(|(i, _)| i).collect();
+    indices.push(longer.len());
+    let n = indices.len() - 1;
+
+    let mut windows = get_all_substrings(longer, k).unwrap();
+    for i in 1..k {
+        windows.push(&longer[..indices[i]]);
+        windows.push(&longer[indices[n - i]..]);
+    }
+
+    windows
+        .iter()
+        .take_while(|_| !deadline::exceeded())
+        .map(|window| {
+            let window_chars: InlineChars = window.chars().collect();
+            fuzz::ratio(&shorter_chars, &window_chars)
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Best Indel similarity (0-1) between the shorter string and any equally
+/// long window of the longer string.
+fn partial_ratio_score(a: &str, b: &str) -> f64 {
+    let count_a = a.chars().count();
+    let count_b = b.chars().count();
+
+    if count_a == 0 && count_b == 0 {
+        1.0
+    } else if count_a == 0 || count_b == 0 {
+        0.0
+    } else if count_a < count_b {
+        best_partial_ratio(a, b)
+    } else if count_b < count_a {
+        best_partial_ratio(b, a)
+    } else {
+        best_partial_ratio(a, b).max(best_partial_ratio(b, a))
+    }
+}
+
+fn scale_fuzz_score(score: f64, normalized: bool) -> f64 {
+    if normalized {
+        score
+    } else {
+        score * 100.0
+    }
+}
+
+/// rapidfuzz-style `partial_ratio`. Scores are on a 0-100 scale unless
+/// `normalized` asks for 0-1.
+pub(super) fn native_partial_ratio(a: &str, b: &str, normalized: bool) -> f64 {
+    scale_fuzz_score(partial_ratio_score(a, b), normalized)
+}
+
+/// rapidfuzz-style `WRatio`: the plain ratio, boosted by token-sort/token-set
+/// scores for similarly long strings, and by (down-scaled) partial and
+/// partial-token scores once one string is at least 1.5x longer.
+pub(super) fn native_w_ratio(a: &str, b: &str, normalized: bool) -> f64 {
+    const UNBASE_SCALE: f64 = 0.95;
+
+    let count_a = a.chars().count();
+    let count_b = b.chars().count();
+    if count_a == 0 || count_b == 0 {
+        return 0.0;
+    }
+
+    let len_ratio = count_a.max(count_b) as f64 / count_a.min(count_b) as f64;
+    let score = fuzz::str_ratio(a, b);
+
+    let score = if len_ratio < 1.5 {
+        let token_ratio = fuzz::token_sort_ratio(a, b).max(fuzz::token_set_ratio(a, b));
+        score.max(token_ratio * UNBASE_SCALE)
+    } else {
+        let partial_scale = if len_ratio < 8.0 { 0.9 } else { 0.6 };
+        let score = score.max(partial_ratio_score(a, b) * partial_scale);
+
+        let set = fuzz::token_set(a, b);
+        let partial_token_set_ratio = if set.intersection.is_empty() {
+            partial_ratio_score(&set.diff_ab, &set.diff_ba)
+        } else {
+            1.0
+        };
+        let partial_token_sort_ratio =
+            partial_ratio_score(&fuzz::sorted_tokens(a), &fuzz::sorted_tokens(b));
+        let partial_token_ratio = partial_token_sort_ratio.max(partial_token_set_ratio);
+        score.max(partial_token_ratio * UNBASE_SCALE * partial_scale)
+    };
+
+    scale_fuzz_score(score, normalized)
+}
+
+/// Positional weight profile selected by `profile` and its parameters.
+fn weight_profile(kwargs: &WeightedDLKwargs) -> PolarsResult<weighted_DL::WeightProfile> {
+    let invalid = |message: &str| Err(PolarsError::ComputeError(message.to_string().into()));
+    match kwargs.profile.as_str() {
+        "geometric" => Ok(weighted_DL::WeightProfile::Geometric {
+            ratio: kwargs.weighted_geometric_ratio,
+            plateau_len: kwargs.plateau_len,
+        }),
+        "linear" => Ok(weighted_DL::WeightProfile::Linear),
+        "exponential" if !kwargs.exponential_decay.is_finite() => {
+            invalid("exponential_decay must be finite.")
+        }
+        "exponential" => Ok(weighted_DL::WeightProfile::Exponential(
+            kwargs.exponential_decay,
+        )),
+        "gaussian" if !kwargs.gaussian_center.is_finite() => {
+            invalid("gaussian_center must be finite.")
+        }
+        "gaussian" if !(kwargs.gaussian_width.is_finite() && kwargs.gaussian_width > 0.0) => {
+            invalid("gaussian_width must be finite and positive.")
+        }
+        "gaussian" => Ok(weighted_DL::WeightProfile::Gaussian {
+            center: kwargs.gaussian_center,
+            width: kwargs.gaussian_width,
+        }),
+        "step" if kwargs.step_weights.len() != kwargs.step_breakpoints.len() + 1 => {
+            invalid("step_weights must hold one more weight than step_breakpoints.")
+        }
+        "step" if kwargs.step_breakpoints.windows(2).any(|w| w[0] >= w[1]) => {
+            invalid("step_breakpoints must be strictly increasing.")
+        }
+        "step"
+            if kwargs
+                .step_weights
+                .iter()
+                .any(|weight| !weight.is_finite() || *weight < 0.0) =>
+        {
+            invalid("step_weights must be finite and non-negative.")
+        }
+        "step" => Ok(weighted_DL::WeightProfile::Step {
+            breakpoints: kwargs.step_breakpoints.clone(),
+            weights: kwargs.step_weights.clone(),
+        }),
+        profile => invalid(&format!("Unknown weight profile '{profile}'.")),
+    }
+}
+
+/// `parallel_apply_gwdl` with the per-character weights of every row taken
+/// from the list columns `inputs[2]` and `inputs[3]` instead of a profile.
+/// The weights index the raw strings, so preprocessing and the cascade are
+/// not supported.
+pub(super) fn parallel_apply_gwdl_row_weights(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: WeightedDLKwargs,
+) -> PolarsResult<Series> {
+    if !kwargs.preprocess.is_noop() || kwargs.cascade.is_some() {
+        return Err(PolarsError::ComputeError(
+            "Per-row weight columns cannot be combined with preprocessing or a cascade.".into(),
+        ));
+    }
+    let normalized = kwargs.normalized;
+    let unit_edits = kwargs.unit_edits;
+    let apply = weighted_apply_kwargs(&kwargs.apply, normalized);
+    parallel_apply_str_with_lists(inputs, context, &apply, |s1, s2, w1, w2| {
+        native_row_weighted_damerau_levenshtein(s1, s2, w1, w2, normalized, unit_edits)
+    })
+}
+
+pub(super) fn parallel_apply_gwdl(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: WeightedDLKwargs,
+) -> PolarsResult<Series> {
+    let profile = weight_profile(&kwargs)?;
+    let char_weights = weighted_DL::CharWeights {
+        by_char: kwargs.char_weights,
+        digit: kwargs.digit_weight,
+        alpha: kwargs.alpha_weight,
+        punct: kwargs.punct_weight,
+    };
+    let class_weights = [char_weights.digit, char_weights.alpha, char_weights.punct];
+    if char_weights
+        .by_char
+        .values()
+        .chain(&class_weights)
+        .any(|weight| !weight.is_finite() || *weight < 0.0)
+    {
+        return Err(PolarsError::ComputeError(
+            "char_weights and the class weights must be finite and non-negative.".into(),
+        ));
+    }
+    let direction = kwargs.direction;
+    let normalized = kwargs.normalized;
+    let unit_edits = kwargs.unit_edits;
+    let native_fn = move |s1: &str, s2: &str| {
+        native_geometric_weighted_damerau_levenshtein(
+            s1,
+            s2,
+            &profile,
+            &direction,
+            &char_weights,
+            normalized,
+            unit_edits,
+        )
+    };
+    apply_weighted(
+        inputs,
+        context,
+        kwargs.preprocess,
+        kwargs.cascade,
+        &weighted_apply_kwargs(&kwargs.apply, normalized),
+        native_fn,
+    )
+}
+
+pub(super) fn parallel_apply_gwdl_bywords(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: WeightedDLByWordsKwargs,
+) -> PolarsResult<Series> {
+    let weighted_geometric_ratio = kwargs.weighted_geometric_ratio;
+    let normalized = kwargs.normalized;
+    let unit_edits = kwargs.unit_edits;
+    let options = by_words_options(&kwargs)?;
+    let tokenizer = by_words_tokenizer(&kwargs)?;
+    let native_fn = move |s1: &str, s2: &str| {
+        native_geometric_weighted_damerau_levenshtein_bywords(
+            s1,
+            s2,
+            weighted_geometric_ratio,
+            normalized,
+            unit_edits,
+            options,
+            &tokenizer,
+        )
+    };
+    apply_weighted(
+        inputs,
+        context,
+        kwargs.preprocess,
+        kwargs.cascade,
+        &weighted_apply_kwargs(&kwargs.apply, normalized),
+        native_fn,
+    )
+}
+
+pub(super) fn parallel_apply_gwdl_word_distances(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: WeightedDLByWordsKwargs,
+) -> PolarsResult<Series> {
+    if kwargs.cascade.is_some() {
+        return Err(PolarsError::ComputeError(
+            "A cascade produces one score per row and cannot be combined with per-word distances."
+                .into(),
+        ));
+    }
+    let weighted_geometric_ratio = kwargs.weighted_geometric_ratio;
+    let normalized = kwargs.normalized;
+    let unit_edits = kwargs.unit_edits;
+    let options = by_words_options(&kwargs)?;
+    let tokenizer = by_words_tokenizer(&kwargs)?;
+    let word_distances = move |s1: &str, s2: &str| {
+        native_geometric_weighted_damerau_levenshtein_word_distances(
+            s1,
+            s2,
+            weighted_geometric_ratio,
+            normalized,
+            unit_edits,
+            options,
+            &tokenizer,
+        )
+    };
+    let native_fn = kwargs
+        .preprocess
+        .wrap_with_identical(|a| word_distances(a, a), &word_distances);
+    parallel_apply_list(
+        inputs,
+        context,
+        &kwargs.apply,
+        &DataType::Float64,
+        |a, b| Ok(Series::new("".into(), native_fn(a, b))),
+    )
+}
+
+/// Whether the inputs are Binary, which are compared byte by byte and so
+/// reject every preprocessing option.
+fn binary_inputs(inputs: &[Series], preprocess: &PreprocessKwargs) -> PolarsResult<bool> {
+    if !matches!(inputs[0].dtype(), DataType::Binary) {
+        return Ok(false);
+    }
+    if !preprocess.is_unset() {
+        return Err(PolarsError::ComputeError(
+            "Preprocessing options do not apply to Binary inputs.".into(),
+        ));
+    }
+    Ok(true)
+}
+
+/// Runs a similarity metric, whose worst score is 0.0. With `score_cutoff`
+/// scores below the cutoff are returned as null.
+fn apply_similarity<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    score_cutoff: Option<f64>,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> f64 + Sync + Send,
+{
+    let apply = apply.with_worst_score(0.0);
+    if apply.score_dtype() == DataType::Float32 {
+        apply_scores::<_, Float32Type>(inputs, context, &apply, score_cutoff, native_fn, |score| {
+            score as f32
+        })
+    } else {
+        apply_scores::<_, Float64Type>(inputs, context, &apply, score_cutoff, native_fn, |score| {
+            score
+        })
+    }
+}
+
+/// `apply_similarity` into `Out`, converting each score with `to_native`
+/// once the cutoff has been applied to the full-precision score.
+fn apply_scores<F, Out>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    score_cutoff: Option<f64>,
+    native_fn: F,
+    to_native: fn(f64) -> Out::Native,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> f64 + Sync + Send,
+    Out: PolarsNumericType,
+{
+    match score_cutoff {
+        Some(score_cutoff) => {
+            let native_fn = move |a: &str, b: &str| {
+                Some(native_fn(a, b))
+                    .filter(|score| *score >= score_cutoff)
+                    .map(to_native)
+            };
+            parallel_apply_opt_with::<_, Out>(inputs, context, apply, native_fn)
+        }
+        None => parallel_apply_with::<_, Out>(inputs, context, apply, move |a, b| {
+            to_native(native_fn(a, b))
+        }),
+    }
+}
+
+/// Normalized weighted distances have a worst score of 1.0.
+fn weighted_apply_kwargs(apply: &ApplyKwargs, normalized: bool) -> ApplyKwargs {
+    if normalized {
+        apply.with_worst_score(1.0)
+    } else {
+        apply.clone()
+    }
+}
+
+/// Runs a weighted Damerau-Levenshtein variant, behind the cheap-metric
+/// `cascade` when one is configured.
+fn apply_weighted<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    preprocess: PreprocessKwargs,
+    cascade: Option<Cascade>,
+    apply: &ApplyKwargs,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> f64 + Sync + Send,
+{
+    match cascade {
+        Some(cascade) => {
+            cascade
+                .validate()
+                .map_err(|e| PolarsError::ComputeError(e.into()))?;
+            let native_fn = preprocess.wrap(Some(0.0), cascade.wrap(native_fn));
+            parallel_apply_opt_with::<_, Float64Type>(inputs, context, apply, native_fn)
+        }
+        None => {
+            let native_fn = preprocess.wrap(0.0, native_fn);
+            parallel_apply_with::<_, Float64Type>(inputs, context, apply, native_fn)
+        }
+    }
+}
+
+/// Number of leading characters `a` and `b` share.
+pub(super) fn native_common_prefix_len(a: &str, b: &str) -> i64 {
+    a.chars()
+        .zip(b.chars())
+        .take_while(|(a_char, b_char)| a_char == b_char)
+        .count() as i64
+}
+
+/// Number of trailing characters `a` and `b` share.
+pub(super) fn native_common_suffix_len(a: &str, b: &str) -> i64 {
+    a.chars()
+        .rev()
+        .zip(b.chars().rev())
+        .take_while(|(a_char, b_char)| a_char == b_char)
+        .count() as i64
+}
+
+/// Normalized Damerau-Levenshtein similarity of the phonetic codes of `a`
+/// and `b`.
+pub(super) fn native_phonetic_similarity(
+    a: &str,
+    b: &str,
+    algorithm: phonetic::PhoneticAlgorithm,
+) -> f64 {
+    native_normalized_damerau_levenshtein(&algorithm.encode(a), &algorithm.encode(b))
+}
+
+pub(super) type NativeMetric = fn(&str, &str) -> f64;
+
+/// Resolves a metric name (as exposed on the Python side) to its native
+/// implementation, widened to `f64` so metrics can be mixed by the generic
+/// appliers, and its score for two identical strings. Weighted metrics use
+/// their default kwargs.
+pub(super) fn native_metric_by_name(name: &str) -> PolarsResult<(NativeMetric, f64)> {
+    match name {
+        "damerau_levenshtein" => Ok((|a, b| native_damerau_levenshtein(a, b) as f64, 0.0)),
+        "normalized_damerau_levenshtein" => Ok((native_normalized_damerau_levenshtein, 1.0)),
+        "partial_damerau_levenshtein" => Ok((
+            |a, b| native_partial_damerau_levenshtein(a, b, Default::default()) as f64,
+            0.0,
+        )),
+        "partial_normalized_damerau_levenshtein" => Ok((
+            |a, b| native_partial_normalized_damerau_levenshtein(a, b, Default::default()),
+            1.0,
+        )),
+        "acronym_similarity" => Ok((acronym::acronym_similarity, 1.0)),
+        "geometric_weighted_damerau_levenshtein" => Ok((
+            |a, b| {
+                native_geometric_weighted_damerau_levenshtein(
+                    a,
+                    b,
+                    &weighted_DL::WeightProfile::Geometric {
+                        ratio: default_weighted_geometric_ratio(),
+                        plateau_len: 0,
+                    },
+                    &default_direction(),
+                    &weighted_DL::CharWeights::default(),
+                    default_normalized(),
+                    false,
+                )
+            },
+            0.0,
+        )),
+        _ => Err(PolarsError::ComputeError(
+            format!("Unknown metric '{name}'.").into(),
+        )),
+    }
+}
+
+pub(super) type NativeThresholdTest = Box<dyn Fn(&str, &str) -> bool + Sync + Send>;
+
+/// Whether a pair scores at least `threshold` under the similarity `name`,
+/// or at most `threshold` under the distance `name`. The Damerau-Levenshtein
+/// metrics run their bounded DPs, which stop as soon as the threshold is
+/// out of reach; other metrics are scored in full.
+pub(super) fn native_threshold_test_by_name(
+    name: &str,
+    threshold: f64,
+) -> PolarsResult<NativeThresholdTest> {
+    // A negative distance threshold can never be met.
+    let max_distance = (threshold >= 0.0).then(|| threshold.floor() as usize);
+    Ok(match name {
+        "damerau_levenshtein" => match max_distance {
+            Some(max) => Box::new(prefiltered(
+                Threshold::MaxDistance(max),
+                false,
+                move |a, b| native_bounded_damerau_levenshtein(a, b, max).is_some(),
+            )),
+            None => Box::new(|_, _| false),
+        },
+        "normalized_damerau_levenshtein" => Box::new(prefiltered(
+            Threshold::ScoreCutoff(threshold),
+            false,
+            move |a, b| native_cutoff_normalized_damerau_levenshtein(a, b, threshold) >= threshold,
+        )),
+        "partial_damerau_levenshtein" => Box::new(move |a, b| {
+            max_distance.is_some_and(|max| {
+                native_bounded_partial_damerau_levenshtein(a, b, max, Default::default()).is_some()
+            })
+        }),
+        "partial_normalized_damerau_levenshtein" => Box::new(move |a, b| {
+            native_cutoff_partial_normalized_damerau_levenshtein(
+                a,
+                b,
+                threshold,
+                Default::default(),
+            ) >= threshold
+        }),
+        _ => {
+            let (metric, identical_score) = native_metric_by_name(name)?;
+            if identical_score > 0.0 {
+                Box::new(move |a, b| metric(a, b) >= threshold)
+            } else {
+                Box::new(move |a, b| metric(a, b) <= threshold)
+            }
+        }
+    })
+}
+
+fn ranked_candidate_fields() -> Vec<Field> {
+    vec![
+        Field::new("candidate".into(), DataType::String),
+        Field::new("score".into(), DataType::Float64),
+    ]
+}
+
+fn nearest_term_fields() -> Vec<Field> {
+    vec![
+        Field::new("term".into(), DataType::String),
+        Field::new("distance".into(), DataType::Int64),
+    ]
+}
+
+fn nearest_terms_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::Struct(nearest_term_fields()))),
+    ))
+}
+
+/// `Struct{best, score}`, or with `k` a list of `Struct{candidate, score}`.
+fn best_match_output(input_fields: &[Field], kwargs: BestMatchKwargs) -> PolarsResult<Field> {
+    let dtype = match kwargs.k {
+        Some(_) => DataType::List(Box::new(DataType::Struct(ranked_candidate_fields()))),
+        None => DataType::Struct(vec![
+            Field::new("best".into(), DataType::String),
+            Field::new("score".into(), DataType::Float64),
+        ]),
+    };
+    Ok(Field::new(input_fields[0].name().clone(), dtype))
+}
+
+fn fuzzy_join_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(vec![
+            Field::new("left_index".into(), DataType::UInt32),
+            Field::new("right_index".into(), DataType::UInt32),
+            Field::new("score".into(), DataType::Float64),
+        ]),
+    ))
+}
+
+/// Struct of the `ab` (`a` against `b`) and `ba` scores of asymmetric
+/// metrics with `both_directions`.
+fn both_directions_dtype(score_dtype: DataType) -> DataType {
+    DataType::Struct(vec![
+        Field::new("ab".into(), score_dtype.clone()),
+        Field::new("ba".into(), score_dtype),
+    ])
+}
+
+/// Output field for asymmetric metrics: a plain score, or with
+/// `both_directions` a struct holding the `ab` and `ba` scores.
+fn float_or_both_directions(
+    input_fields: &[Field],
+    both_directions: bool,
+    apply: &ApplyKwargs,
+) -> PolarsResult<Field> {
+    let dtype = if both_directions {
+        both_directions_dtype(apply.score_dtype())
+    } else {
+        apply.score_dtype()
+    };
+    Ok(Field::new(input_fields[0].name().clone(), dtype))
+}
+
+fn subsequence_containment_output(
+    input_fields: &[Field],
+    kwargs: SubsequenceKwargs,
+) -> PolarsResult<Field> {
+    float_or_both_directions(input_fields, kwargs.both_directions, &kwargs.apply)
+}
+
+fn abbreviation_similarity_output(
+    input_fields: &[Field],
+    kwargs: AbbreviationKwargs,
+) -> PolarsResult<Field> {
+    float_or_both_directions(input_fields, kwargs.both_directions, &kwargs.apply)
+}
+
+/// Runs an asymmetric similarity scoring both directions of each pair, as
+/// the struct of `both_directions_dtype`.
+fn apply_both_directions<F>(
+    inputs: &[Series],
+    context: CallerContext,
+    apply: &ApplyKwargs,
+    score_cutoff: Option<f64>,
+    native_fn: F,
+) -> PolarsResult<Series>
+where
+    F: Fn(&str, &str) -> (f64, f64) + Sync + Send,
+{
+    let scores = parallel_apply_both_directions(
+        inputs,
+        context,
+        &apply.with_worst_score(0.0),
+        score_cutoff,
+        native_fn,
+    )?;
+    scores.cast(&both_directions_dtype(apply.score_dtype()))
+}
+
+/// Output field of the similarity expressions: Float64, or Float32 with
+/// `float32`.
+fn score_output(input_fields: &[Field], apply: &ApplyKwargs) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        apply.score_dtype(),
+    ))
+}
+
+fn similarity_output(input_fields: &[Field], kwargs: SimilarityKwargs) -> PolarsResult<Field> {
+    score_output(input_fields, &kwargs.apply)
+}
+
+fn fuzz_ratio_output(input_fields: &[Field], kwargs: FuzzRatioKwargs) -> PolarsResult<Field> {
+    score_output(input_fields, &kwargs.apply)
+}
+
+fn person_name_output(input_fields: &[Field], kwargs: PersonNameKwargs) -> PolarsResult<Field> {
+    score_output(input_fields, &kwargs.apply)
+}
+
+fn company_name_output(input_fields: &[Field], kwargs: CompanyNameKwargs) -> PolarsResult<Field> {
+    score_output(input_fields, &kwargs.apply)
+}
+
+fn address_output(input_fields: &[Field], kwargs: AddressKwargs) -> PolarsResult<Field> {
+    score_output(input_fields, &kwargs.apply)
+}
+
+fn phonetic_similarity_output(
+    input_fields: &[Field],
+    kwargs: PhoneticSimilarityKwargs,
+) -> PolarsResult<Field> {
+    score_output(input_fields, &kwargs.apply)
+}
+
+fn soft_tfidf_output(input_fields: &[Field], kwargs: SoftTfidfKwargs) -> PolarsResult<Field> {
+    score_output(input_fields, &kwargs.apply)
+}
+
+fn mra_comparison_output(input_fields: &[Field], kwargs: MraKwargs) -> PolarsResult<Field> {
+    let dtype = if kwargs.score {
+        DataType::Int64
+    } else {
+        DataType::Boolean
+    };
+    Ok(Field::new(input_fields[0].name().clone(), dtype))
+}
+
+fn score_histogram_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(vec![
+            Field::new("breakpoint".into(), DataType::Float64),
+            Field::new("count".into(), DataType::UInt64),
+        ]),
+    ))
+}
+
+fn upper_triangle_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::Float64)),
+    ))
+}
+
+/// `List[Float64]`, or a plain Float64 once aggregated with `agg`.
+fn zipped_list_output(input_fields: &[Field], kwargs: ZippedListKwargs) -> PolarsResult<Field> {
+    let dtype = match kwargs.agg {
+        Some(_) => DataType::Float64,
+        None => DataType::List(Box::new(DataType::Float64)),
+    };
+    Ok(Field::new(input_fields[0].name().clone(), dtype))
+}
+
+fn distance_summary_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(vec![
+            Field::new("distance".into(), DataType::Int64),
+            Field::new("normalized".into(), DataType::Float64),
+            Field::new("similarity".into(), DataType::Float64),
+        ]),
+    ))
+}
+
+fn similarity_profile_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    let fields = std::iter::once(Field::new("dl".into(), DataType::Int64))
+        .chain(
+            SIMILARITY_PROFILE_METRICS
+                .iter()
+                .map(|&name| Field::new(name.into(), DataType::Float64)),
+        )
+        .collect();
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(fields),
+    ))
+}
+
+fn partial_match_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(vec![
+            Field::new("distance".into(), DataType::Int64),
+            Field::new("start".into(), DataType::UInt32),
+            Field::new("end".into(), DataType::UInt32),
+            Field::new("matched".into(), DataType::String),
+        ]),
+    ))
+}
+
+fn edit_ops_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::Struct(edit_op_fields()))),
+    ))
+}
+
+fn alignment_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(vec![
+            Field::new("a".into(), DataType::String),
+            Field::new("b".into(), DataType::String),
+            Field::new("markers".into(), DataType::String),
+        ]),
+    ))
+}
+
+fn positions_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::UInt32)),
+    ))
+}
+
+fn word_distances_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::Float64)),
+    ))
+}
+
+fn string_list_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::String)),
+    ))
+}
+
+fn minhash_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::List(Box::new(DataType::UInt64)),
+    ))
+}
+
+fn double_metaphone_output(input_fields: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        input_fields[0].name().clone(),
+        DataType::Struct(vec![
+            Field::new("primary".into(), DataType::String),
+            Field::new("alternate".into(), DataType::String),
+        ]),
+    ))
+}
+
+// Workaround for arrow::ffi module resolution issue
+mod arrow {
+    pub use polars_arrow::ffi;
+}
+
+#[polars_expr(output_type=Int64)]
+fn damerau_levenshtein(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: DistanceKwargs,
+) -> PolarsResult<Series> {
+    if binary_inputs(inputs, &kwargs.preprocess)? {
+        let native_fn = |a: &[u8], b: &[u8]| {
+            kwargs.result(native_bytes_damerau_levenshtein(a, b, kwargs.max_distance))
+        };
+        return parallel_apply_bytes::<_, Int64Type>(inputs, context, &kwargs.apply, native_fn);
+    }
+    if kwargs.unit != Unit::Char {
+        let native_fn = kwargs.preprocess.clone().wrap(Some(0), |s1, s2| {
+            kwargs.result(segment::damerau_levenshtein(
+                s1,
+                s2,
+                kwargs.unit,
+                kwargs.max_distance,
+            ))
+        });
+        return parallel_apply_opt_with::<_, Int64Type>(inputs, context, &kwargs.apply, native_fn);
+    }
+    if let Some(max_distance) = kwargs.max_distance {
+        let native_fn = prefiltered(
+            Threshold::MaxDistance(max_distance),
+            kwargs.bounded_result(max_distance, None),
+            |s1, s2| {
+                let distance = native_bounded_damerau_levenshtein(s1, s2, max_distance);
+                kwargs.bounded_result(max_distance, distance)
+            },
+        );
+        let native_fn = kwargs.preprocess.clone().wrap(Some(0), native_fn);
+        return parallel_apply_opt_with::<_, Int64Type>(inputs, context, &kwargs.apply, native_fn);
+    }
+    let native_fn = kwargs.preprocess.wrap(0, native_damerau_levenshtein);
+    parallel_apply_with::<_, Int64Type>(inputs, context, &kwargs.apply, native_fn)
+}
+
+/// Damerau-Levenshtein distance over tokens instead of characters: each
+/// inserted, deleted, substituted or swapped word is one edit.
+#[polars_expr(output_type=Int64)]
+fn token_damerau_levenshtein(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: TokenDLKwargs,
+) -> PolarsResult<Series> {
+    let separators = kwargs.separators;
+    let native_fn = kwargs.preprocess.wrap(0, move |s1, s2| {
+        native_token_damerau_levenshtein(s1, s2, separators.as_deref())
+    });
+    parallel_apply_with::<_, Int64Type>(inputs, context, &kwargs.apply, native_fn)
+}
+
+/// `damerau_levenshtein`, the distance normalized by the longer length and
+/// `normalized_damerau_levenshtein` of each pair as one struct, computed
+/// with a single DP.
+#[polars_expr(output_type_func=distance_summary_output)]
+fn damerau_levenshtein_full(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: MetricKwargs,
+) -> PolarsResult<Series> {
+    let native_fn = kwargs
+        .preprocess
+        .wrap((0, 0.0, 1.0), native_damerau_levenshtein_summary);
+    parallel_apply_distance_summary(inputs, context, &kwargs.apply, native_fn)
+}
+
+/// Several metrics of each pair as one struct, see
+/// `native_similarity_profile`.
+#[polars_expr(output_type_func=similarity_profile_output)]
+fn similarity_profile(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: MetricKwargs,
+) -> PolarsResult<Series> {
+    let native_fn = kwargs.preprocess.wrap_with_identical(
+        |s| {
+            let normalized = if s.is_empty() { 0.0 } else { 1.0 };
+            (0, [normalized, 1.0, 1.0, 1.0])
+        },
+        native_similarity_profile,
+    );
+    parallel_apply_metric_profile(
+        inputs,
+        context,
+        &kwargs.apply,
+        "dl",
+        SIMILARITY_PROFILE_METRICS,
+        native_fn,
+    )
+}
+
+/// The edit script of an optimal Damerau-Levenshtein alignment, see
+/// `native_damerau_levenshtein_ops`.
+#[polars_expr(output_type_func=edit_ops_output)]
+fn damerau_levenshtein_ops(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: AlignmentKwargs,
+) -> PolarsResult<Series> {
+    let inner_dtype = DataType::Struct(edit_op_fields());
+    parallel_apply_list(
+        inputs,
+        context,
+        &kwargs.apply,
+        &inner_dtype,
+        native_damerau_levenshtein_ops,
+    )
+}
+
+/// An optimal Damerau-Levenshtein alignment as `Struct{a, b, markers}`, see
+/// `native_damerau_levenshtein_alignment`.
+#[polars_expr(output_type_func=alignment_output)]
+fn damerau_levenshtein_alignment(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: AlignmentKwargs,
+) -> PolarsResult<Series> {
+    parallel_apply_str_fields(
+        inputs,
+        context,
+        &kwargs.apply,
+        ["a", "b", "markers"],
+        native_damerau_levenshtein_alignment,
+    )
+}
+
+/// See `native_damerau_levenshtein_mismatch_positions`.
+#[polars_expr(output_type_func=positions_output)]
+fn damerau_levenshtein_mismatch_positions(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: AlignmentKwargs,
+) -> PolarsResult<Series> {
+    parallel_apply_list(inputs, context, &kwargs.apply, &DataType::UInt32, |a, b| {
+        Ok(native_damerau_levenshtein_mismatch_positions(a, b))
+    })
+}
+
+/// Indices and scores of `candidates` against `s`, best first: the lowest
+/// scores for distances, the highest for similarities, earlier candidates
+/// first on ties.
+fn rank_candidates<M>(
+    metric: M,
+    is_similarity: bool,
+    candidates: &[String],
+    s: &str,
+) -> Vec<(usize, f64)>
+where
+    M: Fn(&str, &str) -> f64,
+{
+    let mut ranked: Vec<(usize, f64)> = candidates
+        .iter()
+        .map(|candidate| metric(s, candidate))
+        .enumerate()
+        .collect();
+    ranked.sort_by(|(_, a), (_, b)| {
+        let order = a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal);
+        if is_similarity {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+    ranked
+}
+
+/// The candidate most similar to each row under `metric` as
+/// `Struct{best, score}`, or with `k` the `k` most similar ones, best first,
+/// as a list of `Struct{candidate, score}`. See `rank_candidates`.
+#[polars_expr(output_type_func_with_kwargs=best_match_output)]
+fn best_match(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: BestMatchKwargs,
+) -> PolarsResult<Series> {
+    if kwargs.candidates.is_empty() || kwargs.k == Some(0) {
+        return Err(PolarsError::ComputeError(
+            "candidates must not be empty and k must be positive.".into(),
+        ));
+    }
+    let (metric, identical_score) = native_metric_by_name(&kwargs.metric)?;
+    let is_similarity = identical_score > 0.0;
+    let candidates = &kwargs.candidates;
+    let metric = kwargs.preprocess.wrap(identical_score, metric);
+    let apply = if is_similarity {
+        kwargs.apply.with_worst_score(0.0)
+    } else {
+        kwargs.apply
+    };
+
+    if let Some(k) = kwargs.k {
+        let inner_dtype = DataType::Struct(ranked_candidate_fields());
+        return parallel_apply_str_list(inputs, context, &apply, &inner_dtype, |s| {
+            let mut ranked = rank_candidates(&metric, is_similarity, candidates, s);
+            ranked.truncate(k);
+            let (names, scores): (Vec<&str>, Vec<f64>) = ranked
+                .into_iter()
+                .map(|(i, score)| (candidates[i].as_str(), score))
+                .unzip();
+            let fields = [
+                Series::new("candidate".into(), names),
+                Series::new("score".into(), scores),
+            ];
+            Ok(
+                StructChunked::from_series("".into(), fields[0].len(), fields.iter())?
+                    .into_series(),
+            )
+        });
+    }
+    let native_fn = |s: &str| {
+        let (best, score) = rank_candidates(&metric, is_similarity, candidates, s)[0];
+        (candidates[best].clone(), score)
+    };
+    parallel_apply_str_scored(inputs, context, &apply, ["best", "score"], native_fn)
+}
+
+/// The non-null strings of a vocabulary passed either in the kwargs or as
+/// a second column, but not both.
+fn listed_or_column<'a>(
+    listed: Option<&'a [String]>,
+    column: Option<&'a StringChunked>,
+) -> PolarsResult<Vec<&'a str>> {
+    match (listed, column) {
+        (Some(listed), None) => Ok(listed.iter().map(String::as_str).collect()),
+        (None, Some(column)) => Ok(column.iter().flatten().collect()),
+        _ => Err(PolarsError::ComputeError(
+            "Pass the vocabulary either as a list or as a second column.".into(),
+        )),
+    }
+}
+
+/// The dictionary words within `max_distance` Levenshtein edits of each
+/// row, as `List[String]` in sorted order. The preprocessed dictionary is
+/// loaded once into a `Trie`, and each row's `LevenshteinAutomaton` only
+/// walks the branches it can still accept.
+#[polars_expr(output_type_func=string_list_output)]
+fn dictionary_search(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: DictionarySearchKwargs,
+) -> PolarsResult<Series> {
+    let column = inputs.get(1).map(string_values).transpose()?;
+    let dictionary = listed_or_column(kwargs.dictionary.as_deref(), column.as_deref())?;
+    let preprocess = &kwargs.preprocess;
+    let preprocessed: Vec<String> = dictionary
+        .iter()
+        .map(|word| preprocess.apply(word).into_owned())
+        .collect();
+    let trie = Trie::new(preprocessed.iter().map(String::as_str));
+
+    let max_distance = kwargs.max_distance;
+    parallel_apply_str_list(inputs, context, &kwargs.apply, &DataType::String, |s| {
+        let automaton = LevenshteinAutomaton::new(&preprocess.apply(s), max_distance);
+        let words: Vec<&str> = trie
+            .search(&automaton)
+            .into_iter()
+            .map(|position| dictionary[position as usize])
+            .collect();
+        Ok(Series::new("".into(), words))
+    })
+}
+
+/// The closest dictionary word to each row within `max_distance`
+/// (unrestricted Damerau-Levenshtein), or null when there is none. Ties go
+/// to the more frequent word, then to the earlier one. Words are looked up
+/// in the same `BkTree` as `nearest_terms`.
+#[polars_expr(output_type=String)]
+fn suggest_correction(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: SuggestCorrectionKwargs,
+) -> PolarsResult<Series> {
+    let column = inputs.get(1).map(string_values).transpose()?;
+    let dictionary = listed_or_column(kwargs.dictionary.as_deref(), column.as_deref())?;
+    let preprocess = &kwargs.preprocess;
+    let preprocessed: Vec<String> = dictionary
+        .iter()
+        .map(|word| preprocess.apply(word).into_owned())
+        .collect();
+    let tree = BkTree::new(preprocessed.iter().map(String::as_str));
+
+    let frequency = |position: usize| {
+        kwargs
+            .frequencies
+            .get(dictionary[position])
+            .copied()
+            .unwrap_or(0.0)
+    };
+    let max_distance = kwargs.max_distance;
+    parallel_apply_str_opt(inputs, context, &kwargs.apply, |s| {
+        let found = tree.within(&preprocess.apply(s), max_distance);
+        let &(_, nearest) = found.first()?;
+        // `found` is ordered by distance, then position, so only a strictly
+        // more frequent word replaces an earlier one.
+        found
+            .iter()
+            .take_while(|&&(_, distance)| distance == nearest)
+            .map(|&(position, _)| position)
+            .reduce(|best, position| {
+                if frequency(position) > frequency(best) {
+                    position
+                } else {
+                    best
+                }
+            })
+            .map(|position| dictionary[position].to_string())
+    })
+}
+
+/// The vocabulary terms within `max_distance` (unrestricted
+/// Damerau-Levenshtein) of each row, nearest first, as a list of
+/// `Struct{term, distance}`. The vocabulary comes from the kwargs or from
+/// the non-null values of `inputs[1]` (of any length) and is indexed once in
+/// a `BkTree` over its preprocessed terms.
+#[polars_expr(output_type_func=nearest_terms_output)]
+fn nearest_terms(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: NearestTermsKwargs,
+) -> PolarsResult<Series> {
+    if kwargs.k == Some(0) {
+        return Err(PolarsError::ComputeError("k must be positive.".into()));
+    }
+    let column = inputs.get(1).map(string_values).transpose()?;
+    let vocabulary = listed_or_column(kwargs.vocabulary.as_deref(), column.as_deref())?;
+    let preprocess = &kwargs.preprocess;
+    let preprocessed: Vec<String> = vocabulary
+        .iter()
+        .map(|term| preprocess.apply(term).into_owned())
+        .collect();
+    let tree = BkTree::new(preprocessed.iter().map(String::as_str));
+
+    let (max_distance, k) = (kwargs.max_distance, kwargs.k.unwrap_or(usize::MAX));
+    let inner_dtype = DataType::Struct(nearest_term_fields());
+    parallel_apply_str_list(inputs, context, &kwargs.apply, &inner_dtype, |s| {
+        let mut found = tree.within(&preprocess.apply(s), max_distance);
+        found.truncate(k);
+        let (terms, distances): (Vec<&str>, Vec<i64>) = found
+            .into_iter()
+            .map(|(term, distance)| (vocabulary[term], distance as i64))
+            .unzip();
+        let fields = [
+            Series::new("term".into(), terms),
+            Series::new("distance".into(), distances),
+        ];
+        Ok(StructChunked::from_series("".into(), fields[0].len(), fields.iter())?.into_series())
+    })
+}
+
+/// Builds a blocking index over `right` and returns a function scoring a
+/// string against the right keys at or after `min_position` that share a
+/// block with it, keeping the matching positions and scores. Blocking looks
+/// at the preprocessed keys.
+fn blocked_matcher<'a>(
+    kwargs: &BlockedMatchKwargs,
+    right: Vec<Option<&'a str>>,
+) -> PolarsResult<impl Fn(&str, u32) -> Vec<(u32, f64)> + Sync + Send + 'a> {
+    if kwargs.block_prefix_len == 0 || kwargs.block_q == 0 {
+        return Err(PolarsError::ComputeError(
+            "block_prefix_len and block_q must be positive.".into(),
+        ));
+    }
+    if kwargs.lsh_bands == 0 || kwargs.lsh_rows == 0 {
+        return Err(PolarsError::ComputeError(
+            "lsh_bands and lsh_rows must be positive.".into(),
+        ));
+    }
+    let (metric, identical_score) = native_metric_by_name(&kwargs.metric)?;
+    let is_similarity = identical_score > 0.0;
+    let threshold = kwargs.threshold;
+    let preprocess = kwargs.preprocess.clone();
+    let preprocessed: Vec<Option<String>> = right
+        .iter()
+        .map(|key| key.map(|key| preprocess.apply(key).into_owned()))
+        .collect();
+    let index = BlockIndex::new(
+        kwargs.blocking,
+        kwargs.block_prefix_len,
+        kwargs.block_q,
+        (kwargs.lsh_bands, kwargs.lsh_rows),
+        preprocessed.iter().map(Option::as_deref),
+    );
+
+    let metric = kwargs.preprocess.clone().wrap(identical_score, metric);
+    Ok(move |s: &str, min_position: u32| {
+        index
+            .candidates(&preprocess.apply(s))
+            .into_iter()
+            .filter(|&position| position >= min_position)
+            .filter_map(|position| {
+                let score = metric(s, right[position as usize]?);
+                let matches = if is_similarity {
+                    score >= threshold
+                } else {
+                    score <= threshold
+                };
+                matches.then_some((position, score))
+            })
+            .collect()
+    })
+}
+
+/// Matches every key of `inputs[0]` against the keys of `inputs[1]` (of any
+/// length) that share a block with it, returning one row per matching pair
+/// with both positions and the score.
+#[polars_expr(output_type_func=fuzzy_join_output)]
+fn fuzzy_join_indices(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: BlockedMatchKwargs,
+) -> PolarsResult<Series> {
+    let right = string_values(&inputs[1])?;
+    let matcher = blocked_matcher(&kwargs, right.iter().collect())?;
+    parallel_apply_matches(inputs, context, &kwargs.apply, |s, _| matcher(s, 0))
+}
+
+/// Cluster id of every row of `inputs[0]`: the connected components of the
+/// graph whose edges are the matching pairs of rows, see
+/// `cluster::cluster_ids`.
+#[polars_expr(output_type=UInt32)]
+fn similarity_cluster(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: BlockedMatchKwargs,
+) -> PolarsResult<Series> {
+    let keys = string_values(&inputs[0])?;
+    let keys = keys.as_ref();
+    let matcher = blocked_matcher(&kwargs, keys.iter().collect())?;
+    let matches = parallel_find_matches(keys, context, &kwargs.apply, |s, row| matcher(s, row + 1));
+    let present: Vec<bool> = keys.iter().map(|key| key.is_some()).collect();
+    let ids = cluster::cluster_ids(&present, matches.into_iter().map(|(a, b, _)| (a, b)));
+    Ok(UInt32Chunked::from_iter_options(keys.name().clone(), ids.into_iter()).into_series())
+}
+
+#[polars_expr(output_type_func_with_kwargs=similarity_output)]
+fn normalized_damerau_levenshtein(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: SimilarityKwargs,
+) -> PolarsResult<Series> {
+    let score_cutoff = kwargs.score_cutoff;
+    if binary_inputs(inputs, &kwargs.preprocess)? {
+        let apply = kwargs.apply.with_worst_score(0.0);
+        let native_fn =
+            |a: &[u8], b: &[u8]| native_bytes_normalized_damerau_levenshtein(a, b, score_cutoff);
+        if apply.score_dtype() == DataType::Float32 {
+            return parallel_apply_bytes::<_, Float32Type>(inputs, context, &apply, |a, b| {
+                native_fn(a, b).map(|score| score as f32)
+            });
+        }
+        return parallel_apply_bytes::<_, Float64Type>(inputs, context, &apply, native_fn);
+    }
+    let unit = kwargs.unit;
+    let native_fn = kwargs
+        .preprocess
+        .wrap(1.0, move |s1, s2| match (unit, score_cutoff) {
+            (Unit::Char, Some(score_cutoff))
+                if Threshold::ScoreCutoff(score_cutoff).rejects(s1, s2) =>
+            {
+                0.0
+            }
+            (Unit::Char, Some(score_cutoff)) => {
+                native_cutoff_normalized_damerau_levenshtein(s1, s2, score_cutoff)
+            }
+            (Unit::Char, None) => native_normalized_damerau_levenshtein(s1, s2),
+            _ => native_unit_normalized_damerau_levenshtein(s1, s2, unit, score_cutoff),
+        });
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
+}
+
+#[polars_expr(output_type=Int64)]
+fn partial_damerau_levenshtein(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: DistanceKwargs,
+) -> PolarsResult<Series> {
+    let window = kwargs.window.validated()?;
+    if let Some(max_distance) = kwargs.max_distance {
+        let native_fn = kwargs.preprocess.clone().wrap(Some(0), |s1, s2| {
+            let distance = native_bounded_partial_damerau_levenshtein(s1, s2, max_distance, window);
+            kwargs.bounded_result(max_distance, distance)
+        });
+        return parallel_apply_opt_with::<_, Int64Type>(inputs, context, &kwargs.apply, native_fn);
+    }
+    let native_fn = kwargs.preprocess.wrap(0, move |s1, s2| {
+        native_partial_damerau_levenshtein(s1, s2, window)
+    });
+    parallel_apply_with::<_, Int64Type>(inputs, context, &kwargs.apply, native_fn)
+}
+
+/// The best window of `partial_damerau_levenshtein` as a struct, see
+/// `native_partial_damerau_levenshtein_locate`.
+#[polars_expr(output_type_func=partial_match_output)]
+fn partial_damerau_levenshtein_locate(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: PartialLocateKwargs,
+) -> PolarsResult<Series> {
+    let window = kwargs.window.validated()?;
+    parallel_apply_partial_matches(inputs, context, &kwargs.apply, |s1, s2| {
+        native_partial_damerau_levenshtein_locate(s1, s2, window)
+    })
+}
+
+#[polars_expr(output_type_func_with_kwargs=similarity_output)]
+fn partial_normalized_damerau_levenshtein(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: SimilarityKwargs,
+) -> PolarsResult<Series> {
+    let score_cutoff = kwargs.score_cutoff;
+    let window = kwargs.window.validated()?;
+    let native_fn = kwargs
+        .preprocess
+        .wrap(1.0, move |s1, s2| match score_cutoff {
+            Some(score_cutoff) => {
+                native_cutoff_partial_normalized_damerau_levenshtein(s1, s2, score_cutoff, window)
+            }
+            None => native_partial_normalized_damerau_levenshtein(s1, s2, window),
+        });
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
+}
+#[polars_expr(output_type=Float64)]
+fn geometric_weighted_damerau_levenshtein(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: WeightedDLKwargs,
+) -> PolarsResult<Series> {
+    match inputs.len() {
+        4 => parallel_apply_gwdl_row_weights(inputs, context, kwargs),
+        _ => parallel_apply_gwdl(inputs, context, kwargs),
+    }
+}
+
+#[polars_expr(output_type=Float64)]
+fn geometric_weighted_damerau_levenshtein_bywords(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: WeightedDLByWordsKwargs,
+) -> PolarsResult<Series> {
+    parallel_apply_gwdl_bywords(inputs, context, kwargs)
+}
+
+/// The per-word distances `geometric_weighted_damerau_levenshtein_bywords`
+/// aggregates, as a list.
+#[polars_expr(output_type_func=word_distances_output)]
+fn geometric_weighted_damerau_levenshtein_word_distances(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: WeightedDLByWordsKwargs,
+) -> PolarsResult<Series> {
+    parallel_apply_gwdl_word_distances(inputs, context, kwargs)
+}
+
+/// Damerau-Levenshtein distance between two List columns, counting item
+/// edits.
+#[polars_expr(output_type=Int64)]
+fn list_damerau_levenshtein(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: ListDLKwargs,
+) -> PolarsResult<Series> {
+    if inputs.len() != 2 {
+        return Err(PolarsError::ComputeError(
+            "Expected two list columns.".into(),
+        ));
+    }
+    parallel_apply_lists::<_, Int64Type>(inputs, context, &kwargs.apply, |a, b, _| {
+        native_list_damerau_levenshtein(a, b)
+    })
+}
+
+#[polars_expr(output_type=Float64)]
+fn list_weighted_damerau_levenshtein(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: ListWeightedDLKwargs,
+) -> PolarsResult<Series> {
+    let weighted_geometric_ratio = kwargs.weighted_geometric_ratio;
+    let normalized = kwargs.normalized;
+    let unit_edits = kwargs.unit_edits;
+    parallel_apply_lists::<_, Float64Type>(inputs, context, &kwargs.apply, |a, b, weights| {
+        native_list_weighted_damerau_levenshtein(
+            a,
+            b,
+            weights,
+            weighted_geometric_ratio,
+            normalized,
+            unit_edits,
+        )
+    })
+}
+
+#[polars_expr(output_type=Float64)]
+fn warm_start(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: WarmStartKwargs,
+) -> PolarsResult<Series> {
+    let (metric, identical_score) = native_metric_by_name(&kwargs.metric)?;
+    let native_fn = kwargs.preprocess.wrap(identical_score, metric);
+    let apply = if identical_score == 1.0 {
+        kwargs.apply.with_worst_score(0.0)
+    } else {
+        kwargs.apply
+    };
+    parallel_apply_warm_start(inputs, context, &apply, native_fn)
+}
+
+#[polars_expr(output_type_func_with_kwargs=fuzz_ratio_output)]
+fn partial_ratio(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: FuzzRatioKwargs,
+) -> PolarsResult<Series> {
+    let normalized = kwargs.normalized;
+    let native_fn = kwargs
+        .preprocess
+        .wrap(scale_fuzz_score(1.0, normalized), move |s1, s2| {
+            native_partial_ratio(s1, s2, normalized)
+        });
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
 }
 
-fn default_weighted_geometric_ratio() -> f64 {
-    1.0
+#[polars_expr(output_type_func_with_kwargs=fuzz_ratio_output)]
+fn w_ratio(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: FuzzRatioKwargs,
+) -> PolarsResult<Series> {
+    let normalized = kwargs.normalized;
+    let native_fn = kwargs
+        .preprocess
+        .wrap(scale_fuzz_score(1.0, normalized), move |s1, s2| {
+            native_w_ratio(s1, s2, normalized)
+        });
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
 }
 
-fn default_normalized() -> bool {
-    false
+#[polars_expr(output_type=Int64)]
+fn bounded_mismatch(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: BoundedMismatchKwargs,
+) -> PolarsResult<Series> {
+    let max_mismatches = kwargs.max_mismatches;
+    let native_fn = kwargs.preprocess.wrap(0, move |s1, s2| {
+        native_bounded_mismatch(s1, s2, max_mismatches)
+    });
+    parallel_apply_with::<_, Int64Type>(
+        inputs,
+        context,
+        &kwargs.apply.with_worst_score((max_mismatches + 1) as f64),
+        native_fn,
+    )
 }
 
-fn default_agg() -> String {
-    "mean".to_string()
+#[polars_expr(output_type=Boolean)]
+fn within_bounded_mismatch(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: BoundedMismatchKwargs,
+) -> PolarsResult<Series> {
+    let max_mismatches = kwargs.max_mismatches;
+    let native_fn = kwargs.preprocess.wrap(true, move |s1, s2| {
+        native_bounded_mismatch(s1, s2, max_mismatches) <= max_mismatches as i64
+    });
+    parallel_apply_bool_with(
+        inputs,
+        context,
+        &kwargs.apply.with_worst_score(0.0),
+        native_fn,
+    )
 }
 
-pub(super) fn native_geometric_weighted_damerau_levenshtein(
-    a: &str,
-    b: &str,
-    weighted_geometric_ratio: f64,
-    normalized: bool,
-) -> f64 {
-    weighted_DL::normalized_descending_weighted_damerau_levenshtein(
-        a,
-        b,
-        weighted_geometric_ratio,
-        normalized,
-    ) as f64
+#[polars_expr(output_type_func_with_kwargs=subsequence_containment_output)]
+fn subsequence_containment(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: SubsequenceKwargs,
+) -> PolarsResult<Series> {
+    let max_gap = kwargs.max_gap;
+    if kwargs.both_directions {
+        let native_fn = kwargs.preprocess.wrap((1.0, 1.0), move |s1, s2| {
+            native_subsequence_containment_both(s1, s2, max_gap)
+        });
+        return apply_both_directions(
+            inputs,
+            context,
+            &kwargs.apply,
+            kwargs.score_cutoff,
+            native_fn,
+        );
+    }
+    let native_fn = kwargs.preprocess.wrap(1.0, move |s1, s2| {
+        native_subsequence_containment(s1, s2, max_gap)
+    });
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
 }
 
-pub(super) fn native_geometric_weighted_damerau_levenshtein_bywords(
-    a: &str,
-    b: &str,
-    weighted_geometric_ratio: f64,
-    normalized: bool,
-    agg: &str,
-) -> f64 {
-    let agg_method = match agg {
-        "max" => weighted_DL::ByWordsAggregation::Max,
-        "min" => weighted_DL::ByWordsAggregation::Min,
-        _ => weighted_DL::ByWordsAggregation::Mean,
-    };
-    weighted_DL::normalized_descending_weighted_damerau_levenshtein_bywords(
-        a,
-        b,
-        weighted_geometric_ratio,
-        normalized,
-        agg_method,
+#[polars_expr(output_type_func_with_kwargs=similarity_output)]
+fn acronym_similarity(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: SimilarityKwargs,
+) -> PolarsResult<Series> {
+    let native_fn = kwargs.preprocess.wrap(1.0, acronym::acronym_similarity);
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
     )
 }
 
-fn get_all_substrings<'a>(s: &'a str, k: usize) -> Result<Vec<&'a str>, String> {
-    if k == 0 {
-        return Err("k must be greater than 0".to_string());
+#[polars_expr(output_type_func_with_kwargs=abbreviation_similarity_output)]
+fn abbreviation_similarity(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: AbbreviationKwargs,
+) -> PolarsResult<Series> {
+    if kwargs.both_directions {
+        let native_fn = kwargs.preprocess.wrap((1.0, 1.0), |s1, s2| {
+            (
+                acronym::abbreviation_similarity(s1, s2),
+                acronym::abbreviation_similarity(s2, s1),
+            )
+        });
+        return apply_both_directions(
+            inputs,
+            context,
+            &kwargs.apply,
+            kwargs.score_cutoff,
+            native_fn,
+        );
     }
+    let native_fn = kwargs
+        .preprocess
+        .wrap(1.0, acronym::abbreviation_similarity);
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
+}
 
-    let char_count = s.chars().count();
-    if char_count < k {
-        return Err("longer string must be longer than k".to_string());
+#[polars_expr(output_type_func_with_kwargs=person_name_output)]
+fn person_name_similarity(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: PersonNameKwargs,
+) -> PolarsResult<Series> {
+    let options = PersonNameOptions {
+        initial_score: kwargs.initial_score,
+        phonetic_score: kwargs.phonetic_score,
+        unmatched_token_weight: kwargs.unmatched_token_weight,
+    };
+    let in_unit_range = |score: f64| (0.0..=1.0).contains(&score);
+    if !in_unit_range(options.initial_score)
+        || !in_unit_range(options.phonetic_score)
+        || !in_unit_range(options.unmatched_token_weight)
+    {
+        return Err(PolarsError::ComputeError(
+            "initial_score, phonetic_score and unmatched_token_weight must be between 0 and 1."
+                .into(),
+        ));
     }
+    let native_fn = kwargs.preprocess.wrap(1.0, move |s1, s2| {
+        person_name::person_name_similarity(s1, s2, options)
+    });
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
+}
 
-    let mut indices: Vec<usize> = s.char_indices().map(|(i, _)| i).collect();
-    indices.push(s.len());
+#[polars_expr(output_type_func_with_kwargs=company_name_output)]
+fn company_name_similarity(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: CompanyNameKwargs,
+) -> PolarsResult<Series> {
+    let normalizer = CompanyNameNormalizer::new(&kwargs.legal_suffixes);
+    let native_fn = kwargs
+        .preprocess
+        .wrap(1.0, |s1, s2| normalizer.similarity(s1, s2));
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
+}
 
-    let mut result: Vec<&'a str> = Vec::with_capacity(char_count - k + 1);
-    for i in 0..(char_count - k + 1) {
-        let start = indices[i];
-        let end = indices[i + k];
-        result.push(&s[start..end]);
+#[polars_expr(output_type_func_with_kwargs=address_output)]
+fn address_similarity(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: AddressKwargs,
+) -> PolarsResult<Series> {
+    let unmatched_token_weight = kwargs.unmatched_token_weight;
+    if !(0.0..=1.0).contains(&unmatched_token_weight) {
+        return Err(PolarsError::ComputeError(
+            "unmatched_token_weight must be between 0 and 1.".into(),
+        ));
     }
+    let native_fn = kwargs.preprocess.wrap(1.0, move |s1, s2| {
+        address::address_similarity(s1, s2, unmatched_token_weight)
+    });
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
+}
 
-    if result.is_empty() {
-        return Err("no substrings found".to_string());
-    }
+#[polars_expr(output_type=Float64)]
+fn affine_gap_distance(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: AffineGapKwargs,
+) -> PolarsResult<Series> {
+    let gap_open = kwargs.gap_open;
+    let gap_extend = kwargs.gap_extend;
+    let mismatch = kwargs.mismatch;
+    let native_fn = kwargs.preprocess.wrap(0.0, move |s1, s2| {
+        native_affine_gap_distance(s1, s2, gap_open, gap_extend, mismatch)
+    });
+    parallel_apply_with::<_, Float64Type>(inputs, context, &kwargs.apply, native_fn)
+}
 
-    Ok(result)
+#[polars_expr(output_type=Float64)]
+fn custom_cost_damerau_levenshtein(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: CustomCostDLKwargs,
+) -> PolarsResult<Series> {
+    let costs = weighted_DL::EditCosts {
+        insertion: kwargs.ins_cost,
+        deletion: kwargs.del_cost,
+        substitution: kwargs.sub_cost,
+        transposition: kwargs.trans_cost,
+    };
+    let all_costs = [
+        costs.insertion,
+        costs.deletion,
+        costs.substitution,
+        costs.transposition,
+    ];
+    if all_costs
+        .iter()
+        .any(|cost| !cost.is_finite() || *cost < 0.0)
+    {
+        return Err(PolarsError::ComputeError(
+            "Edit costs must be finite and non-negative.".into(),
+        ));
+    }
+    let cost_model = kwargs.cost_model.map(CharCosts::new);
+    let native_fn = kwargs.preprocess.wrap(0.0, move |s1, s2| {
+        native_custom_cost_damerau_levenshtein(s1, s2, &costs, cost_model.as_ref())
+    });
+    parallel_apply_with::<_, Float64Type>(inputs, context, &kwargs.apply, native_fn)
 }
 
-pub(super) fn native_partial_damerau_levenshtein(a: &str, b: &str) -> i64 {
-    let count_a = a.chars().count();
-    let count_b = b.chars().count();
+/// Condensed pairwise score matrix of one column: row `i` holds the scores
+/// of `(i, j)` for every `j > i`, in the order `scipy.spatial.distance`
+/// uses for condensed matrices once the lists are concatenated.
+#[polars_expr(output_type_func=upper_triangle_output)]
+fn pairwise_matrix(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: PairwiseMatrixKwargs,
+) -> PolarsResult<Series> {
+    let (metric, identical_score) = native_metric_by_name(&kwargs.metric)?;
+    let native_fn = kwargs.preprocess.wrap(identical_score, metric);
+    let apply = if identical_score == 1.0 {
+        kwargs.apply.with_worst_score(0.0)
+    } else {
+        kwargs.apply
+    };
+    parallel_apply_upper_triangle(inputs, context, &apply, native_fn)
+}
 
-    if count_a == 0 || count_b == 0 {
-        return 0;
+/// Scores the aligned elements of two `List[String]` columns with a metric,
+/// returning the per-pair scores or their `agg` reduction.
+#[polars_expr(output_type_func_with_kwargs=zipped_list_output)]
+fn zipped_list_score(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: ZippedListKwargs,
+) -> PolarsResult<Series> {
+    let (metric, identical_score) = native_metric_by_name(&kwargs.metric)?;
+    let native_fn = kwargs.preprocess.wrap(identical_score, metric);
+    let apply = if identical_score == 1.0 {
+        kwargs.apply.with_worst_score(0.0)
+    } else {
+        kwargs.apply
+    };
+    let scores = parallel_apply_zipped_lists(inputs, context, &apply, native_fn)?;
+    match kwargs.agg {
+        Some(agg) => aggregate_list_scores(&scores, agg),
+        None => Ok(scores),
     }
+}
 
-    let (shorter, longer, k) = if count_a < count_b {
-        (a, b, count_a)
+/// Scores each string against every element of the same row's
+/// `List[String]` of candidates (e.g. aliases) and reduces the scores with
+/// `agg`, by default to the best one: the highest for similarities, the
+/// lowest for distances.
+#[polars_expr(output_type=Float64)]
+fn candidate_list_score(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: CandidateListKwargs,
+) -> PolarsResult<Series> {
+    let (metric, identical_score) = native_metric_by_name(&kwargs.metric)?;
+    let native_fn = kwargs.preprocess.wrap(identical_score, metric);
+    let (apply, best) = if identical_score == 1.0 {
+        (kwargs.apply.with_worst_score(0.0), ScoreAggregation::Max)
     } else {
-        (b, a, count_b)
+        (kwargs.apply, ScoreAggregation::Min)
     };
+    let agg = kwargs.agg.unwrap_or(best);
+    parallel_apply_str_with_candidates(inputs, context, &apply, agg, native_fn)
+}
 
-    let substrings = get_all_substrings(longer, k).unwrap();
-
-    let distances = substrings
+/// Weighted mean of similarity metrics over several string pairs (e.g. the
+/// name and address fields of two records), given as consecutive inputs
+/// with one metric and weight per pair. Null below `score_cutoff`.
+#[polars_expr(output_type=Float64)]
+fn composite_similarity(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: CompositeSimilarityKwargs,
+) -> PolarsResult<Series> {
+    if kwargs.metrics.len() != kwargs.weights.len() {
+        return Err(PolarsError::ComputeError(
+            "Expected one metric per weight.".into(),
+        ));
+    }
+    let metrics = kwargs
+        .metrics
         .iter()
-        .map(|substring| strsim::damerau_levenshtein(substring, shorter) as i64)
-        .collect::<Vec<_>>();
-
-    *distances.iter().min().unwrap()
+        .map(|name| match native_metric_by_name(name)? {
+            (metric, identical_score) if identical_score == 1.0 => {
+                Ok(kwargs.preprocess.clone().wrap(identical_score, metric))
+            }
+            _ => Err(PolarsError::ComputeError(
+                format!("'{name}' is not a similarity metric.").into(),
+            )),
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    parallel_apply_weighted_pairs(
+        inputs,
+        context,
+        &kwargs.apply.with_worst_score(0.0),
+        &kwargs.weights,
+        kwargs.score_cutoff,
+        |pair, a, b| metrics[pair](a, b),
+    )
 }
 
-pub(super) fn native_partial_normalized_damerau_levenshtein(a: &str, b: &str) -> f64 {
-    let count_a = a.chars().count();
-    let count_b = b.chars().count();
+/// `bias` plus the weighted sum of several metrics of the same pair, e.g. a
+/// linear scoring model fitted offline. Distances may be mixed in, usually
+/// with negative weights.
+#[polars_expr(output_type=Float64)]
+fn ensemble_similarity(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: EnsembleSimilarityKwargs,
+) -> PolarsResult<Series> {
+    if kwargs.metrics.is_empty() || kwargs.metrics.len() != kwargs.weights.len() {
+        return Err(PolarsError::ComputeError(
+            "Expected at least one metric and one weight per metric.".into(),
+        ));
+    }
+    let metrics = kwargs
+        .metrics
+        .iter()
+        .zip(&kwargs.weights)
+        .map(|(name, &weight)| {
+            let (metric, identical_score) = native_metric_by_name(name)?;
+            Ok((
+                kwargs.preprocess.clone().wrap(identical_score, metric),
+                weight,
+            ))
+        })
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let bias = kwargs.bias;
+    let native_fn = |a: &str, b: &str| {
+        metrics.iter().fold(bias, |score, (metric, weight)| {
+            score + weight * metric(a, b)
+        })
+    };
+    parallel_apply_with::<_, Float64Type>(inputs, context, &kwargs.apply, native_fn)
+}
 
-    if count_a == 0 || count_b == 0 {
-        return 0.0;
+/// Fixed-bucket histogram of a metric's scores over all pairs, accumulated
+/// per split without materializing the score column. Returns one row per
+/// bucket with its upper `breakpoint` and `count`.
+#[polars_expr(output_type_func=score_histogram_output)]
+fn score_histogram(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: ScoreHistogramKwargs,
+) -> PolarsResult<Series> {
+    if kwargs.bins == 0 || kwargs.upper <= kwargs.lower {
+        return Err(PolarsError::ComputeError(
+            "bins must be positive and upper must be greater than lower.".into(),
+        ));
     }
+    let (metric, identical_score) = native_metric_by_name(&kwargs.metric)?;
+    let native_fn = kwargs.preprocess.wrap(identical_score, metric);
+    let empty = Histogram::new(kwargs.bins, kwargs.lower, kwargs.upper);
 
-    let (shorter, longer, k) = if count_a < count_b {
-        (a, b, count_a)
-    } else {
-        (b, a, count_b)
-    };
+    let histogram = parallel_fold(
+        inputs,
+        context,
+        || empty.clone(),
+        |histogram, s1, s2| histogram.add(native_fn(s1, s2)),
+    )?
+    .iter()
+    .fold(empty.clone(), |mut total, split| {
+        total.merge(split);
+        total
+    });
 
-    let substrings = get_all_substrings(longer, k).unwrap();
+    let fields = [
+        Series::new("breakpoint".into(), histogram.breakpoints()),
+        Series::new("count".into(), histogram.counts()),
+    ];
+    Ok(StructChunked::from_series("".into(), kwargs.bins, fields.iter())?.into_series())
+}
 
-    let similarities = substrings
-        .iter()
-        .map(|substring| strsim::normalized_damerau_levenshtein(substring, shorter))
-        .collect::<Vec<_>>();
+#[polars_expr(output_type=String)]
+fn soundex(inputs: &[Series], context: CallerContext) -> PolarsResult<Series> {
+    parallel_apply_str(inputs, context, phonetic::soundex)
+}
 
-    *similarities
-        .iter()
-        .max_by(|a, b| a.partial_cmp(b).unwrap())
-        .unwrap()
+#[polars_expr(output_type_func=double_metaphone_output)]
+fn double_metaphone(inputs: &[Series], context: CallerContext) -> PolarsResult<Series> {
+    parallel_apply_str_pair(
+        inputs,
+        context,
+        ["primary", "alternate"],
+        phonetic::double_metaphone,
+    )
 }
 
-pub(super) fn parallel_apply_gwdl(
+#[polars_expr(output_type=String)]
+fn nysiis(inputs: &[Series], context: CallerContext, kwargs: NysiisKwargs) -> PolarsResult<Series> {
+    let max_length = kwargs.max_length;
+    parallel_apply_str(inputs, context, move |s| phonetic::nysiis(s, max_length))
+}
+
+#[polars_expr(output_type=String)]
+fn cologne_phonetics(inputs: &[Series], context: CallerContext) -> PolarsResult<Series> {
+    parallel_apply_str(inputs, context, phonetic::cologne_phonetics)
+}
+
+#[polars_expr(output_type_func_with_kwargs=phonetic_similarity_output)]
+fn phonetic_similarity(
     inputs: &[Series],
     context: CallerContext,
-    kwargs: WeightedDLKwargs,
+    kwargs: PhoneticSimilarityKwargs,
 ) -> PolarsResult<Series> {
-    let weighted_geometric_ratio = kwargs.weighted_geometric_ratio;
-    let normalized = kwargs.normalized;
-    parallel_apply::<_, Float64Type>(inputs, context, move |s1, s2| {
-        native_geometric_weighted_damerau_levenshtein(s1, s2, weighted_geometric_ratio, normalized)
-    })
+    let algorithm = kwargs.algorithm;
+    let native_fn = kwargs.preprocess.wrap(1.0, move |s1, s2| {
+        native_phonetic_similarity(s1, s2, algorithm)
+    });
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
 }
 
-pub(super) fn parallel_apply_gwdl_bywords(
+/// Match Rating Approach comparison, or with `score` the similarity rating
+/// (null where the rules do not allow a comparison).
+#[polars_expr(output_type_func_with_kwargs=mra_comparison_output)]
+fn mra_comparison(
     inputs: &[Series],
     context: CallerContext,
-    kwargs: WeightedDLByWordsKwargs,
+    kwargs: MraKwargs,
 ) -> PolarsResult<Series> {
-    let weighted_geometric_ratio = kwargs.weighted_geometric_ratio;
-    let normalized = kwargs.normalized;
-    let agg = kwargs.agg;
-    parallel_apply::<_, Float64Type>(inputs, context, move |s1, s2| {
-        native_geometric_weighted_damerau_levenshtein_bywords(
-            s1,
-            s2,
-            weighted_geometric_ratio,
-            normalized,
-            &agg,
-        )
-    })
+    if kwargs.score {
+        let native_fn = kwargs.preprocess.wrap(Some(6), |s1, s2| {
+            phonetic::mra_rating(s1, s2).map(|(rating, _)| rating as i64)
+        });
+        return parallel_apply_opt_with::<_, Int64Type>(
+            inputs,
+            context,
+            &kwargs.apply.with_worst_score(0.0),
+            native_fn,
+        );
+    }
+    let native_fn = kwargs.preprocess.wrap(true, phonetic::mra_comparison);
+    parallel_apply_bool_with(
+        inputs,
+        context,
+        &kwargs.apply.with_worst_score(0.0),
+        native_fn,
+    )
 }
 
-// Workaround for arrow::ffi module resolution issue
-mod arrow {
-    pub use polars_arrow::ffi;
+/// Whether each pair passes `threshold` under `metric`, see
+/// `native_threshold_test_by_name`.
+#[polars_expr(output_type=Boolean)]
+fn is_similar(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: IsSimilarKwargs,
+) -> PolarsResult<Series> {
+    let test = native_threshold_test_by_name(&kwargs.metric, kwargs.threshold)?;
+    let native_fn = kwargs.preprocess.wrap(true, test);
+    parallel_apply_bool_with(
+        inputs,
+        context,
+        &kwargs.apply.with_worst_score(0.0),
+        native_fn,
+    )
 }
 
 #[polars_expr(output_type=Int64)]
-fn damerau_levenshtein(inputs: &[Series], context: CallerContext) -> PolarsResult<Series> {
-    parallel_apply::<_, Int64Type>(inputs, context, native_damerau_levenshtein)
+fn common_prefix_len(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: MetricKwargs,
+) -> PolarsResult<Series> {
+    let native_fn = kwargs
+        .preprocess
+        .wrap_with_identical(|s| s.chars().count() as i64, native_common_prefix_len);
+    parallel_apply_with::<_, Int64Type>(
+        inputs,
+        context,
+        &kwargs.apply.with_worst_score(0.0),
+        native_fn,
+    )
 }
 
-#[polars_expr(output_type=Float64)]
-fn normalized_damerau_levenshtein(
+#[polars_expr(output_type=Int64)]
+fn common_suffix_len(
     inputs: &[Series],
     context: CallerContext,
+    kwargs: MetricKwargs,
 ) -> PolarsResult<Series> {
-    parallel_apply::<_, Float64Type>(inputs, context, native_normalized_damerau_levenshtein)
+    let native_fn = kwargs
+        .preprocess
+        .wrap_with_identical(|s| s.chars().count() as i64, native_common_suffix_len);
+    parallel_apply_with::<_, Int64Type>(
+        inputs,
+        context,
+        &kwargs.apply.with_worst_score(0.0),
+        native_fn,
+    )
 }
 
 #[polars_expr(output_type=Int64)]
-fn partial_damerau_levenshtein(inputs: &[Series], context: CallerContext) -> PolarsResult<Series> {
-    parallel_apply::<_, Int64Type>(inputs, context, native_partial_damerau_levenshtein)
+fn qgram_distance(
+    inputs: &[Series],
+    context: CallerContext,
+    kwargs: QgramKwargs,
+) -> PolarsResult<Series> {
+    if kwargs.q == 0 {
+        return Err(PolarsError::ComputeError("q must be positive.".into()));
+    }
+    let q = kwargs.q;
+    let native_fn = kwargs
+        .preprocess
+        .wrap(0, move |s1, s2| ngram::qgram_distance(s1, s2, q) as i64);
+    parallel_apply_with::<_, Int64Type>(inputs, context, &kwargs.apply, native_fn)
 }
 
-#[polars_expr(output_type=Float64)]
-fn partial_normalized_damerau_levenshtein(
+/// The overlapping character n-grams of each string as used by the q-gram
+/// metrics, after preprocessing and optional boundary padding.
+#[polars_expr(output_type_func=string_list_output)]
+fn ngrams(inputs: &[Series], context: CallerContext, kwargs: NgramKwargs) -> PolarsResult<Series> {
+    if kwargs.n == 0 {
+        return Err(PolarsError::ComputeError("n must be positive.".into()));
+    }
+    let (n, pad) = (kwargs.n, kwargs.pad);
+    let preprocess = &kwargs.preprocess;
+    parallel_apply_str_list(inputs, context, &kwargs.apply, &DataType::String, |s| {
+        let s = preprocess.apply(s);
+        let s = match pad {
+            Some(pad) => ngram::padded(&s, n, pad),
+            None => s.into_owned(),
+        };
+        Ok(Series::new("".into(), ngram::qgrams(&s, n)))
+    })
+}
+
+/// MinHash signature of the character shingles of each string, after
+/// preprocessing.
+#[polars_expr(output_type_func=minhash_output)]
+fn minhash(
     inputs: &[Series],
     context: CallerContext,
+    kwargs: MinHashKwargs,
 ) -> PolarsResult<Series> {
-    parallel_apply::<_, Float64Type>(
-        inputs,
-        context,
-        native_partial_normalized_damerau_levenshtein,
-    )
+    if kwargs.num_hashes == 0 {
+        return Err(PolarsError::ComputeError(
+            "num_hashes must be positive.".into(),
+        ));
+    }
+    if kwargs.shingle_size == 0 {
+        return Err(PolarsError::ComputeError(
+            "shingle_size must be positive.".into(),
+        ));
+    }
+    let (num_hashes, shingle_size, seed) = (kwargs.num_hashes, kwargs.shingle_size, kwargs.seed);
+    let preprocess = &kwargs.preprocess;
+    parallel_apply_str_list(inputs, context, &kwargs.apply, &DataType::UInt64, |s| {
+        let signature = minhash::signature(&preprocess.apply(s), shingle_size, num_hashes, seed);
+        Ok(Series::new("".into(), signature))
+    })
 }
 
+/// Jaccard similarity of the shingle sets of two strings, estimated from
+/// their `minhash` signatures.
 #[polars_expr(output_type=Float64)]
-fn geometric_weighted_damerau_levenshtein(
+fn minhash_similarity(
     inputs: &[Series],
     context: CallerContext,
-    kwargs: WeightedDLKwargs,
+    kwargs: MinHashSimilarityKwargs,
 ) -> PolarsResult<Series> {
-    parallel_apply_gwdl(inputs, context, kwargs)
+    if inputs.len() != 2 {
+        return Err(PolarsError::ComputeError(
+            "Expected two signature columns.".into(),
+        ));
+    }
+    parallel_apply_lists::<_, Float64Type>(inputs, context, &kwargs.apply, |a, b, _| {
+        native_minhash_similarity(a, b)
+    })
 }
 
-#[polars_expr(output_type=Float64)]
-fn geometric_weighted_damerau_levenshtein_bywords(
+/// Soft-TFIDF in two passes: token document frequencies are gathered from
+/// every non-null string of both inputs, then each pair is scored against
+/// those statistics.
+#[polars_expr(output_type_func_with_kwargs=soft_tfidf_output)]
+fn soft_tfidf(
     inputs: &[Series],
     context: CallerContext,
-    kwargs: WeightedDLByWordsKwargs,
+    kwargs: SoftTfidfKwargs,
 ) -> PolarsResult<Series> {
-    parallel_apply_gwdl_bywords(inputs, context, kwargs)
+    let preprocess = kwargs.preprocess.clone();
+    let stats = parallel_fold_strings(inputs, context, TokenStats::default, |stats, s| {
+        stats.add_document(&preprocess.apply(s))
+    })?
+    .into_iter()
+    .fold(TokenStats::default(), |mut total, split| {
+        total.merge(split);
+        total
+    });
+
+    let threshold = kwargs.threshold;
+    let native_fn = kwargs
+        .preprocess
+        .wrap(1.0, |s1, s2| corpus::soft_tfidf(&stats, s1, s2, threshold));
+    apply_similarity(
+        inputs,
+        context,
+        &kwargs.apply,
+        kwargs.score_cutoff,
+        native_fn,
+    )
 }
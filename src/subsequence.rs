@@ -0,0 +1,49 @@
+// subsequence containment
+
+use crate::deadline;
+use crate::fuzz;
+
+/// Length of the longest subsequence of `a` that can be matched, in order,
+/// against positions of `b` where consecutive matched positions of `b` skip
+/// at most `max_gap` elements.
+///
+/// `best[j]` holds the longest chain found so far (over earlier rows of `a`)
+/// that ends on `b[j]`; each row extends the best chain ending inside the
+/// `max_gap + 1` positions before `j`.
+fn gap_bounded_subsequence_len<Elem: Eq>(a: &[Elem], b: &[Elem], max_gap: usize) -> usize {
+    let mut best = vec![0usize; b.len()];
+    let mut longest = 0;
+    for a_elem in a {
+        if deadline::exceeded() {
+            break;
+        }
+        let previous = best.clone();
+        for (j, b_elem) in b.iter().enumerate() {
+            if a_elem != b_elem {
+                continue;
+            }
+            let window_start = j.saturating_sub(max_gap + 1);
+            let chain = previous[window_start..j].iter().copied().max().unwrap_or(0) + 1;
+            if chain > best[j] {
+                best[j] = chain;
+                longest = longest.max(chain);
+            }
+        }
+    }
+    longest
+}
+
+/// How completely `a` appears as a (not necessarily contiguous) subsequence
+/// of `b`: the number of elements of `a` that can be matched in order, divided
+/// by `len(a)`. With `max_gap`, consecutive matches may skip at most that
+/// many elements of `b`. Returns `0.0` if either input is empty.
+pub fn subsequence_containment<Elem: Eq>(a: &[Elem], b: &[Elem], max_gap: Option<usize>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let matched = match max_gap {
+        Some(max_gap) => gap_bounded_subsequence_len(a, b, max_gap),
+        None => fuzz::lcs_len(a, b),
+    };
+    matched as f64 / a.len() as f64
+}
@@ -0,0 +1,119 @@
+// transliteration of Latin, Greek and Cyrillic text to ASCII
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// ASCII spelling of a lowercase letter or punctuation mark that NFKD does
+/// not already reduce to ASCII. Cyrillic follows the usual English
+/// romanization ("ж" -> "zh", "щ" -> "shch") and Greek the Latin letter
+/// each letter is commonly spelled with.
+fn ascii_spelling(c: char) -> Option<&'static str> {
+    Some(match c {
+        // Latin letters without a decomposition.
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        'ø' => "o",
+        'đ' | 'ð' => "d",
+        'þ' => "th",
+        'ł' => "l",
+        'ı' => "i",
+        'ħ' => "h",
+        'ŧ' => "t",
+        'ŋ' => "ng",
+        'ə' => "e",
+        // Cyrillic.
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' => "g",
+        'д' => "d",
+        'е' | 'э' => "e",
+        'є' => "ie",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ы' => "y",
+        'ю' => "iu",
+        'я' => "ia",
+        // Greek.
+        'α' => "a",
+        'β' => "b",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' | 'η' => "e",
+        'ζ' => "z",
+        'θ' => "th",
+        'ι' => "i",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' | 'ω' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' | 'ς' => "s",
+        'τ' => "t",
+        'υ' => "y",
+        'φ' => "ph",
+        'χ' => "ch",
+        'ψ' => "ps",
+        // Typographic punctuation.
+        '‘' | '’' | '‚' | '′' => "'",
+        '“' | '”' | '„' | '″' | '«' | '»' => "\"",
+        '‐' | '‑' | '‒' | '–' | '—' | '―' | '−' => "-",
+        _ => return None,
+    })
+}
+
+/// Folds `s` towards ASCII: compatibility decomposition (NFKD) with the
+/// combining marks dropped takes care of accents, ligatures and full-width
+/// forms ("Müller" -> "Muller", "ﬁ" -> "fi"), and the Latin letters,
+/// Cyrillic and Greek letters and punctuation of `ascii_spelling` are
+/// spelled out ("Москва" -> "Moskva", "Αθήνα" -> "Athena"). An uppercase
+/// letter is spelled with its first letter capitalized ("Щ" -> "Shch").
+/// Characters of other scripts are kept as they are.
+pub fn transliterate(s: &str) -> String {
+    let mut ascii = String::with_capacity(s.len());
+    for c in s.nfkd().filter(|&c| !is_combining_mark(c)) {
+        if c.is_ascii() {
+            ascii.push(c);
+            continue;
+        }
+        let mut lowercase = c.to_lowercase();
+        let spelling = match (lowercase.next(), lowercase.next()) {
+            (Some(lower), None) => ascii_spelling(lower),
+            _ => None,
+        };
+        match spelling {
+            Some(spelling) if c.is_uppercase() => {
+                let mut chars = spelling.chars();
+                if let Some(first) = chars.next() {
+                    ascii.push(first.to_ascii_uppercase());
+                    ascii.push_str(chars.as_str());
+                }
+            }
+            Some(spelling) => ascii.push_str(spelling),
+            None => ascii.push(c),
+        }
+    }
+    ascii
+}
@@ -1,11 +1,53 @@
+use crate::buffers::{with_buffers, with_chars};
+use crate::deadline;
+use crate::segment::Tokenizer;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::hash::Hash;
+use std::rc::Rc;
 
 #[derive(Clone, Copy)]
 pub enum ByWordsAggregation {
     Max,
     Mean,
     Min,
+    /// Mean weighted by the length of the longer word of each pair.
+    WeightedMean,
+}
+
+/// How the bywords distance pairs the words of the shorter string with
+/// those of the longer one.
+#[derive(Clone, Copy)]
+pub enum WordAlignment {
+    /// The `i`-th word with the `i`-th word.
+    Positional,
+    /// Every word with a distinct word, in order, minimizing the total
+    /// distance; the unpaired words of the longer string are skipped.
+    Optimal,
+    /// Every word with a distinct word in any order, minimizing the total
+    /// distance (an optimal bipartite assignment).
+    Assignment,
+}
+
+/// How the bywords distance pairs and combines the word distances.
+#[derive(Clone, Copy)]
+pub struct ByWordsOptions {
+    pub agg: ByWordsAggregation,
+    pub alignment: WordAlignment,
+    /// Geometric ratio of the word weights, by position in the shorter
+    /// string; `1.0` weighs every word equally.
+    pub word_ratio: f64,
+    /// Distance entered into the aggregation for every word of the longer
+    /// string left unpaired, with weight `1`; such words are ignored when
+    /// `None`.
+    pub unmatched_distance: Option<f64>,
+}
+
+/// Which end of the strings the geometric weights emphasize.
+#[derive(Clone, Copy)]
+pub enum WeightDirection {
+    Descending,
+    Ascending,
 }
 
 /* Returns the final index for a value in a single vector that represents a fixed
@@ -14,6 +56,46 @@ fn flat_index(i: usize, j: usize, width: usize) -> usize {
     j * width + i
 }
 
+/// Class of the elements of the longer sequence that do not occur in the
+/// shorter one, see `element_classes`.
+const NO_CLASS: usize = usize::MAX;
+
+/// Class ids for the "last row holding this element" lookups of the
+/// Damerau-Levenshtein DP, found with equality alone so the DP needs no
+/// per-call hash map: equal elements of `a` and `b` get the same id below
+/// `min(|a|, |b|)`, and elements of the longer sequence that the shorter one
+/// lacks get `NO_CLASS`. Takes `O((|a| + |b|) * d)` comparisons for the `d`
+/// distinct elements of the shorter sequence, which is at most the
+/// `O(|a| * |b|)` of the DP. `distinct` is scratch space.
+fn element_classes<Elem: Eq>(
+    a: &[Elem],
+    b: &[Elem],
+    [a_classes, b_classes, distinct]: [&mut Vec<usize>; 3],
+) {
+    let (shorter, longer, shorter_classes, longer_classes) = if a.len() <= b.len() {
+        (a, b, a_classes, b_classes)
+    } else {
+        (b, a, b_classes, a_classes)
+    };
+    // The first position of each distinct element of `shorter` is its id.
+    for (i, elem) in shorter.iter().enumerate() {
+        let class = match distinct.iter().find(|&&first| shorter[first] == *elem) {
+            Some(&first) => first,
+            None => {
+                distinct.push(i);
+                i
+            }
+        };
+        shorter_classes.push(class);
+    }
+    longer_classes.extend(longer.iter().map(|elem| {
+        distinct
+            .iter()
+            .find(|&&first| shorter[first] == *elem)
+            .map_or(NO_CLASS, |&first| first)
+    }));
+}
+
 /// Like optimal string alignment, but substrings can be edited an unlimited
 /// number of times, and the triangle inequality holds. Weighted version where
 /// deletion costs from `a_elems` are multiplied by `weight_a` (position-dependent)
@@ -23,26 +105,48 @@ fn flat_index(i: usize, j: usize, width: usize) -> usize {
 /// four involved character weights: `weight_a` for both swapped chars and `weight_b`
 /// for both swapped chars. For an adjacent swap at (i,j), this is
 /// `(weight_a[i-1] + weight_a[i-2] + weight_b[j-1] + weight_b[j-2]) / 4`.
-pub fn generic_weighted_damerau_levenshtein<Elem>(
+///
+/// The DP matrix and bookkeeping live in the thread's pooled buffers
+/// (`buffers::with_buffers`), so repeated calls do not allocate.
+pub fn generic_weighted_damerau_levenshtein<Elem: Eq>(
     a_elems: &[Elem],
     b_elems: &[Elem],
     weight_a: &[f64],
     weight_b: &[f64],
-) -> f64
-where
-    Elem: Eq + Hash + Clone,
-{
+) -> f64 {
+    with_buffers(|[distances, prefix_a, prefix_b]: [&mut Vec<f64>; 3]| {
+        with_buffers(|[a_classes, b_classes, distinct, last_rows]| {
+            weighted_damerau_levenshtein_in(
+                a_elems,
+                b_elems,
+                weight_a,
+                weight_b,
+                [distances, prefix_a, prefix_b],
+                [a_classes, b_classes, distinct, last_rows],
+            )
+        })
+    })
+}
+
+fn weighted_damerau_levenshtein_in<Elem: Eq>(
+    a_elems: &[Elem],
+    b_elems: &[Elem],
+    weight_a: &[f64],
+    weight_b: &[f64],
+    [distances, prefix_a, prefix_b]: [&mut Vec<f64>; 3],
+    [a_classes, b_classes, distinct, last_rows]: [&mut Vec<usize>; 4],
+) -> f64 {
     let a_len = a_elems.len();
     let b_len = b_elems.len();
 
     assert_eq!(weight_a.len(), a_len);
     assert_eq!(weight_b.len(), b_len);
 
-    let mut prefix_a: Vec<f64> = vec![0.0];
+    prefix_a.push(0.0);
     for &w in weight_a {
         prefix_a.push(*prefix_a.last().unwrap() + w);
     }
-    let mut prefix_b: Vec<f64> = vec![0.0];
+    prefix_b.push(0.0);
     for &w in weight_b {
         prefix_b.push(*prefix_b.last().unwrap() + w);
     }
@@ -55,7 +159,7 @@ where
     }
 
     let width = a_len + 2;
-    let mut distances = vec![0.0_f64; (a_len + 2) * (b_len + 2)];
+    distances.resize((a_len + 2) * (b_len + 2), 0.0);
     let max_distance = prefix_a[a_len] + prefix_b[b_len] + 1.0;
 
     distances[0] = max_distance;
@@ -70,13 +174,20 @@ where
         distances[flat_index(1, j + 1, width)] = prefix_b[j];
     }
 
-    let mut elems: HashMap<Elem, usize> = HashMap::with_capacity(64);
+    element_classes(a_elems, b_elems, [a_classes, b_classes, distinct]);
+    last_rows.resize(a_len.min(b_len), 0);
 
     for i in 1..=a_len {
+        if deadline::exceeded() {
+            return max_distance;
+        }
         let mut db = 0;
 
         for j in 1..=b_len {
-            let k = *elems.get(&b_elems[j - 1]).unwrap_or(&0);
+            let k = match b_classes[j - 1] {
+                NO_CLASS => 0,
+                class => last_rows[class],
+            };
 
             let deletion_cost_code = distances[flat_index(i, j + 1, width)] + weight_a[i - 1];
             let insertion_cost_code = distances[flat_index(i + 1, j, width)] + weight_b[j - 1];
@@ -118,12 +229,268 @@ where
             }
         }
 
+        if a_classes[i - 1] != NO_CLASS {
+            last_rows[a_classes[i - 1]] = i;
+        }
+    }
+
+    distances[flat_index(a_len + 1, b_len + 1, width)]
+}
+
+/// Cost of each edit operation for `custom_cost_damerau_levenshtein`.
+#[derive(Clone, Copy, Debug)]
+pub struct EditCosts {
+    pub insertion: f64,
+    pub deletion: f64,
+    pub substitution: f64,
+    pub transposition: f64,
+}
+
+/// Relative substitution costs for `custom_cost_damerau_levenshtein`, as
+/// multiples of `EditCosts::substitution`.
+pub trait SubstitutionScale<Elem> {
+    /// Scale for substituting `a` with `b` (never called for equal elements).
+    fn scale(&self, a: &Elem, b: &Elem) -> f64;
+
+    /// Scale for reading the pair `first, second` as the single element
+    /// `merged` (e.g. OCR reading "m" as "rn"), or `None` when the pair is
+    /// not interchangeable with it.
+    fn merge_scale(&self, _first: &Elem, _second: &Elem, _merged: &Elem) -> Option<f64> {
+        None
+    }
+}
+
+/// Every substitution costs the full `EditCosts::substitution`.
+pub struct UniformSubstitution;
+
+impl<Elem> SubstitutionScale<Elem> for UniformSubstitution {
+    fn scale(&self, _a: &Elem, _b: &Elem) -> f64 {
+        1.0
+    }
+}
+
+/// Unrestricted Damerau-Levenshtein (Lowrance-Wagner) with a separate cost
+/// per operation: deleting an element of `a_elems` costs `deletion`,
+/// inserting one of `b_elems` costs `insertion`. A transposition costs
+/// `transposition` plus the deletions and insertions of the elements between
+/// the swapped pair. Substitutions cost `substitution` scaled by
+/// `substitution_scale`, which may also allow two elements on either side to
+/// be substituted by one on the other. The result is optimal as long as
+/// `2 * transposition >= insertion + deletion`.
+pub fn custom_cost_damerau_levenshtein<Elem, S>(
+    a_elems: &[Elem],
+    b_elems: &[Elem],
+    costs: &EditCosts,
+    substitution_scale: &S,
+) -> f64
+where
+    Elem: Eq + Hash + Clone,
+    S: SubstitutionScale<Elem>,
+{
+    let a_len = a_elems.len();
+    let b_len = b_elems.len();
+
+    if a_len == 0 {
+        return b_len as f64 * costs.insertion;
+    }
+    if b_len == 0 {
+        return a_len as f64 * costs.deletion;
+    }
+
+    let width = a_len + 2;
+    let mut distances = vec![0.0_f64; (a_len + 2) * (b_len + 2)];
+    let max_distance = a_len as f64 * costs.deletion + b_len as f64 * costs.insertion + 1.0;
+
+    distances[0] = max_distance;
+
+    for i in 0..=a_len {
+        distances[flat_index(i + 1, 0, width)] = max_distance;
+        distances[flat_index(i + 1, 1, width)] = i as f64 * costs.deletion;
+    }
+
+    for j in 0..=b_len {
+        distances[flat_index(0, j + 1, width)] = max_distance;
+        distances[flat_index(1, j + 1, width)] = j as f64 * costs.insertion;
+    }
+
+    let mut elems: HashMap<Elem, usize> = HashMap::with_capacity(64);
+
+    for i in 1..=a_len {
+        if deadline::exceeded() {
+            return max_distance;
+        }
+        let mut db = 0;
+
+        for j in 1..=b_len {
+            let k = *elems.get(&b_elems[j - 1]).unwrap_or(&0);
+
+            let deletion_cost = distances[flat_index(i, j + 1, width)] + costs.deletion;
+            let insertion_cost = distances[flat_index(i + 1, j, width)] + costs.insertion;
+
+            let is_match = a_elems[i - 1] == b_elems[j - 1];
+            let substitution_cost = distances[flat_index(i, j, width)]
+                + if is_match {
+                    0.0
+                } else {
+                    costs.substitution * substitution_scale.scale(&a_elems[i - 1], &b_elems[j - 1])
+                };
+            let merge_cost = if i >= 2 {
+                substitution_scale
+                    .merge_scale(&a_elems[i - 2], &a_elems[i - 1], &b_elems[j - 1])
+                    .map(|scale| {
+                        distances[flat_index(i - 1, j, width)] + costs.substitution * scale
+                    })
+            } else {
+                None
+            };
+            let split_cost = if j >= 2 {
+                substitution_scale
+                    .merge_scale(&b_elems[j - 2], &b_elems[j - 1], &a_elems[i - 1])
+                    .map(|scale| {
+                        distances[flat_index(i, j - 1, width)] + costs.substitution * scale
+                    })
+            } else {
+                None
+            };
+
+            let transposition_cost = distances[flat_index(k, db, width)]
+                + (i - k - 1) as f64 * costs.deletion
+                + (j - db - 1) as f64 * costs.insertion
+                + costs.transposition;
+
+            distances[flat_index(i + 1, j + 1, width)] = substitution_cost
+                .min(deletion_cost)
+                .min(insertion_cost)
+                .min(transposition_cost)
+                .min(merge_cost.unwrap_or(f64::INFINITY))
+                .min(split_cost.unwrap_or(f64::INFINITY));
+
+            if is_match {
+                db = j;
+            }
+        }
+
         elems.insert(a_elems[i - 1].clone(), i);
     }
 
     distances[flat_index(a_len + 1, b_len + 1, width)]
 }
 
+/// Unrestricted Damerau-Levenshtein distance, or `None` as soon as it is
+/// known to exceed `max_distance`.
+///
+/// Every entry of the DP is at least its offset from the main diagonal, and
+/// an entry within `max_distance` only depends on entries within
+/// `max_distance` (every edit costs at least 1), so only the diagonal band
+/// of width `2 * max_distance + 1` is computed (Ukkonen); entries outside
+/// it read as unreachable. This takes `O(|a| * max_distance)` time and
+/// memory instead of `O(|a| * |b|)`. The smallest entry of a band row is a
+/// lower bound on the final distance (a transposition spanning the row
+/// costs at least the deletions it skips), so the DP also stops at the first
+/// row whose minimum is above the bound. Like
+/// `generic_weighted_damerau_levenshtein`, it works in pooled buffers.
+pub fn bounded_damerau_levenshtein<Elem: Eq>(
+    a_elems: &[Elem],
+    b_elems: &[Elem],
+    max_distance: usize,
+) -> Option<usize> {
+    with_buffers(|[distances, a_classes, b_classes, distinct, last_rows]| {
+        bounded_damerau_levenshtein_in(
+            a_elems,
+            b_elems,
+            max_distance,
+            distances,
+            [a_classes, b_classes, distinct, last_rows],
+        )
+    })
+}
+
+fn bounded_damerau_levenshtein_in<Elem: Eq>(
+    a_elems: &[Elem],
+    b_elems: &[Elem],
+    max_distance: usize,
+    distances: &mut Vec<usize>,
+    [a_classes, b_classes, distinct, last_rows]: [&mut Vec<usize>; 4],
+) -> Option<usize> {
+    let a_len = a_elems.len();
+    let b_len = b_elems.len();
+
+    if a_len.abs_diff(b_len) > max_distance {
+        return None;
+    }
+    if a_len == 0 || b_len == 0 {
+        return Some(a_len.max(b_len));
+    }
+
+    // No distance exceeds the longer length, so neither does the band.
+    let band = max_distance.min(a_len.max(b_len));
+    let width = 2 * band + 1;
+    let unreachable = a_len + b_len + 1;
+    // distances[i * width + j + band - i]: the distance between the first
+    // `i` elements of `a` and the first `j` of `b`, for `|i - j| <= band`.
+    distances.resize((a_len + 1) * width, unreachable);
+    let band_index =
+        |i: usize, j: usize| (j + band >= i && j <= i + band).then(|| i * width + j + band - i);
+    let distance_at = |distances: &[usize], i: usize, j: usize| {
+        band_index(i, j).map_or(unreachable, |index| distances[index])
+    };
+
+    for j in 0..=band.min(b_len) {
+        distances[band + j] = j;
+    }
+    for i in 1..=band.min(a_len) {
+        distances[i * width + band - i] = i;
+    }
+
+    element_classes(a_elems, b_elems, [a_classes, b_classes, distinct]);
+    last_rows.resize(a_len.min(b_len), 0);
+
+    for i in 1..=a_len {
+        if deadline::exceeded() {
+            return None;
+        }
+        let mut db = 0;
+        let mut row_min = if i <= band { i } else { unreachable };
+
+        for j in i.saturating_sub(band).max(1)..=(i + band).min(b_len) {
+            let k = match b_classes[j - 1] {
+                NO_CLASS => 0,
+                class => last_rows[class],
+            };
+
+            let is_match = a_elems[i - 1] == b_elems[j - 1];
+            let substitution_cost = distance_at(distances, i - 1, j - 1) + usize::from(!is_match);
+            let deletion_cost = distance_at(distances, i - 1, j) + 1;
+            let insertion_cost = distance_at(distances, i, j - 1) + 1;
+            let transposition_cost = if k > 0 && db > 0 {
+                distance_at(distances, k - 1, db - 1) + (i - k - 1) + 1 + (j - db - 1)
+            } else {
+                unreachable
+            };
+
+            let distance = substitution_cost
+                .min(deletion_cost)
+                .min(insertion_cost)
+                .min(transposition_cost);
+            distances[i * width + j + band - i] = distance;
+            row_min = row_min.min(distance);
+
+            if is_match {
+                db = j;
+            }
+        }
+
+        if row_min > max_distance {
+            return None;
+        }
+        if a_classes[i - 1] != NO_CLASS {
+            last_rows[a_classes[i - 1]] = i;
+        }
+    }
+
+    Some(distance_at(distances, a_len, b_len)).filter(|&d| d <= max_distance)
+}
+
 // weighted damerau levenshtein
 
 /// Generate a descending geometric weight sequence of length `n` with ratio `k`,
@@ -165,6 +532,116 @@ fn normalized_geometric_descending_weights(n: usize, k: f64) -> Vec<f64> {
     weights
 }
 
+/// Weight vectors longer than this are generated per call rather than
+/// cached, so one long outlier does not stay resident.
+const MAX_CACHED_WEIGHTS_LEN: usize = 1024;
+
+/// Number of `(k, n)` weight vectors a thread keeps before its cache is
+/// cleared.
+const MAX_CACHED_WEIGHT_VECTORS: usize = 4096;
+
+/// Geometric weight vectors by the bits of their `k` and their length.
+type WeightCache = HashMap<(u64, usize), Rc<[f64]>>;
+
+thread_local! {
+    static GEOMETRIC_WEIGHTS: RefCell<WeightCache> = RefCell::new(HashMap::new());
+}
+
+/// `normalized_geometric_descending_weights`, cached per thread by `(k, n)`.
+/// Every row of an expression shares its `k` and lengths repeat, so the
+/// weights are generated once per distinct length rather than once per row
+/// (and per word pair in the by-words variants).
+fn cached_geometric_weights(n: usize, k: f64) -> Rc<[f64]> {
+    if n > MAX_CACHED_WEIGHTS_LEN {
+        return normalized_geometric_descending_weights(n, k).into();
+    }
+    let key = (k.to_bits(), n);
+    GEOMETRIC_WEIGHTS.with_borrow_mut(|cache| {
+        if cache.len() >= MAX_CACHED_WEIGHT_VECTORS && !cache.contains_key(&key) {
+            cache.clear();
+        }
+        cache
+            .entry(key)
+            .or_insert_with(|| normalized_geometric_descending_weights(n, k).into())
+            .clone()
+    })
+}
+
+/// Shape of the positional weights, from the first position to the last.
+pub enum WeightProfile {
+    /// `k^i`, see `normalized_geometric_descending_weights`, after a plateau
+    /// of `plateau_len` equally weighted leading positions (`k^0` up to
+    /// position `plateau_len - 1`, then `k^1`, `k^2`, ...).
+    Geometric { ratio: f64, plateau_len: usize },
+    /// `n - i`: falls linearly from `n` to `1`.
+    Linear,
+    /// `exp(-decay * i)`.
+    Exponential(f64),
+    /// A Gaussian bump over the relative position `(i + 0.5) / n`, peaking
+    /// at `center` with standard deviation `width` (both relative, in
+    /// `[0, 1]` for a bump inside the string).
+    Gaussian { center: f64, width: f64 },
+    /// `weights[s]` for the positions of step `s`, where step `s` starts at
+    /// `breakpoints[s - 1]` (`weights` holds one more entry than
+    /// `breakpoints`), e.g. `[3]` and `[2, 1]` for "the first three
+    /// characters count twice".
+    Step {
+        breakpoints: Vec<usize>,
+        weights: Vec<f64>,
+    },
+}
+
+impl WeightProfile {
+    /// `n` weights of this profile, normalized so they sum to `n`.
+    fn normalized_weights(&self, n: usize) -> Vec<f64> {
+        let raw: Vec<f64> = match self {
+            WeightProfile::Geometric {
+                ratio,
+                plateau_len: 0 | 1,
+            } => return normalized_geometric_descending_weights(n, *ratio),
+            WeightProfile::Geometric { ratio, plateau_len } => {
+                let decay = normalized_geometric_descending_weights(n, *ratio);
+                (0..n)
+                    .map(|i| decay[i.saturating_sub(plateau_len - 1)])
+                    .collect()
+            }
+            WeightProfile::Linear => (0..n).map(|i| (n - i) as f64).collect(),
+            WeightProfile::Exponential(decay) => {
+                (0..n).map(|i| (-decay * i as f64).exp()).collect()
+            }
+            WeightProfile::Gaussian { center, width } => (0..n)
+                .map(|i| {
+                    let offset = ((i as f64 + 0.5) / n as f64 - center) / width;
+                    (-0.5 * offset * offset).exp()
+                })
+                .collect(),
+            WeightProfile::Step {
+                breakpoints,
+                weights,
+            } => (0..n)
+                .map(|i| weights[breakpoints.partition_point(|&start| start <= i)])
+                .collect(),
+        };
+        let sum: f64 = raw.iter().sum();
+        if sum <= 0.0 {
+            return vec![1.0; n];
+        }
+        let scale = n as f64 / sum;
+        raw.into_iter().map(|w| w * scale).collect()
+    }
+}
+
+/// Mean of the positional weights of every character taking part in a
+/// comparison. Dividing a weighted distance by it expresses the distance in
+/// "unit edits", i.e. as if every consumed position had weight `1`.
+fn mean_consumed_weight(weight_a: &[f64], weight_b: &[f64]) -> f64 {
+    let count = weight_a.len() + weight_b.len();
+    if count == 0 {
+        return 1.0;
+    }
+    (weight_a.iter().sum::<f64>() + weight_b.iter().sum::<f64>()) / count as f64
+}
+
 /// Wrapper over generic weighted Damerau-Levenshtein that uses normalized
 /// descending geometric weights for both strings, parameterized by `k`.
 ///
@@ -172,38 +649,170 @@ fn normalized_geometric_descending_weights(n: usize, k: f64) -> Vec<f64> {
 /// normalize using a shared scale based on `max(len(a), len(b))` and then
 /// slice the weights for each string. This avoids making early-character
 /// weights larger solely because one string is longer.
+///
+/// With `unit_edits`, the distance is divided by the mean weight of the
+/// positions both strings actually consume, so rows of different lengths and
+/// `k` values land on the same scale as an unweighted edit count.
 pub fn normalized_descending_weighted_damerau_levenshtein(
     a: &str,
     b: &str,
     k: f64,
     normalized: bool,
+    unit_edits: bool,
 ) -> f64 {
-    let a_chars: Vec<char> = a.chars().collect();
-    let b_chars: Vec<char> = b.chars().collect();
+    with_chars(a, b, |a_chars, b_chars| {
+        descending_weighted_damerau_levenshtein_elems(a_chars, b_chars, k, normalized, unit_edits)
+    })
+}
 
-    let max_len = a_chars.len().max(b_chars.len());
-    let shared_weights = normalized_geometric_descending_weights(max_len, k);
-    let weight_a = shared_weights[0..a_chars.len()].to_vec();
-    let weight_b = shared_weights[0..b_chars.len()].to_vec();
-    match normalized {
-        true => {
-            let result =
-                generic_weighted_damerau_levenshtein(&a_chars, &b_chars, &weight_a, &weight_b);
-            result / max_len as f64
+/// Multipliers of the positional weights by character: an explicit entry in
+/// `by_char` wins, otherwise the weight of the character's class applies.
+/// Punctuation covers every character that is neither alphanumeric,
+/// whitespace nor a control character, i.e. symbols too.
+pub struct CharWeights {
+    pub by_char: HashMap<char, f64>,
+    pub digit: f64,
+    pub alpha: f64,
+    pub punct: f64,
+}
+
+impl Default for CharWeights {
+    fn default() -> Self {
+        CharWeights {
+            by_char: HashMap::new(),
+            digit: 1.0,
+            alpha: 1.0,
+            punct: 1.0,
+        }
+    }
+}
+
+impl CharWeights {
+    pub fn weight(&self, c: char) -> f64 {
+        if let Some(&weight) = self.by_char.get(&c) {
+            weight
+        } else if c.is_numeric() {
+            self.digit
+        } else if c.is_alphabetic() {
+            self.alpha
+        } else if !c.is_whitespace() && !c.is_control() {
+            self.punct
+        } else {
+            1.0
         }
-        false => generic_weighted_damerau_levenshtein(&a_chars, &b_chars, &weight_a, &weight_b),
+    }
+}
+
+/// Weighted Damerau-Levenshtein with positional weights of the given
+/// `profile`, each further multiplied by its character's weight in
+/// `char_weights`, e.g. to make punctuation cheap and digits expensive.
+/// With `WeightDirection::Ascending` the profile runs from the end of both
+/// strings instead, emphasizing suffix differences.
+pub fn profile_weighted_damerau_levenshtein(
+    a: &str,
+    b: &str,
+    profile: &WeightProfile,
+    direction: WeightDirection,
+    char_weights: &CharWeights,
+    normalized: bool,
+    unit_edits: bool,
+) -> f64 {
+    with_buffers(|[a_chars, b_chars]: [&mut Vec<char>; 2]| {
+        a_chars.extend(a.chars());
+        b_chars.extend(b.chars());
+        // Weights anchored at the end of both strings are the descending
+        // weights of the reversed strings, and reversing both sides
+        // preserves distances.
+        if let WeightDirection::Ascending = direction {
+            a_chars.reverse();
+            b_chars.reverse();
+        }
+
+        let shared_weights = profile.normalized_weights(a_chars.len().max(b_chars.len()));
+        with_buffers(|[weight_a, weight_b]: [&mut Vec<f64>; 2]| {
+            let weigh = |chars: &[char], weights: &mut Vec<f64>| {
+                weights.extend(
+                    chars
+                        .iter()
+                        .zip(&shared_weights)
+                        .map(|(c, weight)| weight * char_weights.weight(*c)),
+                )
+            };
+            weigh(a_chars, weight_a);
+            weigh(b_chars, weight_b);
+            explicitly_weighted_damerau_levenshtein(
+                a_chars, b_chars, weight_a, weight_b, normalized, unit_edits,
+            )
+        })
+    })
+}
+
+/// `normalized_descending_weighted_damerau_levenshtein` over arbitrary
+/// element sequences (e.g. the items of two list values).
+pub fn descending_weighted_damerau_levenshtein_elems<Elem>(
+    a_elems: &[Elem],
+    b_elems: &[Elem],
+    k: f64,
+    normalized: bool,
+    unit_edits: bool,
+) -> f64
+where
+    Elem: Eq + Hash + Clone,
+{
+    let max_len = a_elems.len().max(b_elems.len());
+    let shared_weights = cached_geometric_weights(max_len, k);
+    let weight_a = &shared_weights[0..a_elems.len()];
+    let weight_b = &shared_weights[0..b_elems.len()];
+    explicitly_weighted_damerau_levenshtein(
+        a_elems, b_elems, weight_a, weight_b, normalized, unit_edits,
+    )
+}
+
+/// Generic weighted Damerau-Levenshtein with caller-supplied per-element
+/// weights, applying the same `unit_edits` and `normalized` (by the longer
+/// sequence) rescaling as the geometric variants.
+pub fn explicitly_weighted_damerau_levenshtein<Elem>(
+    a_elems: &[Elem],
+    b_elems: &[Elem],
+    weight_a: &[f64],
+    weight_b: &[f64],
+    normalized: bool,
+    unit_edits: bool,
+) -> f64
+where
+    Elem: Eq + Hash + Clone,
+{
+    let max_len = a_elems.len().max(b_elems.len());
+    let mut result = generic_weighted_damerau_levenshtein(a_elems, b_elems, weight_a, weight_b);
+    if unit_edits {
+        result /= mean_consumed_weight(weight_a, weight_b);
+    }
+    match normalized {
+        true => result / max_len as f64,
+        false => result,
     }
 }
 
 /// Calculates the Damerau-Levenshtein distance between two strings on a word-by-word basis.
 ///
-/// This function splits the input strings `a` and `b` into words, based on whitespace.
-/// It then performs a positional comparison, matching the first word of the shorter list
-/// with the first word of the longer list, the second with the second, and so on.
+/// This function splits the input strings `a` and `b` into words with `tokenizer`.
+/// With `WordAlignment::Positional` it matches the first word of the shorter list with
+/// the first word of the longer list, the second with the second, and so on. With
+/// `WordAlignment::Optimal` it instead picks, in order, the words of the longer list
+/// that minimize the summed word distances, so a missing leading word does not shift
+/// every other pair. `WordAlignment::Assignment` drops the order constraint and solves
+/// the optimal assignment, for strings whose word order is meaningless.
 ///
 /// For each pair of words, it computes the `normalized_descending_weighted_damerau_levenshtein`
-/// distance. Finally, it aggregates these distances into a single `f64` value using the
-/// method specified by the `agg` parameter.
+/// distance. Each distance is then multiplied by the weight of its word, a descending
+/// geometric sequence over the words of the shorter string by `word_ratio` normalized to
+/// average `1` (see `normalized_geometric_descending_weights`), so the first words count
+/// most. Finally, it aggregates these distances into a single `f64` value using the
+/// method specified by `agg`; `Mean` thus yields the weighted mean, and `WeightedMean`
+/// additionally weighs each pair by the character length of its longer word, so a
+/// mismatched long word is not diluted by matching initials. The words of the longer string
+/// left unpaired are ignored unless `unmatched_distance` is set, in which case each one
+/// enters the aggregation with that distance and weight `1`.
 ///
 /// # Arguments
 ///
@@ -211,7 +820,11 @@ pub fn normalized_descending_weighted_damerau_levenshtein(
 /// * `b` - The second string.
 /// * `k` - The geometric ratio for weighted Damerau-Levenshtein.
 /// * `normalized` - If true, the distance for each word pair is normalized by word length.
-/// * `agg` - The aggregation method (`Max`, `Mean`, or `Min`) to combine word-level distances.
+/// * `unit_edits` - If true, each word-pair distance is rescaled to unit edits.
+/// * `options` - The aggregation method (`Max`, `Mean`, `Min` or `WeightedMean`)
+///   combining the word-level distances, how the words of the two strings are paired,
+///   the word weight ratio and the distance of unpaired words.
+/// * `tokenizer` - How the strings are split into words.
 ///
 /// # Returns
 ///
@@ -222,13 +835,76 @@ pub fn normalized_descending_weighted_damerau_levenshtein_bywords(
     b: &str,
     k: f64,
     normalized: bool,
-    agg: ByWordsAggregation,
+    unit_edits: bool,
+    options: ByWordsOptions,
+    tokenizer: &Tokenizer,
 ) -> f64 {
-    let a_words: Vec<&str> = a.split_whitespace().collect();
-    let b_words: Vec<&str> = b.split_whitespace().collect();
+    let entries = word_entries(a, b, k, normalized, unit_edits, options, tokenizer);
+    if entries.is_empty() {
+        return 0.0;
+    }
+    let distances = entries.iter().map(|entry| entry.distance * entry.weight);
+
+    match options.agg {
+        ByWordsAggregation::Max => distances.fold(f64::NEG_INFINITY, f64::max),
+        ByWordsAggregation::Mean => distances.sum::<f64>() / entries.len() as f64,
+        ByWordsAggregation::Min => distances.fold(f64::INFINITY, f64::min),
+        ByWordsAggregation::WeightedMean => {
+            let (total, total_weight) =
+                entries
+                    .iter()
+                    .fold((0.0, 0.0), |(total, total_weight), entry| {
+                        let weight = entry.weight * entry.length as f64;
+                        (total + entry.distance * weight, total_weight + weight)
+                    });
+            total / total_weight
+        }
+    }
+}
+
+/// The unweighted word distances `normalized_descending_weighted_damerau_levenshtein_bywords`
+/// aggregates, in the order of the words of the shorter string, followed by those of the
+/// unpaired words of the longer string when `options.unmatched_distance` is set.
+/// `options.agg` and `options.word_ratio` are not used. Empty if either string has no
+/// words.
+pub fn descending_weighted_damerau_levenshtein_word_distances(
+    a: &str,
+    b: &str,
+    k: f64,
+    normalized: bool,
+    unit_edits: bool,
+    options: ByWordsOptions,
+    tokenizer: &Tokenizer,
+) -> Vec<f64> {
+    word_entries(a, b, k, normalized, unit_edits, options, tokenizer)
+        .into_iter()
+        .map(|entry| entry.distance)
+        .collect()
+}
+
+/// One word distance entering the bywords aggregation.
+struct WordEntry {
+    distance: f64,
+    /// Positional word weight.
+    weight: f64,
+    /// Length of the longer word of the pair.
+    length: usize,
+}
+
+fn word_entries(
+    a: &str,
+    b: &str,
+    k: f64,
+    normalized: bool,
+    unit_edits: bool,
+    options: ByWordsOptions,
+    tokenizer: &Tokenizer,
+) -> Vec<WordEntry> {
+    let a_words = tokenizer.tokens(a);
+    let b_words = tokenizer.tokens(b);
 
     if a_words.is_empty() || b_words.is_empty() {
-        return 0.0;
+        return Vec::new();
     }
 
     let (shorter, longer) = if a_words.len() < b_words.len() {
@@ -237,13 +913,239 @@ pub fn normalized_descending_weighted_damerau_levenshtein_bywords(
         (b_words, a_words)
     };
 
-    let distances = (0..shorter.len()).map(|i| {
-        normalized_descending_weighted_damerau_levenshtein(shorter[i], longer[i], k, normalized)
-    });
+    let word_distance = |s: &str, l: &str| {
+        normalized_descending_weighted_damerau_levenshtein(s, l, k, normalized, unit_edits)
+    };
+    let pairs: Vec<(usize, f64)> = match options.alignment {
+        WordAlignment::Positional => (0..shorter.len())
+            .map(|i| (i, word_distance(shorter[i], longer[i])))
+            .collect(),
+        WordAlignment::Optimal => optimal_word_pairs(&shorter, &longer, word_distance),
+        WordAlignment::Assignment => assigned_word_pairs(&shorter, &longer, word_distance),
+    };
+    let word_weights = cached_geometric_weights(shorter.len(), options.word_ratio);
 
-    match agg {
-        ByWordsAggregation::Max => distances.fold(f64::NEG_INFINITY, f64::max),
-        ByWordsAggregation::Mean => distances.sum::<f64>() / shorter.len() as f64,
-        ByWordsAggregation::Min => distances.fold(f64::INFINITY, f64::min),
+    let mut entries: Vec<WordEntry> = pairs
+        .iter()
+        .zip(word_weights.iter())
+        .enumerate()
+        .map(|(i, (&(j, distance), &weight))| WordEntry {
+            distance,
+            weight,
+            length: shorter[i].chars().count().max(longer[j].chars().count()),
+        })
+        .collect();
+    if let Some(unmatched_distance) = options.unmatched_distance {
+        let mut paired = vec![false; longer.len()];
+        for &(j, _) in &pairs {
+            paired[j] = true;
+        }
+        entries.extend(
+            longer
+                .iter()
+                .zip(paired)
+                .filter(|&(_, paired)| !paired)
+                .map(|(word, _)| WordEntry {
+                    distance: unmatched_distance,
+                    weight: 1.0,
+                    length: word.chars().count(),
+                }),
+        );
+    }
+    entries
+}
+
+/// The partners in `longer` and word distances of the in-order pairing of
+/// every word of `shorter` with a distinct word of `longer` that minimizes
+/// their sum. Word `i` can
+/// only pair with words `i..=i + longer.len() - shorter.len()`, so only that
+/// band of distances is computed.
+fn optimal_word_pairs(
+    shorter: &[&str],
+    longer: &[&str],
+    word_distance: impl Fn(&str, &str) -> f64,
+) -> Vec<(usize, f64)> {
+    let (n, m) = (shorter.len(), longer.len());
+    let slack = m - n;
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+
+    // costs[at(i, j)]: the least total distance pairing the first `i` words
+    // of `shorter` within the first `j` words of `longer`.
+    let mut costs = vec![f64::INFINITY; (n + 1) * (m + 1)];
+    let mut pair_distances = vec![f64::NAN; (n + 1) * (m + 1)];
+    for j in 0..=slack {
+        costs[at(0, j)] = 0.0;
+    }
+    for i in 1..=n {
+        for j in i..=i + slack {
+            let distance = word_distance(shorter[i - 1], longer[j - 1]);
+            pair_distances[at(i, j)] = distance;
+            let skip = if j > i {
+                costs[at(i, j - 1)]
+            } else {
+                f64::INFINITY
+            };
+            costs[at(i, j)] = skip.min(costs[at(i - 1, j - 1)] + distance);
+        }
+    }
+
+    let mut pairs = Vec::with_capacity(n);
+    let (mut i, mut j) = (n, m);
+    while i > 0 {
+        if j > i && costs[at(i, j)] == costs[at(i, j - 1)] {
+            j -= 1;
+        } else {
+            pairs.push((j - 1, pair_distances[at(i, j)]));
+            i -= 1;
+            j -= 1;
+        }
+    }
+    pairs.reverse();
+    pairs
+}
+
+/// The partners in `longer` and word distances of the pairing of every word
+/// of `shorter` with a distinct word of `longer`, in any order, that
+/// minimizes their sum, in the order of `shorter`. Solved with the Hungarian
+/// algorithm (shortest augmenting paths with potentials) in `O(n^2 m)`.
+pub fn assigned_word_pairs(
+    shorter: &[&str],
+    longer: &[&str],
+    word_distance: impl Fn(&str, &str) -> f64,
+) -> Vec<(usize, f64)> {
+    let (n, m) = (shorter.len(), longer.len());
+    let pair_distances: Vec<f64> = shorter
+        .iter()
+        .flat_map(|s| longer.iter().map(|l| word_distance(s, l)))
+        .collect();
+    let cost = |i: usize, j: usize| pair_distances[(i - 1) * m + (j - 1)];
+
+    // Rows are the words of `shorter` and columns those of `longer`, both
+    // 1-based; column 0 is a virtual column holding the row being added.
+    let mut row_potentials = vec![0.0; n + 1];
+    let mut column_potentials = vec![0.0; m + 1];
+    let mut column_rows = vec![0_usize; m + 1];
+    let mut previous_columns = vec![0_usize; m + 1];
+    for row in 1..=n {
+        column_rows[0] = row;
+        let mut column = 0;
+        let mut slacks = vec![f64::INFINITY; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[column] = true;
+            let i = column_rows[column];
+            let mut delta = f64::INFINITY;
+            let mut next_column = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let slack = cost(i, j) - row_potentials[i] - column_potentials[j];
+                if slack < slacks[j] {
+                    slacks[j] = slack;
+                    previous_columns[j] = column;
+                }
+                if slacks[j] < delta {
+                    delta = slacks[j];
+                    next_column = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    row_potentials[column_rows[j]] += delta;
+                    column_potentials[j] -= delta;
+                } else {
+                    slacks[j] -= delta;
+                }
+            }
+            column = next_column;
+            if column_rows[column] == 0 {
+                break;
+            }
+        }
+        while column != 0 {
+            let previous = previous_columns[column];
+            column_rows[column] = column_rows[previous];
+            column = previous;
+        }
+    }
+
+    let mut pairs = vec![(0, 0.0); n];
+    for (j, &i) in column_rows.iter().enumerate().skip(1) {
+        if i != 0 {
+            pairs[i - 1] = (j - 1, cost(i, j));
+        }
+    }
+    pairs
+}
+
+/// Similarity in `[0, 1]` of two token lists: every token of the list with
+/// fewer tokens is paired with a distinct token of the other, in any order,
+/// maximizing the summed `token_similarity` (see `assigned_word_pairs`).
+/// The sum is divided by the number of pairs plus `unmatched_weight` per
+/// unpaired token. `0.0` when either list is empty.
+pub fn assigned_token_similarity(
+    a_tokens: &[String],
+    b_tokens: &[String],
+    token_similarity: impl Fn(&str, &str) -> f64,
+    unmatched_weight: f64,
+) -> f64 {
+    if a_tokens.is_empty() || b_tokens.is_empty() {
+        return 0.0;
+    }
+    let a_words: Vec<&str> = a_tokens.iter().map(String::as_str).collect();
+    let b_words: Vec<&str> = b_tokens.iter().map(String::as_str).collect();
+    let (shorter, longer) = if a_words.len() <= b_words.len() {
+        (a_words, b_words)
+    } else {
+        (b_words, a_words)
+    };
+
+    let pairs = assigned_word_pairs(&shorter, &longer, |s, l| 1.0 - token_similarity(s, l));
+    let matched: f64 = pairs.iter().map(|&(_, distance)| 1.0 - distance).sum();
+    let unmatched = (longer.len() - shorter.len()) as f64 * unmatched_weight;
+    matched / (shorter.len() as f64 + unmatched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_damerau_levenshtein_is_none_exactly_above_the_bound() {
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = |n: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % n
+        };
+        let mut random_string = |max_len: u64| -> String {
+            let len = next(max_len + 1);
+            (0..len).map(|_| (b'a' + next(3) as u8) as char).collect()
+        };
+        let mut pairs: Vec<(String, String)> = vec![
+            ("ca".into(), "abc".into()),
+            ("abxcd".into(), "bacyd".into()),
+            ("ab".repeat(40), "ba".repeat(40)),
+        ];
+        for _ in 0..150 {
+            pairs.push((random_string(12), random_string(12)));
+        }
+        for _ in 0..20 {
+            pairs.push((random_string(90), random_string(90)));
+        }
+        for (a, b) in &pairs {
+            let a_chars: Vec<char> = a.chars().collect();
+            let b_chars: Vec<char> = b.chars().collect();
+            let distance = strsim::damerau_levenshtein(a, b);
+            for max_distance in 0..=distance + 2 {
+                assert_eq!(
+                    bounded_damerau_levenshtein(&a_chars, &b_chars, max_distance),
+                    (distance <= max_distance).then_some(distance),
+                    "{a:?} {b:?} {max_distance}"
+                );
+            }
+        }
     }
 }
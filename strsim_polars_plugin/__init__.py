@@ -1,6 +1,6 @@
 from __future__ import annotations
 
-from strsim_polars_plugin import distance
+from strsim_polars_plugin import config, distance, drift, features, join, phonetic
 
 
-__all__ = ["distance"]
+__all__ = ["config", "distance", "drift", "features", "join", "phonetic"]
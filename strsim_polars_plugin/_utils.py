@@ -1,3 +1,50 @@
+from __future__ import annotations
+
 from pathlib import Path
+from typing import Any
+
+LIB = Path(__file__).parent
+
+PREPROCESS_OPTIONS = frozenset(
+    {
+        "arabic_romanization",
+        "cache_preprocessed",
+        "case_insensitive",
+        "exact_tier",
+        "normalize_whitespace",
+        "sort_chars",
+        "strip_accents",
+        "strip_prefixes",
+        "strip_punctuation",
+        "strip_suffixes",
+        "transliterate",
+        "unicode_normalize",
+    }
+)
+
+EXECUTION_OPTIONS = frozenset(
+    {
+        "dedup_pairs",
+        "memoize",
+        "null_strategy",
+        "per_row_timeout_ms",
+        "timeout_value",
+    }
+)
+
+
+SCORE_OPTIONS = frozenset({"float32"})
+
 
-LIB = Path(__file__).parent
\ No newline at end of file
+def common_kwargs(
+    options: dict[str, Any], execution: bool = True, preprocess: bool = True, scores: bool = False
+) -> dict[str, Any]:
+    allowed = (
+        (PREPROCESS_OPTIONS if preprocess else frozenset())
+        | (EXECUTION_OPTIONS if execution else frozenset())
+        | (SCORE_OPTIONS if scores else frozenset())
+    )
+    unknown = set(options) - allowed
+    if unknown:
+        raise TypeError(f"Unknown option(s): {', '.join(sorted(unknown))}.")
+    return options
@@ -0,0 +1,11 @@
+from __future__ import annotations
+
+import os
+
+NUM_THREADS_ENV = "STRSIM_POLARS_PLUGIN_NUM_THREADS"
+
+
+def set_num_threads(num_threads: int) -> None:
+    if num_threads < 1:
+        raise ValueError("num_threads must be a positive integer.")
+    os.environ[NUM_THREADS_ENV] = str(num_threads)
@@ -1,61 +1,245 @@
 from __future__ import annotations
 
-from typing import TYPE_CHECKING, Literal
+from typing import TYPE_CHECKING, Any, Literal
 
 import polars as pl
 from polars.plugins import register_plugin_function
 
-from strsim_polars_plugin._utils import LIB
+from strsim_polars_plugin._utils import LIB, common_kwargs
 
 if TYPE_CHECKING:
     from strsim_polars_plugin._typing import IntoExprColumn
 
 
-def damerau_levenshtein(expr: IntoExprColumn, other: IntoExprColumn) -> pl.Expr:
+def damerau_levenshtein(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    max_distance: int | None = None,
+    null_above_max_distance: bool = False,
+    unit: str = "char",
+    **options: Any,
+) -> pl.Expr:
     return register_plugin_function(
         plugin_path=LIB,
         args=[expr, other],
         function_name="damerau_levenshtein",
         is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "max_distance": max_distance,
+            "null_above_max_distance": null_above_max_distance,
+            "unit": unit,
+        },
+    )
+
+
+def token_damerau_levenshtein(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    separators: str | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="token_damerau_levenshtein",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "separators": separators,
+        },
+    )
+
+
+def damerau_levenshtein_full(expr: IntoExprColumn, other: IntoExprColumn, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="damerau_levenshtein_full",
+        is_elementwise=True,
+        kwargs=common_kwargs(options),
+    )
+
+
+def similarity_profile(expr: IntoExprColumn, other: IntoExprColumn, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="similarity_profile",
+        is_elementwise=True,
+        kwargs=common_kwargs(options),
+    )
+
+
+def damerau_levenshtein_ops(expr: IntoExprColumn, other: IntoExprColumn, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="damerau_levenshtein_ops",
+        is_elementwise=True,
+        kwargs=common_kwargs(options, preprocess=False),
+    )
+
+
+def damerau_levenshtein_alignment(expr: IntoExprColumn, other: IntoExprColumn, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="damerau_levenshtein_alignment",
+        is_elementwise=True,
+        kwargs=common_kwargs(options, preprocess=False),
+    )
+
+
+def damerau_levenshtein_mismatch_positions(expr: IntoExprColumn, other: IntoExprColumn, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="damerau_levenshtein_mismatch_positions",
+        is_elementwise=True,
+        kwargs=common_kwargs(options, preprocess=False),
     )
 
 
-def normalized_damerau_levenshtein(expr: IntoExprColumn, other: IntoExprColumn) -> pl.Expr:
+def normalized_damerau_levenshtein(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    score_cutoff: float | None = None,
+    unit: str = "char",
+    **options: Any,
+) -> pl.Expr:
     return register_plugin_function(
         plugin_path=LIB,
         args=[expr, other],
         function_name="normalized_damerau_levenshtein",
         is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "score_cutoff": score_cutoff,
+            "unit": unit,
+        },
     )
 
 
-def partial_damerau_levenshtein(expr: IntoExprColumn, other: IntoExprColumn) -> pl.Expr:
+def partial_damerau_levenshtein(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    max_distance: int | None = None,
+    null_above_max_distance: bool = False,
+    window: int | None = None,
+    stride: int = 1,
+    **options: Any,
+) -> pl.Expr:
     return register_plugin_function(
         plugin_path=LIB,
         args=[expr, other],
         function_name="partial_damerau_levenshtein",
         is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "max_distance": max_distance,
+            "null_above_max_distance": null_above_max_distance,
+            "window": window,
+            "stride": stride,
+        },
     )
 
 
-def partial_normalized_damerau_levenshtein(expr: IntoExprColumn, other: IntoExprColumn) -> pl.Expr:
+def partial_damerau_levenshtein_locate(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    window: int | None = None,
+    stride: int = 1,
+    **options: Any,
+) -> pl.Expr:
     return register_plugin_function(
         plugin_path=LIB,
         args=[expr, other],
-        function_name="partial_normalized_damerau_levenshtein",
+        function_name="partial_damerau_levenshtein_locate",
         is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "window": window,
+            "stride": stride,
+        },
     )
 
 
-def geometric_weighted_damerau_levenshtein(expr: IntoExprColumn, other: IntoExprColumn, weighted_geometric_ratio: float = 1.0, normalized: bool = False) -> pl.Expr:
+def partial_normalized_damerau_levenshtein(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    score_cutoff: float | None = None,
+    window: int | None = None,
+    stride: int = 1,
+    **options: Any,
+) -> pl.Expr:
     return register_plugin_function(
         plugin_path=LIB,
         args=[expr, other],
+        function_name="partial_normalized_damerau_levenshtein",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "score_cutoff": score_cutoff,
+            "window": window,
+            "stride": stride,
+        },
+    )
+
+
+def geometric_weighted_damerau_levenshtein(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    weighted_geometric_ratio: float = 1.0,
+    normalized: bool = False,
+    unit_edits: bool = False,
+    profile: Literal["geometric", "linear", "exponential", "gaussian", "step"] = "geometric",
+    plateau_len: int = 0,
+    exponential_decay: float = 1.0,
+    gaussian_center: float = 0.5,
+    gaussian_width: float = 0.25,
+    step_breakpoints: list[int] | None = None,
+    step_weights: list[float] | None = None,
+    direction: Literal["descending", "ascending"] = "descending",
+    char_weights: dict[str, float] | None = None,
+    digit_weight: float = 1.0,
+    alpha_weight: float = 1.0,
+    punct_weight: float = 1.0,
+    cascade: dict[str, Any] | None = None,
+    weights: IntoExprColumn | None = None,
+    other_weights: IntoExprColumn | None = None,
+    **options: Any,
+) -> pl.Expr:
+    if profile not in ("geometric", "linear", "exponential", "gaussian", "step"):
+        raise ValueError("profile must be one of 'geometric', 'linear', 'exponential', 'gaussian', 'step'.")
+    if direction not in ("descending", "ascending"):
+        raise ValueError("direction must be one of 'descending', 'ascending'.")
+    if (weights is None) != (other_weights is None):
+        raise ValueError("weights and other_weights must be given together.")
+    args = [expr, other] if weights is None else [expr, other, weights, other_weights]
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=args,
         function_name="geometric_weighted_damerau_levenshtein",
         is_elementwise=True,
         kwargs={
+            **common_kwargs(options),
             "weighted_geometric_ratio": weighted_geometric_ratio,
             "normalized": normalized,
+            "unit_edits": unit_edits,
+            "profile": profile,
+            "plateau_len": plateau_len,
+            "exponential_decay": exponential_decay,
+            "gaussian_center": gaussian_center,
+            "gaussian_width": gaussian_width,
+            "step_breakpoints": step_breakpoints or [],
+            "step_weights": step_weights or [],
+            "direction": direction,
+            "char_weights": char_weights or {},
+            "digit_weight": digit_weight,
+            "alpha_weight": alpha_weight,
+            "punct_weight": punct_weight,
+            "cascade": cascade,
         },
     )
 
@@ -64,20 +248,660 @@ def geometric_weighted_damerau_levenshtein_bywords(expr: IntoExprColumn,
     other: IntoExprColumn,
     weighted_geometric_ratio: float = 1.0,
     normalized: bool = False,
-    agg: Literal["max", "min", "mean"] = "mean",
+    agg: Literal["max", "min", "mean", "weighted_mean"] = "mean",
+    unit_edits: bool = False,
+    cascade: dict[str, Any] | None = None,
+    word_alignment: Literal["positional", "optimal", "assignment"] = "positional",
+    word_weighted_geometric_ratio: float = 1.0,
+    unmatched_word_distance: float | None = None,
+    separators: str | None = None,
+    separator_pattern: str | None = None,
+    **options: Any,
 ) -> pl.Expr:
-    if agg not in ("max", "min", "mean"):
-        raise ValueError("agg must be one of 'max', 'min', 'mean'.")
+    if agg not in ("max", "min", "mean", "weighted_mean"):
+        raise ValueError("agg must be one of 'max', 'min', 'mean', 'weighted_mean'.")
+    if word_alignment not in ("positional", "optimal", "assignment"):
+        raise ValueError("word_alignment must be one of 'positional', 'optimal', 'assignment'.")
     return register_plugin_function(
         plugin_path=LIB,
         args=[expr, other],
         function_name="geometric_weighted_damerau_levenshtein_bywords",
         is_elementwise=True,
         kwargs={
+            **common_kwargs(options),
+            "weighted_geometric_ratio": weighted_geometric_ratio,
+            "normalized": normalized,
+            "unit_edits": unit_edits,
+            "agg": agg,
+            "cascade": cascade,
+            "word_alignment": word_alignment,
+            "word_weighted_geometric_ratio": word_weighted_geometric_ratio,
+            "unmatched_word_distance": unmatched_word_distance,
+            "separators": separators,
+            "separator_pattern": separator_pattern,
+        },
+    )
+
+
+def geometric_weighted_damerau_levenshtein_word_distances(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    weighted_geometric_ratio: float = 1.0,
+    normalized: bool = False,
+    unit_edits: bool = False,
+    word_alignment: Literal["positional", "optimal", "assignment"] = "positional",
+    unmatched_word_distance: float | None = None,
+    separators: str | None = None,
+    separator_pattern: str | None = None,
+    **options: Any,
+) -> pl.Expr:
+    if word_alignment not in ("positional", "optimal", "assignment"):
+        raise ValueError("word_alignment must be one of 'positional', 'optimal', 'assignment'.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="geometric_weighted_damerau_levenshtein_word_distances",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
             "weighted_geometric_ratio": weighted_geometric_ratio,
             "normalized": normalized,
+            "unit_edits": unit_edits,
+            "word_alignment": word_alignment,
+            "unmatched_word_distance": unmatched_word_distance,
+            "separators": separators,
+            "separator_pattern": separator_pattern,
+        },
+    )
+
+
+def list_damerau_levenshtein(expr: IntoExprColumn, other: IntoExprColumn, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="list_damerau_levenshtein",
+        is_elementwise=True,
+        kwargs=common_kwargs(options, preprocess=False),
+    )
+
+
+def list_weighted_damerau_levenshtein(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    weights: IntoExprColumn | None = None,
+    other_weights: IntoExprColumn | None = None,
+    weighted_geometric_ratio: float = 1.0,
+    normalized: bool = False,
+    unit_edits: bool = False,
+    **options: Any,
+) -> pl.Expr:
+    if (weights is None) != (other_weights is None):
+        raise ValueError("weights and other_weights must be given together.")
+    args = [expr, other] if weights is None else [expr, other, weights, other_weights]
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=args,
+        function_name="list_weighted_damerau_levenshtein",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, preprocess=False),
+            "weighted_geometric_ratio": weighted_geometric_ratio,
+            "normalized": normalized,
+            "unit_edits": unit_edits,
+        },
+    )
+
+
+def warm_start(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    previous: IntoExprColumn,
+    changed: IntoExprColumn,
+    metric: str = "normalized_damerau_levenshtein",
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other, previous, changed],
+        function_name="warm_start",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "metric": metric,
+        },
+    )
+
+
+def partial_ratio(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    normalized: bool = False,
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="partial_ratio",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "normalized": normalized,
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def w_ratio(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    normalized: bool = False,
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="w_ratio",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "normalized": normalized,
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def bounded_mismatch(expr: IntoExprColumn, other: IntoExprColumn, max_mismatches: int = 1, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="bounded_mismatch",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "max_mismatches": max_mismatches,
+        },
+    )
+
+
+def within_bounded_mismatch(expr: IntoExprColumn, other: IntoExprColumn, max_mismatches: int = 1, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="within_bounded_mismatch",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "max_mismatches": max_mismatches,
+        },
+    )
+
+
+def subsequence_containment(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    max_gap: int | None = None,
+    both_directions: bool = False,
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="subsequence_containment",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "max_gap": max_gap,
+            "both_directions": both_directions,
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def acronym_similarity(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="acronym_similarity",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def abbreviation_similarity(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    both_directions: bool = False,
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="abbreviation_similarity",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "both_directions": both_directions,
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def person_name_similarity(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    initial_score: float = 0.9,
+    phonetic_score: float = 0.8,
+    unmatched_token_weight: float = 0.5,
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="person_name_similarity",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "initial_score": initial_score,
+            "phonetic_score": phonetic_score,
+            "unmatched_token_weight": unmatched_token_weight,
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def company_name_similarity(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    legal_suffixes: list[str] | None = None,
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="company_name_similarity",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "legal_suffixes": legal_suffixes or [],
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def address_similarity(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    unmatched_token_weight: float = 0.5,
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="address_similarity",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "unmatched_token_weight": unmatched_token_weight,
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def affine_gap_distance(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    gap_open: float = 1.0,
+    gap_extend: float = 0.5,
+    mismatch: float = 1.0,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="affine_gap_distance",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "gap_open": gap_open,
+            "gap_extend": gap_extend,
+            "mismatch": mismatch,
+        },
+    )
+
+
+def custom_cost_damerau_levenshtein(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    ins_cost: float = 1.0,
+    del_cost: float = 1.0,
+    sub_cost: float = 1.0,
+    trans_cost: float = 1.0,
+    cost_model: str | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="custom_cost_damerau_levenshtein",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "ins_cost": ins_cost,
+            "del_cost": del_cost,
+            "sub_cost": sub_cost,
+            "trans_cost": trans_cost,
+            "cost_model": cost_model,
+        },
+    )
+
+
+def is_similar(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    threshold: float,
+    metric: str = "normalized_damerau_levenshtein",
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="is_similar",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "threshold": threshold,
+            "metric": metric,
+        },
+    )
+
+
+def best_match(
+    expr: IntoExprColumn,
+    candidates: list[str],
+    metric: str = "normalized_damerau_levenshtein",
+    k: int | None = None,
+    **options: Any,
+) -> pl.Expr:
+    if not candidates:
+        raise ValueError("candidates must not be empty.")
+    if k is not None and k < 1:
+        raise ValueError("k must be positive.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr],
+        function_name="best_match",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "candidates": list(candidates),
+            "metric": metric,
+            "k": k,
+        },
+    )
+
+
+def nearest_terms(
+    expr: IntoExprColumn,
+    vocabulary: list[str] | IntoExprColumn,
+    max_distance: int = 2,
+    k: int | None = None,
+    **options: Any,
+) -> pl.Expr:
+    if k is not None and k < 1:
+        raise ValueError("k must be positive.")
+    from_list = isinstance(vocabulary, list)
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr] if from_list else [expr, vocabulary],
+        function_name="nearest_terms",
+        is_elementwise=from_list,
+        kwargs={
+            **common_kwargs(options),
+            "vocabulary": list(vocabulary) if from_list else None,
+            "max_distance": max_distance,
+            "k": k,
+        },
+    )
+
+
+def dictionary_search(
+    expr: IntoExprColumn,
+    dictionary: list[str] | IntoExprColumn,
+    max_distance: int = 1,
+    **options: Any,
+) -> pl.Expr:
+    from_list = isinstance(dictionary, list)
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr] if from_list else [expr, dictionary],
+        function_name="dictionary_search",
+        is_elementwise=from_list,
+        kwargs={
+            **common_kwargs(options),
+            "dictionary": list(dictionary) if from_list else None,
+            "max_distance": max_distance,
+        },
+    )
+
+
+def suggest_correction(
+    expr: IntoExprColumn,
+    dictionary: list[str] | IntoExprColumn,
+    max_distance: int = 2,
+    frequencies: dict[str, float] | None = None,
+    **options: Any,
+) -> pl.Expr:
+    from_list = isinstance(dictionary, list)
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr] if from_list else [expr, dictionary],
+        function_name="suggest_correction",
+        is_elementwise=from_list,
+        kwargs={
+            **common_kwargs(options),
+            "dictionary": list(dictionary) if from_list else None,
+            "max_distance": max_distance,
+            "frequencies": dict(frequencies or {}),
+        },
+    )
+
+
+def pairwise_matrix(expr: IntoExprColumn, metric: str = "normalized_damerau_levenshtein", **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr],
+        function_name="pairwise_matrix",
+        is_elementwise=False,
+        kwargs={
+            **common_kwargs(options),
+            "metric": metric,
+        },
+    )
+
+
+def zipped_list_score(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    metric: str = "normalized_damerau_levenshtein",
+    agg: str | None = None,
+    **options: Any,
+) -> pl.Expr:
+    if agg not in (None, "max", "min", "mean"):
+        raise ValueError("agg must be one of 'max', 'min', 'mean' or None.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="zipped_list_score",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "metric": metric,
+            "agg": agg,
+        },
+    )
+
+
+def candidate_list_score(
+    expr: IntoExprColumn,
+    candidates: IntoExprColumn,
+    metric: str = "normalized_damerau_levenshtein",
+    agg: str | None = None,
+    **options: Any,
+) -> pl.Expr:
+    if agg not in (None, "max", "min", "mean"):
+        raise ValueError("agg must be one of 'max', 'min', 'mean' or None.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, candidates],
+        function_name="candidate_list_score",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "metric": metric,
             "agg": agg,
         },
     )
 
 
+def composite_similarity(
+    pairs: list[tuple[IntoExprColumn, IntoExprColumn]],
+    weights: list[float] | None = None,
+    metric: str | list[str] = "normalized_damerau_levenshtein",
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    if not pairs:
+        raise ValueError("pairs must not be empty.")
+    if weights is None:
+        weights = [1.0] * len(pairs)
+    metrics = [metric] * len(pairs) if isinstance(metric, str) else list(metric)
+    if len(weights) != len(pairs) or len(metrics) != len(pairs):
+        raise ValueError("weights and metric must have one entry per pair.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[side for pair in pairs for side in pair],
+        function_name="composite_similarity",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "metrics": metrics,
+            "weights": [float(w) for w in weights],
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def ensemble_similarity(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    weights: dict[str, float],
+    bias: float = 0.0,
+    **options: Any,
+) -> pl.Expr:
+    if not weights:
+        raise ValueError("weights must not be empty.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="ensemble_similarity",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "metrics": list(weights),
+            "weights": [float(w) for w in weights.values()],
+            "bias": float(bias),
+        },
+    )
+
+
+def score_histogram(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    metric: str = "normalized_damerau_levenshtein",
+    bins: int = 10,
+    lower: float = 0.0,
+    upper: float = 1.0,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="score_histogram",
+        is_elementwise=False,
+        changes_length=True,
+        kwargs={
+            **common_kwargs(options, execution=False),
+            "metric": metric,
+            "bins": bins,
+            "lower": lower,
+            "upper": upper,
+        },
+    )
+
+
+def common_prefix_len(expr: IntoExprColumn, other: IntoExprColumn, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="common_prefix_len",
+        is_elementwise=True,
+        kwargs=common_kwargs(options),
+    )
+
+
+def common_suffix_len(expr: IntoExprColumn, other: IntoExprColumn, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="common_suffix_len",
+        is_elementwise=True,
+        kwargs=common_kwargs(options),
+    )
+
+
+def qgram_distance(expr: IntoExprColumn, other: IntoExprColumn, q: int = 2, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="qgram_distance",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "q": q,
+        },
+    )
+
+
+def soft_tfidf(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    threshold: float = 0.9,
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="soft_tfidf",
+        is_elementwise=False,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "threshold": threshold,
+            "score_cutoff": score_cutoff,
+        },
+    )
@@ -0,0 +1,68 @@
+from __future__ import annotations
+
+from typing import Any
+
+import polars as pl
+
+from strsim_polars_plugin import distance
+
+DRIFT_METRICS = frozenset(
+    {
+        "damerau_levenshtein",
+        "normalized_damerau_levenshtein",
+        "partial_damerau_levenshtein",
+        "partial_normalized_damerau_levenshtein",
+        "geometric_weighted_damerau_levenshtein",
+        "geometric_weighted_damerau_levenshtein_bywords",
+        "partial_ratio",
+        "w_ratio",
+        "bounded_mismatch",
+        "affine_gap_distance",
+        "acronym_similarity",
+    }
+)
+
+
+def drift_report(
+    old: pl.DataFrame | pl.LazyFrame,
+    new: pl.DataFrame | pl.LazyFrame,
+    on: str | list[str],
+    column: str,
+    metric: str = "normalized_damerau_levenshtein",
+    **options: Any,
+) -> tuple[pl.DataFrame, pl.DataFrame]:
+    if metric not in DRIFT_METRICS:
+        raise ValueError(f"metric must be one of {', '.join(sorted(DRIFT_METRICS))}.")
+    scorer = getattr(distance, metric)
+    old_value, new_value = f"{column}_old", f"{column}_new"
+
+    rows = (
+        old.lazy()
+        .select(pl.col(on), pl.col(column).alias(old_value), pl.lit(True).alias("_in_old"))
+        .join(
+            new.lazy().select(pl.col(on), pl.col(column).alias(new_value), pl.lit(True).alias("_in_new")),
+            on=on,
+            how="full",
+            coalesce=True,
+        )
+        .with_columns(
+            pl.col("_in_old").fill_null(False),
+            pl.col("_in_new").fill_null(False),
+            scorer(pl.col(old_value), pl.col(new_value), **options).alias("score"),
+        )
+        .collect()
+    )
+
+    both = pl.col("_in_old") & pl.col("_in_new")
+    stats = rows.select(
+        pl.len().alias("rows"),
+        (~pl.col("_in_old")).sum().alias("added"),
+        (~pl.col("_in_new")).sum().alias("removed"),
+        (both & pl.col(old_value).eq_missing(pl.col(new_value))).sum().alias("unchanged"),
+        (both & pl.col(old_value).ne_missing(pl.col(new_value))).sum().alias("changed"),
+        pl.col("score").mean().alias("mean_score"),
+        pl.col("score").median().alias("median_score"),
+        pl.col("score").min().alias("min_score"),
+        pl.col("score").max().alias("max_score"),
+    )
+    return rows.drop("_in_old", "_in_new"), stats
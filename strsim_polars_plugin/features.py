@@ -0,0 +1,58 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any
+
+import polars as pl
+from polars.plugins import register_plugin_function
+
+from strsim_polars_plugin._utils import LIB, common_kwargs
+
+if TYPE_CHECKING:
+    from strsim_polars_plugin._typing import IntoExprColumn
+
+
+def ngrams(expr: IntoExprColumn, n: int = 2, pad: str | None = None, **options: Any) -> pl.Expr:
+    if pad is not None and len(pad) != 1:
+        raise ValueError("pad must be a single character.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr],
+        function_name="ngrams",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "n": n,
+            "pad": pad,
+        },
+    )
+
+
+def minhash(
+    expr: IntoExprColumn,
+    num_hashes: int = 128,
+    shingle_size: int = 3,
+    seed: int = 0,
+    **options: Any,
+) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr],
+        function_name="minhash",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "num_hashes": num_hashes,
+            "shingle_size": shingle_size,
+            "seed": seed,
+        },
+    )
+
+
+def minhash_similarity(expr: IntoExprColumn, other: IntoExprColumn, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="minhash_similarity",
+        is_elementwise=True,
+        kwargs=common_kwargs(options, preprocess=False),
+    )
@@ -0,0 +1,112 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any, Literal
+
+import polars as pl
+from polars.plugins import register_plugin_function
+
+from strsim_polars_plugin._utils import LIB, common_kwargs
+
+if TYPE_CHECKING:
+    from strsim_polars_plugin._typing import IntoExprColumn
+
+
+def fuzzy_join_indices(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    threshold: float,
+    metric: str = "normalized_damerau_levenshtein",
+    blocking: Literal["none", "prefix", "qgram", "minhash"] = "qgram",
+    block_prefix_len: int = 3,
+    block_q: int = 3,
+    lsh_bands: int = 32,
+    lsh_rows: int = 4,
+    **options: Any,
+) -> pl.Expr:
+    if blocking not in ("none", "prefix", "qgram", "minhash"):
+        raise ValueError("blocking must be one of 'none', 'prefix', 'qgram', 'minhash'.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="fuzzy_join_indices",
+        is_elementwise=False,
+        changes_length=True,
+        kwargs={
+            **common_kwargs(options),
+            "threshold": threshold,
+            "metric": metric,
+            "blocking": blocking,
+            "block_prefix_len": block_prefix_len,
+            "block_q": block_q,
+            "lsh_bands": lsh_bands,
+            "lsh_rows": lsh_rows,
+        },
+    )
+
+
+def fuzzy_join(
+    left: pl.DataFrame,
+    right: pl.DataFrame,
+    left_on: str,
+    right_on: str,
+    threshold: float,
+    metric: str = "normalized_damerau_levenshtein",
+    blocking: Literal["none", "prefix", "qgram", "minhash"] = "qgram",
+    block_prefix_len: int = 3,
+    block_q: int = 3,
+    lsh_bands: int = 32,
+    lsh_rows: int = 4,
+    suffix: str = "_right",
+    **options: Any,
+) -> pl.DataFrame:
+    matches = left.select(
+        fuzzy_join_indices(
+            pl.col(left_on),
+            pl.lit(right.get_column(right_on)),
+            threshold=threshold,
+            metric=metric,
+            blocking=blocking,
+            block_prefix_len=block_prefix_len,
+            block_q=block_q,
+            lsh_bands=lsh_bands,
+            lsh_rows=lsh_rows,
+            **options,
+        ).alias("_match")
+    ).unnest("_match")
+    return (
+        matches.join(left.with_row_index("left_index"), on="left_index")
+        .join(right.with_row_index("right_index"), on="right_index", suffix=suffix)
+        .sort("left_index", "right_index")
+        .drop("left_index", "right_index")
+    )
+
+
+def similarity_cluster(
+    expr: IntoExprColumn,
+    threshold: float,
+    metric: str = "normalized_damerau_levenshtein",
+    blocking: Literal["none", "prefix", "qgram", "minhash"] = "qgram",
+    block_prefix_len: int = 3,
+    block_q: int = 3,
+    lsh_bands: int = 32,
+    lsh_rows: int = 4,
+    **options: Any,
+) -> pl.Expr:
+    if blocking not in ("none", "prefix", "qgram", "minhash"):
+        raise ValueError("blocking must be one of 'none', 'prefix', 'qgram', 'minhash'.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr],
+        function_name="similarity_cluster",
+        is_elementwise=False,
+        kwargs={
+            **common_kwargs(options),
+            "threshold": threshold,
+            "metric": metric,
+            "blocking": blocking,
+            "block_prefix_len": block_prefix_len,
+            "block_q": block_q,
+            "lsh_bands": lsh_bands,
+            "lsh_rows": lsh_rows,
+        },
+    )
@@ -0,0 +1,83 @@
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any, Literal
+
+import polars as pl
+from polars.plugins import register_plugin_function
+
+from strsim_polars_plugin._utils import LIB, common_kwargs
+
+if TYPE_CHECKING:
+    from strsim_polars_plugin._typing import IntoExprColumn
+
+
+def soundex(expr: IntoExprColumn) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr],
+        function_name="soundex",
+        is_elementwise=True,
+    )
+
+
+def double_metaphone(expr: IntoExprColumn) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr],
+        function_name="double_metaphone",
+        is_elementwise=True,
+    )
+
+
+def nysiis(expr: IntoExprColumn, max_length: int | None = 6) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr],
+        function_name="nysiis",
+        is_elementwise=True,
+        kwargs={"max_length": max_length},
+    )
+
+
+def cologne_phonetics(expr: IntoExprColumn) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr],
+        function_name="cologne_phonetics",
+        is_elementwise=True,
+    )
+
+
+def phonetic_similarity(
+    expr: IntoExprColumn,
+    other: IntoExprColumn,
+    algorithm: Literal["soundex", "metaphone", "nysiis", "cologne_phonetics"] = "soundex",
+    score_cutoff: float | None = None,
+    **options: Any,
+) -> pl.Expr:
+    if algorithm not in ("soundex", "metaphone", "nysiis", "cologne_phonetics"):
+        raise ValueError("algorithm must be one of 'soundex', 'metaphone', 'nysiis', 'cologne_phonetics'.")
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="phonetic_similarity",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options, scores=True),
+            "algorithm": algorithm,
+            "score_cutoff": score_cutoff,
+        },
+    )
+
+
+def mra_comparison(expr: IntoExprColumn, other: IntoExprColumn, score: bool = False, **options: Any) -> pl.Expr:
+    return register_plugin_function(
+        plugin_path=LIB,
+        args=[expr, other],
+        function_name="mra_comparison",
+        is_elementwise=True,
+        kwargs={
+            **common_kwargs(options),
+            "score": score,
+        },
+    )